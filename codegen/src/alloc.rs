@@ -0,0 +1,376 @@
+//! Stack allocation of locals.
+//!
+//! Locals are spilled to their memory slots by default, which costs a
+//! PUSH plus MLOAD or MSTORE on every access. The short-lived locals are
+//! kept on the EVM stack instead, the value set to such a local stays
+//! where it is and the later accesses reach it with DUPn or SWAPn.
+//!
+//! A local is kept on the stack only if all of its accesses are in the
+//! same straight-line region, which is split by the control instructions
+//! and the calls of imported functions, the first access sets it and the
+//! last access gets it, so it is dead out of the region. Besides, no
+//! operator may consume the operands below a kept local, and the kept
+//! local has to be within the reach of DUPn and SWAPn on every access.
+
+use crate::wasm::Env;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use wasmparser::Operator;
+
+/// Maximum depth of the stack items reachable by DUPn and SWAPn.
+const MAX_DEPTH: usize = 16;
+
+/// The code of a local access.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    /// Load or store the local from its memory slot.
+    Memory,
+    /// Keep the value on the top of the stack as the local.
+    Keep,
+    /// Replace the local with the value on the top of the stack,
+    /// which is `SWAPn` and `POP`.
+    Replace(u8),
+    /// Duplicate the local at the depth, which is `DUPn`.
+    Dup(u8),
+    /// Take the local at the depth at the last access, which is
+    /// nothing at depth 1 and `SWAP1` at depth 2.
+    Take(u8),
+}
+
+/// Accesses of a local in a function.
+#[derive(Debug, Default)]
+struct Accesses {
+    /// The region of the accesses, `None` if they are in different regions.
+    region: Option<usize>,
+    /// The count of the accesses.
+    count: usize,
+    /// If the first access sets the local.
+    first_set: bool,
+    /// If the last access gets the local.
+    last_get: bool,
+}
+
+/// The locals of a function kept on the stack.
+#[derive(Debug, Default)]
+pub struct StackLocals {
+    /// Indexes of the locals kept on the stack.
+    locals: BTreeSet<u32>,
+    /// Codes of the local accesses in order, `local.tee` has two
+    /// of them for setting and getting the local.
+    accesses: VecDeque<Access>,
+}
+
+impl StackLocals {
+    /// Allocate the locals of a function with `params` parameters.
+    pub fn new(ops: &[Operator], params: u32, env: &Env) -> Self {
+        let mut locals = Self::candidates(ops, params, env);
+        loop {
+            match Self::simulate(ops, &locals, env) {
+                Ok(accesses) => {
+                    return Self {
+                        locals: locals.into_keys().collect(),
+                        accesses,
+                    }
+                }
+                Err(local) => {
+                    tracing::trace!("spill local {local} to memory");
+                    locals.remove(&local);
+                }
+            }
+        }
+    }
+
+    /// If the local is kept on the stack.
+    pub fn contains(&self, index: u32) -> bool {
+        self.locals.contains(&index)
+    }
+
+    /// Take the code of the next local access.
+    pub fn access(&mut self) -> Access {
+        self.accesses.pop_front().unwrap_or(Access::Memory)
+    }
+
+    /// The locals accessed only in one region, which are set first
+    /// and got at last, with the counts of their accesses.
+    fn candidates(ops: &[Operator], params: u32, env: &Env) -> BTreeMap<u32, usize> {
+        let mut accesses: BTreeMap<u32, Accesses> = Default::default();
+        let mut region = 0;
+        for op in ops {
+            let (index, set, get) = match op {
+                Operator::LocalGet { local_index } => (*local_index, false, true),
+                Operator::LocalSet { local_index } => (*local_index, true, false),
+                Operator::LocalTee { local_index } => (*local_index, true, true),
+                op => {
+                    if effect(op, env).is_none() {
+                        region += 1;
+                    }
+
+                    continue;
+                }
+            };
+
+            let local = accesses.entry(index).or_insert_with(|| Accesses {
+                region: Some(region),
+                first_set: set,
+                ..Default::default()
+            });
+
+            if local.region != Some(region) {
+                local.region = None;
+            }
+
+            local.count += 1;
+            local.last_get = get;
+        }
+
+        accesses
+            .into_iter()
+            .filter(|(index, local)| {
+                *index >= params && local.region.is_some() && local.first_set && local.last_get
+            })
+            .map(|(index, local)| (index, local.count))
+            .collect()
+    }
+
+    /// Simulate the stack with the kept locals and returns the codes
+    /// of the local accesses, or the local which has to be spilled.
+    fn simulate(
+        ops: &[Operator],
+        locals: &BTreeMap<u32, usize>,
+        env: &Env,
+    ) -> std::result::Result<VecDeque<Access>, u32> {
+        // The stack items of the current region, `None` for operands.
+        let mut stack: Vec<Option<u32>> = Default::default();
+        let mut remaining = locals.clone();
+        let mut accesses = VecDeque::new();
+
+        for op in ops {
+            match op {
+                Operator::LocalGet { local_index } if locals.contains_key(local_index) => {
+                    accesses.push_back(get(&mut stack, &mut remaining, *local_index)?);
+                }
+                Operator::LocalSet { local_index } if locals.contains_key(local_index) => {
+                    if let Some(count) = remaining.get_mut(local_index) {
+                        *count -= 1;
+                    }
+
+                    accesses.push_back(set(&mut stack, *local_index)?);
+                }
+                Operator::LocalTee { local_index } if locals.contains_key(local_index) => {
+                    accesses.push_back(set(&mut stack, *local_index)?);
+                    accesses.push_back(get(&mut stack, &mut remaining, *local_index)?);
+                }
+                Operator::LocalGet { .. } => {
+                    accesses.push_back(Access::Memory);
+                    stack.push(None);
+                }
+                Operator::LocalSet { .. } => {
+                    accesses.push_back(Access::Memory);
+                    pop(&mut stack, 1)?;
+                }
+                Operator::LocalTee { .. } => {
+                    accesses.extend([Access::Memory, Access::Memory]);
+                    pop(&mut stack, 1)?;
+                    stack.push(None);
+                }
+                op => {
+                    let Some((pops, pushes)) = effect(op, env) else {
+                        if let Some(local) = stack.iter().flatten().next() {
+                            return Err(*local);
+                        }
+
+                        stack.clear();
+                        continue;
+                    };
+
+                    pop(&mut stack, pops)?;
+                    stack.extend((0..pushes).map(|_| None));
+                }
+            }
+        }
+
+        Ok(accesses)
+    }
+}
+
+/// Set a kept local with the value on the top of the stack.
+fn set(stack: &mut Vec<Option<u32>>, local: u32) -> std::result::Result<Access, u32> {
+    if stack.last() != Some(&None) {
+        return Err(local);
+    }
+
+    let Some(position) = stack.iter().position(|item| *item == Some(local)) else {
+        *stack.last_mut().expect("checked above") = Some(local);
+        return Ok(Access::Keep);
+    };
+
+    let depth = stack.len() - 1 - position;
+    if depth > MAX_DEPTH {
+        return Err(local);
+    }
+
+    stack.pop();
+    Ok(Access::Replace(depth as u8))
+}
+
+/// Get a kept local, the local is taken off the stack at the last access.
+fn get(
+    stack: &mut Vec<Option<u32>>,
+    remaining: &mut BTreeMap<u32, usize>,
+    local: u32,
+) -> std::result::Result<Access, u32> {
+    let Some(position) = stack.iter().position(|item| *item == Some(local)) else {
+        return Err(local);
+    };
+
+    let depth = stack.len() - position;
+    let count = remaining.entry(local).or_default();
+    *count = count.saturating_sub(1);
+    if *count > 0 {
+        if depth > MAX_DEPTH {
+            return Err(local);
+        }
+
+        stack.push(None);
+        return Ok(Access::Dup(depth as u8));
+    }
+
+    match depth {
+        1 => stack[position] = None,
+        2 => {
+            stack.remove(position);
+            stack.push(None);
+        }
+        _ => return Err(local),
+    }
+
+    Ok(Access::Take(depth as u8))
+}
+
+/// Pop operands from the stack, the kept locals can not be consumed
+/// by the operators.
+fn pop(stack: &mut Vec<Option<u32>>, items: usize) -> std::result::Result<(), u32> {
+    for _ in 0..items {
+        if let Some(Some(local)) = stack.pop() {
+            return Err(local);
+        }
+    }
+
+    Ok(())
+}
+
+/// The count of the operands consumed and produced by an operator in
+/// a straight-line region, `None` for the operators splitting regions.
+fn effect(op: &Operator, env: &Env) -> Option<(usize, usize)> {
+    use Operator::*;
+
+    Some(match op {
+        I32Const { .. } | I64Const { .. } | GlobalGet { .. } => (0, 1),
+        GlobalSet { .. } | Drop => (1, 0),
+        Select => (3, 1),
+        I32Eqz | I64Eqz | I32Clz | I32Ctz | I32Popcnt | I64Clz | I64Ctz | I64Popcnt
+        | I32WrapI64 | I64ExtendI32S | I64ExtendI32U | I32Extend8S | I32Extend16S | I64Extend8S
+        | I64Extend16S | I64Extend32S => (1, 1),
+        I32Eq | I32Ne | I32LtS | I32LtU | I32GtS | I32GtU | I32LeS | I32LeU | I32GeS | I32GeU
+        | I64Eq | I64Ne | I64LtS | I64LtU | I64GtS | I64GtU | I64LeS | I64LeU | I64GeS | I64GeU
+        | I32Add | I32Sub | I32Mul | I32DivS | I32DivU | I32RemS | I32RemU | I32And | I32Or
+        | I32Xor | I32Shl | I32ShrS | I32ShrU | I32Rotl | I32Rotr | I64Add | I64Sub | I64Mul
+        | I64DivS | I64DivU | I64RemS | I64RemU | I64And | I64Or | I64Xor | I64Shl | I64ShrS
+        | I64ShrU | I64Rotl | I64Rotr => (2, 1),
+        I32Load { .. }
+        | I64Load { .. }
+        | I32Load8S { .. }
+        | I32Load8U { .. }
+        | I32Load16S { .. }
+        | I32Load16U { .. }
+        | I64Load8S { .. }
+        | I64Load8U { .. }
+        | I64Load16S { .. }
+        | I64Load16U { .. }
+        | I64Load32S { .. }
+        | I64Load32U { .. } => (1, 1),
+        I32Store { .. }
+        | I64Store { .. }
+        | I32Store8 { .. }
+        | I32Store16 { .. }
+        | I64Store8 { .. }
+        | I64Store16 { .. }
+        | I64Store32 { .. } => (2, 0),
+        Call { function_index } => {
+            let (params, results) = env.funcs.get(function_index)?;
+            (*params as usize, *results as usize)
+        }
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Operator::*;
+
+    #[test]
+    fn test_keep_short_lived_locals() {
+        let mut locals = StackLocals::new(
+            &[
+                I32Const { value: 1 },
+                LocalSet { local_index: 0 },
+                LocalGet { local_index: 0 },
+                LocalGet { local_index: 0 },
+                I32Add,
+                Drop,
+            ],
+            0,
+            &Env::default(),
+        );
+
+        assert!(locals.contains(0));
+        assert_eq!(locals.access(), Access::Keep);
+        assert_eq!(locals.access(), Access::Dup(1));
+        assert_eq!(locals.access(), Access::Take(2));
+    }
+
+    #[test]
+    fn test_spill_locals() {
+        // accessed across regions
+        let locals = StackLocals::new(
+            &[
+                I32Const { value: 1 },
+                LocalSet { local_index: 0 },
+                Nop,
+                LocalGet { local_index: 0 },
+                Drop,
+            ],
+            0,
+            &Env::default(),
+        );
+        assert!(!locals.contains(0));
+
+        // the operand below the local is consumed
+        let locals = StackLocals::new(
+            &[
+                I32Const { value: 1 },
+                I32Const { value: 2 },
+                LocalSet { local_index: 0 },
+                Drop,
+                LocalGet { local_index: 0 },
+                Drop,
+            ],
+            0,
+            &Env::default(),
+        );
+        assert!(!locals.contains(0));
+
+        // parameters are passed in memory
+        let locals = StackLocals::new(
+            &[
+                I32Const { value: 1 },
+                LocalSet { local_index: 0 },
+                LocalGet { local_index: 0 },
+                Drop,
+            ],
+            1,
+            &Env::default(),
+        );
+        assert!(!locals.contains(0));
+    }
+}
@@ -9,6 +9,9 @@ pub struct Code {
     offset: usize,
     /// Function table.
     funcs: IndexMap<ExtFunc, usize>,
+    /// Data blobs placed after the functions, with their offsets
+    /// from the end of the functions.
+    data: IndexMap<Vec<u8>, usize>,
 }
 
 impl Code {
@@ -17,6 +20,7 @@ impl Code {
         Self {
             offset: 0,
             funcs: Default::default(),
+            data: Default::default(),
         }
     }
 
@@ -45,6 +49,25 @@ impl Code {
         self.offset += len;
     }
 
+    /// Get the data blobs in the code section.
+    pub fn data(&self) -> Vec<Vec<u8>> {
+        self.data.keys().cloned().collect()
+    }
+
+    /// Add a data blob to the code section.
+    ///
+    /// The data blobs are placed after all of the functions, so
+    /// none of the functions is swallowed by a trailing push opcode
+    /// of the data.
+    pub fn try_add_data(&mut self, data: Vec<u8>) {
+        if self.data.contains_key(&data) {
+            return;
+        }
+
+        let offset = self.data.keys().map(Vec::len).sum();
+        self.data.insert(data, offset);
+    }
+
     /// Get the current offset of the code section.
     pub fn offset(&self) -> usize {
         self.offset
@@ -55,6 +78,13 @@ impl Code {
         self.funcs.get(func).and_then(|i| (*i).try_into().ok())
     }
 
+    /// Get the offset of a data blob.
+    pub fn offset_of_data(&self, data: &[u8]) -> Option<u16> {
+        self.data
+            .get(data)
+            .and_then(|i| (self.offset + *i).try_into().ok())
+    }
+
     /// Get the bytecode of the code section.
     pub fn finish(&self) -> Vec<u8> {
         let mut code = Vec::new();
@@ -62,6 +92,10 @@ impl Code {
             tracing::trace!("add function to code section: {:?}", func);
             code.extend(func.bytecode.clone());
         }
+
+        for data in self.data.keys() {
+            code.extend(data);
+        }
         code
     }
 }
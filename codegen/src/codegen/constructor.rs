@@ -2,19 +2,28 @@
 
 use crate::{wasm::ToLSBytes, Buffer, MacroAssembler, Result};
 use smallvec::SmallVec;
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
-/// Initial storage of contracts
-pub type InitStorage = HashMap<SmallVec<[u8; 32]>, SmallVec<[u8; 32]>>;
+/// Initial storage of contracts, which is preset in the order of
+/// the keys.
+pub type InitStorage = BTreeMap<SmallVec<[u8; 32]>, SmallVec<[u8; 32]>>;
 
 /// Contract constructor.
 #[derive(Default, Debug, Clone)]
 pub struct Constructor {
+    /// Init code compiled from the constructor function.
+    init_code: Vec<u8>,
     /// Code generator.
     masm: MacroAssembler,
 }
 
 impl Constructor {
+    /// Run the init code compiled from the constructor function
+    /// before presetting the storage.
+    pub fn init_code(&mut self, init_code: &[u8]) {
+        self.init_code = init_code.into();
+    }
+
     /// preset storage for the contract
     pub fn storage(&mut self, mapping: InitStorage) -> Result<()> {
         tracing::debug!("Building storage in constructor ...");
@@ -32,8 +41,7 @@ impl Constructor {
     /// Here we override the memory totally with
     /// the runtime bytecode.
     pub fn finish(&self, runtime_bytecode: Buffer) -> Result<Buffer> {
-        let init_code = self.masm.buffer();
-        let init_code_len = init_code.len();
+        let init_code_len = self.init_code.len() + self.masm.buffer().len();
         let runtime_bytecode_len = runtime_bytecode.len();
         let runtime_bytecode_size = runtime_bytecode_len.to_ls_bytes();
         let runtime_bytecode_offset =
@@ -58,13 +66,15 @@ impl Constructor {
         masm.asm._return()?;
         masm.buffer_mut().extend_from_slice(&runtime_bytecode);
 
-        Ok(masm.buffer().into())
+        let mut bytecode = Buffer::from_slice(&self.init_code);
+        bytecode.extend_from_slice(masm.buffer());
+        Ok(bytecode)
     }
 
     /// Returns the offset of runtime bytecode.
     ///
     /// [
-    ///   init_code,
+    ///   init_code, storage,
     ///   pushn, runtime_bytecode_size, pushn + <offset>, push0, code_copy
     ///   pushn, runtime_bytecode_size, push0, return,
     ///   <OFFSET>
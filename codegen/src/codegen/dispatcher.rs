@@ -1,12 +1,16 @@
 //! Code generator for EVM dispatcher.
 
 use crate::{
-    wasm::{self, Env, Functions},
-    JumpTable, MacroAssembler, Result,
+    wasm::{Env, Functions},
+    Error, JumpTable, MacroAssembler, Result,
 };
 use std::collections::BTreeMap;
 use wasmparser::FuncType;
-use zabi::Abi;
+use zabi::{Abi, Type};
+
+/// Maximum selectors matched one by one in a branch of the binary
+/// search by default.
+pub const LINEAR_SELECTORS: usize = 4;
 
 /// Code generator for EVM dispatcher.
 pub struct Dispatcher {
@@ -14,6 +18,12 @@ pub struct Dispatcher {
     pub abi: Vec<Abi>,
     /// Code buffer
     pub asm: MacroAssembler,
+    /// If binary search the sorted selectors instead of matching
+    /// them one by one.
+    pub binary_search: bool,
+    /// Maximum selectors matched one by one in a branch of the binary
+    /// search.
+    pub linear_selectors: usize,
     /// WASM environment
     pub env: Env,
     /// Module functions
@@ -33,45 +43,181 @@ impl Dispatcher {
         Ok(Self {
             abi: Default::default(),
             asm: Default::default(),
+            binary_search: false,
+            linear_selectors: LINEAR_SELECTORS,
             env,
             funcs,
             table: Default::default(),
         })
     }
 
+    /// With the binary search over the sorted selectors.
+    pub fn binary_search(mut self, binary_search: bool) -> Self {
+        self.binary_search = binary_search;
+        self
+    }
+
+    /// With the maximum selectors matched one by one in a branch of
+    /// the binary search, the more of them, the fewer comparisons are
+    /// emitted for the branches.
+    pub fn linear_selectors(mut self, linear_selectors: usize) -> Self {
+        self.linear_selectors = linear_selectors;
+        self
+    }
+
     /// Emit compiled code to the given buffer.
-    pub fn finish(&mut self, selectors: Functions<'_>, table: &mut JumpTable) -> Result<Vec<u8>> {
+    ///
+    /// Empty calldata is routed to the receive function, or the fallback
+    /// function without it. If no selector matches, the fallback function
+    /// is called, or the call reverts without it.
+    ///
+    /// The call value is rejected here if none of the entries is payable,
+    /// otherwise the non-payable functions reject it by themselves.
+    pub fn finish(
+        &mut self,
+        selectors: Functions<'_>,
+        table: &mut JumpTable,
+        pc: u16,
+    ) -> Result<Vec<u8>> {
         if selectors.is_empty() {
             return Ok(Default::default());
         }
 
+        let mut targets = Vec::new();
+        let (mut fallback, mut receive) = (None, None);
+        for func in selectors.values() {
+            let abi = self.env.load_abi(func)?;
+            self.abi.push(abi.clone());
+
+            let index = self.env.query_func(&abi.name)?;
+            let entry = match abi.ty {
+                Type::Constructor => continue,
+                Type::Fallback => &mut fallback,
+                Type::Receive => &mut receive,
+                _ => {
+                    let selector_bytes = abi.selector();
+                    tracing::debug!(
+                        "Emitting selector {:?} for function: {}",
+                        selector_bytes,
+                        abi.signature(),
+                    );
+
+                    targets.push((selector_bytes, index));
+                    continue;
+                }
+            };
+
+            if entry.replace(index).is_some() {
+                return Err(Error::DuplicateEntry(abi.ty.to_string()));
+            }
+        }
+
+        if !self
+            .abi
+            .iter()
+            .any(|abi| !matches!(abi.ty, Type::Constructor) && abi.state_mutability.is_payable())
+        {
+            self.asm.reject_callvalue(&mut self.table)?;
+        }
+
+        if let Some(func) = receive.or(fallback) {
+            self.asm._calldatasize()?;
+            self.asm._iszero()?;
+            self.table.call(self.asm.pc(), func);
+            self.asm.increment_sp(1)?;
+            self.asm._jumpi()?;
+        }
+
+        if !targets.is_empty() {
+            self.emit_targets(targets)?;
+        }
+
+        if let Some(func) = fallback {
+            self.table.call(self.asm.pc(), func);
+            self.asm.increment_sp(1)?;
+            self.asm._jump()?;
+        } else {
+            self.asm._push0()?;
+            self.asm._push0()?;
+            self.asm._revert()?;
+        }
+
+        table.merge(self.table.clone(), pc)?;
+        Ok(self.asm.buffer().into())
+    }
+
+    /// Match the selector of the calldata with the selectors of the
+    /// external functions, falls through if no selector matches.
+    fn emit_targets(&mut self, mut targets: Vec<([u8; 4], u32)>) -> Result<()> {
         self.asm._push0()?;
         self.asm._calldataload()?;
         self.asm.push(&[0xe0])?;
         self.asm._shr()?;
-        let mut len = selectors.len();
-        for (_, func) in selectors.iter() {
-            self.emit_selector(func, len == 1)?;
-            len -= 1;
+
+        if !self.binary_search {
+            return self.emit_selectors(&targets);
         }
 
-        table.merge(self.table.clone(), 0)?;
-        Ok(self.asm.buffer().into())
+        targets.sort();
+        let mut exits = Vec::new();
+        self.emit_search(&targets, &mut exits)?;
+        if !exits.is_empty() {
+            let exit = self.asm.pc();
+            for pc in exits {
+                self.table.label(pc, exit);
+            }
+            self.asm._jumpdest()?;
+        }
+
+        Ok(())
+    }
+
+    /// Binary search the sorted selectors.
+    ///
+    /// The selector is compared with the last one of the lower half for
+    /// jumping to the upper half, and the selectors are matched one by one
+    /// in the branches with no more than `linear_selectors` selectors.
+    /// The program counters of the jumps from the branches to the end of
+    /// the dispatcher are pushed to `exits`, which is where the last
+    /// branch falls through.
+    fn emit_search(&mut self, targets: &[([u8; 4], u32)], exits: &mut Vec<u16>) -> Result<()> {
+        if targets.len() <= self.linear_selectors {
+            return self.emit_selectors(targets);
+        }
+
+        let (lower, upper) = targets.split_at(targets.len() / 2);
+        self.asm._dup1()?;
+        self.asm.push(&lower[lower.len() - 1].0)?;
+        // Compares with the raw `LT` for `pivot < selector`.
+        self.asm.asm._lt()?;
+        let branch = self.asm.pc();
+        self.asm.increment_sp(1)?;
+        self.asm._jumpi()?;
+
+        self.emit_search(lower, exits)?;
+        exits.push(self.asm.pc());
+        self.asm.increment_sp(1)?;
+        self.asm._jump()?;
+
+        self.table.label(branch, self.asm.pc());
+        self.asm._jumpdest()?;
+        // The selector is left on the stack by the branch.
+        self.asm.increment_sp(1)?;
+        self.emit_search(upper, exits)
+    }
+
+    /// Match the selectors one by one, the selector on the stack is
+    /// consumed by the last one.
+    fn emit_selectors(&mut self, targets: &[([u8; 4], u32)]) -> Result<()> {
+        for (index, (selector, func)) in targets.iter().enumerate() {
+            self.emit_selector(selector, *func, index + 1 == targets.len())?;
+        }
+
+        Ok(())
     }
 
     /// Emit selector to buffer.
-    fn emit_selector(&mut self, selector: &wasm::Function<'_>, last: bool) -> Result<()> {
-        let abi = self.env.load_abi(selector)?;
-        self.abi.push(abi.clone());
-
-        let selector_bytes = abi.selector();
-        tracing::debug!(
-            "Emitting selector {:?} for function: {}",
-            selector_bytes,
-            abi.signature(),
-        );
-
-        let func = self.env.query_func(&abi.name)?;
+    fn emit_selector(&mut self, selector_bytes: &[u8; 4], func: u32, last: bool) -> Result<()> {
         self.asm.increment_sp(1)?;
 
         // Prepare the `PC` of the callee function.
@@ -83,7 +229,7 @@ impl Dispatcher {
             self.asm._dup2()?;
         }
 
-        self.asm.push(&selector_bytes)?;
+        self.asm.push(selector_bytes)?;
         self.asm._eq()?;
         self.asm._swap1()?;
         self.asm._jumpi()?;
@@ -0,0 +1,956 @@
+//! Soft-float runtime.
+//!
+//! EVM has no floating point instructions, so the float instructions of
+//! WASM are lowered to the internal routines of this runtime, which
+//! compute the IEEE-754 results with round-to-nearest-even on the bit
+//! patterns of the floats.
+//!
+//! The routines take their operands and the return address on the stack
+//! and leave the results on it, the intermediate values are kept in the
+//! memory registers reserved at [`Env::float`]. Only the routines called
+//! by the compiled functions are emitted after them.
+
+use crate::{jump::JumpTable, wasm::Env, Buffer, MacroAssembler, Result};
+use std::ops;
+
+/// Memory slots of the registers of the soft-float routines.
+pub const FLOAT_REGISTERS: u32 = Reg::Count as u32;
+
+/// Function index of the first soft-float routine in the jump table,
+/// which is out of the range of the WASM functions.
+const ROUTINE_BASE: u32 = 0xffff_0000;
+
+/// Kinds of the routines, the index of a routine in the jump table is
+/// computed from its kind and its float format.
+const ROUTINES: [fn(Float) -> Routine; 14] = [
+    Routine::Add,
+    Routine::Mul,
+    Routine::Div,
+    Routine::Sqrt,
+    Routine::Compare,
+    Routine::Min,
+    Routine::Max,
+    Routine::Ceil,
+    Routine::Floor,
+    Routine::Trunc,
+    Routine::Nearest,
+    Routine::ToInt,
+    Routine::Convert,
+    Routine::Pack,
+];
+
+/// Float formats.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Float {
+    /// 32-bit float.
+    F32,
+    /// 64-bit float.
+    F64,
+}
+
+impl Float {
+    /// Bits of the float.
+    pub fn bits(self) -> u8 {
+        match self {
+            Float::F32 => 32,
+            Float::F64 => 64,
+        }
+    }
+
+    /// Bits of the fraction.
+    fn fraction(self) -> u8 {
+        match self {
+            Float::F32 => 23,
+            Float::F64 => 52,
+        }
+    }
+
+    /// Bias of the exponent.
+    fn bias(self) -> i128 {
+        match self {
+            Float::F32 => 127,
+            Float::F64 => 1023,
+        }
+    }
+
+    /// The sign bit.
+    pub fn sign(self) -> u64 {
+        1 << (self.bits() - 1)
+    }
+
+    /// The positive infinity.
+    fn infinity(self) -> u64 {
+        ((1 << (self.bits() - self.fraction() - 1)) - 1) << self.fraction()
+    }
+
+    /// The quiet bit of NaN.
+    fn quiet(self) -> u64 {
+        1 << (self.fraction() - 1)
+    }
+
+    /// The canonical NaN.
+    fn nan(self) -> u64 {
+        self.infinity() | self.quiet()
+    }
+
+    /// The other float format.
+    fn other(self) -> Self {
+        match self {
+            Float::F32 => Float::F64,
+            Float::F64 => Float::F32,
+        }
+    }
+}
+
+/// Soft-float routines.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Routine {
+    /// STACK: [a, b] -> [a + b]
+    Add(Float),
+    /// STACK: [a, b] -> [a * b]
+    Mul(Float),
+    /// STACK: [a, b] -> [a / b]
+    Div(Float),
+    /// STACK: [a] -> [sqrt(a)]
+    Sqrt(Float),
+    /// Compare two floats, the result is `1` if `a < b`, `2` if
+    /// `a == b`, `4` if `a > b` and `0` if they are unordered.
+    ///
+    /// STACK: [a, b] -> [flags]
+    Compare(Float),
+    /// STACK: [a, b] -> [min(a, b)]
+    Min(Float),
+    /// STACK: [a, b] -> [max(a, b)]
+    Max(Float),
+    /// STACK: [a] -> [ceil(a)]
+    Ceil(Float),
+    /// STACK: [a] -> [floor(a)]
+    Floor(Float),
+    /// STACK: [a] -> [trunc(a)]
+    Trunc(Float),
+    /// STACK: [a] -> [nearest(a)]
+    Nearest(Float),
+    /// Truncate a float to the sign and the magnitude of an integer,
+    /// traps on NaN, infinity and the floats not less than 2^65, the
+    /// ranges of the integer types are checked by the callers.
+    ///
+    /// STACK: [a] -> [sign, magnitude]
+    ToInt(Float),
+    /// Convert a float to the other format.
+    ///
+    /// STACK: [a] -> [b]
+    Convert(Float),
+    /// Round `magnitude * 2^exponent` to a float.
+    ///
+    /// STACK: [sign, magnitude, exponent] -> [a]
+    Pack(Float),
+}
+
+impl Routine {
+    /// Function index of the routine in the jump table.
+    pub fn index(self) -> u32 {
+        let (kind, float) = self.kind();
+        ROUTINE_BASE + kind * 2 + float as u32
+    }
+
+    /// The kind and the float format of the routine.
+    fn kind(self) -> (u32, Float) {
+        match self {
+            Routine::Add(f) => (0, f),
+            Routine::Mul(f) => (1, f),
+            Routine::Div(f) => (2, f),
+            Routine::Sqrt(f) => (3, f),
+            Routine::Compare(f) => (4, f),
+            Routine::Min(f) => (5, f),
+            Routine::Max(f) => (6, f),
+            Routine::Ceil(f) => (7, f),
+            Routine::Floor(f) => (8, f),
+            Routine::Trunc(f) => (9, f),
+            Routine::Nearest(f) => (10, f),
+            Routine::ToInt(f) => (11, f),
+            Routine::Convert(f) => (12, f),
+            Routine::Pack(f) => (13, f),
+        }
+    }
+
+    /// Counts of the params and the results of the routine.
+    fn signature(self) -> (u16, u16) {
+        match self {
+            Routine::Add(_)
+            | Routine::Mul(_)
+            | Routine::Div(_)
+            | Routine::Compare(_)
+            | Routine::Min(_)
+            | Routine::Max(_) => (2, 1),
+            Routine::ToInt(_) => (1, 2),
+            Routine::Pack(_) => (3, 1),
+            _ => (1, 1),
+        }
+    }
+
+    /// Emit a call to the routine.
+    ///
+    /// STACK: [.., params] -> [.., results]
+    pub fn call(self, masm: &mut MacroAssembler, table: &mut JumpTable) -> Result<()> {
+        let (params, results) = self.signature();
+
+        // Register the label to jump back.
+        table.label(masm.pc(), masm.pc() + 2);
+        masm.increment_sp(1)?;
+        masm._jumpdest()?;
+
+        table.call(masm.pc(), self.index());
+        masm.increment_sp(1)?;
+        masm._jump()?;
+
+        masm._jumpdest()?;
+        masm.decrement_sp(params + 1)?;
+        masm.increment_sp(results)
+    }
+
+    /// Compile the routine.
+    pub fn finish(self, env: &Env, jump_table: &mut JumpTable, pc: u16) -> Result<Buffer> {
+        let mut routine = SoftFloat {
+            masm: Default::default(),
+            table: Default::default(),
+            base: env.float,
+        };
+
+        let (params, _) = self.signature();
+        routine.masm.increment_sp(params + 1)?;
+        routine.masm._jumpdest()?;
+        match self {
+            Routine::Add(f) => routine.add(f)?,
+            Routine::Mul(f) => routine.mul(f)?,
+            Routine::Div(f) => routine.div(f)?,
+            Routine::Sqrt(f) => routine.sqrt(f)?,
+            Routine::Compare(f) => routine.compare(f)?,
+            Routine::Min(f) => routine.min_max(f, false)?,
+            Routine::Max(f) => routine.min_max(f, true)?,
+            Routine::Ceil(f) => routine.round(f, Rounding::Ceil)?,
+            Routine::Floor(f) => routine.round(f, Rounding::Floor)?,
+            Routine::Trunc(f) => routine.round(f, Rounding::Trunc)?,
+            Routine::Nearest(f) => routine.round(f, Rounding::Nearest)?,
+            Routine::ToInt(f) => routine.integer(f)?,
+            Routine::Convert(f) => routine.convert(f)?,
+            Routine::Pack(f) => routine.pack(f)?,
+        }
+
+        jump_table.merge(routine.table, pc)?;
+        Ok(routine.masm.buffer().into())
+    }
+}
+
+impl TryFrom<u32> for Routine {
+    type Error = ();
+
+    fn try_from(index: u32) -> core::result::Result<Self, ()> {
+        let index = index.checked_sub(ROUTINE_BASE).ok_or(())?;
+        let float = if index % 2 == 0 {
+            Float::F32
+        } else {
+            Float::F64
+        };
+        let kind = ROUTINES.get(index as usize / 2).ok_or(())?;
+        Ok(kind(float))
+    }
+}
+
+/// Rounding modes of the float to integral routines.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Rounding {
+    Ceil,
+    Floor,
+    Trunc,
+    Nearest,
+}
+
+/// Memory registers of the routines, the ones of [`Routine::Pack`]
+/// are separated since it is called by the other routines.
+#[derive(Clone, Copy)]
+enum Reg {
+    Ret,
+    A,
+    B,
+    Sign,
+    SignB,
+    Sa,
+    Ea,
+    Sb,
+    Eb,
+    S,
+    X,
+    PackRet,
+    PackSign,
+    PackS,
+    PackE,
+    PackC,
+    PackT,
+    Count,
+}
+
+/// Expressions evaluated on the stack.
+#[derive(Clone)]
+enum Expr {
+    Reg(Reg),
+    Const(i128),
+    Binary(Binary, Box<Expr>, Box<Expr>),
+    IsZero(Box<Expr>),
+    Bitlen(Box<Expr>),
+}
+
+/// Binary operators of the expressions, `a op b` for the shifts means
+/// shifting `a` by `b`.
+#[derive(Clone, Copy)]
+enum Binary {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+    And,
+    Or,
+    Xor,
+    Shl,
+    Shr,
+    Sar,
+    Lt,
+    Gt,
+    Slt,
+    Sgt,
+    Eq,
+}
+
+/// Constant expression.
+fn c(value: impl Into<i128>) -> Expr {
+    Expr::Const(value.into())
+}
+
+impl From<Reg> for Expr {
+    fn from(reg: Reg) -> Self {
+        Expr::Reg(reg)
+    }
+}
+
+impl Expr {
+    fn binary(self, op: Binary, rhs: impl Into<Expr>) -> Expr {
+        Expr::Binary(op, Box::new(self), Box::new(rhs.into()))
+    }
+
+    fn lt(self, rhs: impl Into<Expr>) -> Expr {
+        self.binary(Binary::Lt, rhs)
+    }
+
+    fn gt(self, rhs: impl Into<Expr>) -> Expr {
+        self.binary(Binary::Gt, rhs)
+    }
+
+    fn slt(self, rhs: impl Into<Expr>) -> Expr {
+        self.binary(Binary::Slt, rhs)
+    }
+
+    fn sgt(self, rhs: impl Into<Expr>) -> Expr {
+        self.binary(Binary::Sgt, rhs)
+    }
+
+    fn eq(self, rhs: impl Into<Expr>) -> Expr {
+        self.binary(Binary::Eq, rhs)
+    }
+
+    fn sar(self, rhs: impl Into<Expr>) -> Expr {
+        self.binary(Binary::Sar, rhs)
+    }
+
+    fn iszero(self) -> Expr {
+        Expr::IsZero(Box::new(self))
+    }
+
+    fn nonzero(self) -> Expr {
+        self.iszero().iszero()
+    }
+
+    fn bitlen(self) -> Expr {
+        Expr::Bitlen(Box::new(self))
+    }
+}
+
+impl Reg {
+    fn e(self) -> Expr {
+        self.into()
+    }
+}
+
+macro_rules! impl_binary {
+    ($(($trait:ident, $method:ident, $op:ident)),+) => {
+        $(
+            impl<T: Into<Expr>> ops::$trait<T> for Expr {
+                type Output = Expr;
+
+                fn $method(self, rhs: T) -> Expr {
+                    self.binary(Binary::$op, rhs)
+                }
+            }
+
+            impl<T: Into<Expr>> ops::$trait<T> for Reg {
+                type Output = Expr;
+
+                fn $method(self, rhs: T) -> Expr {
+                    self.e().binary(Binary::$op, rhs)
+                }
+            }
+        )+
+    };
+}
+
+impl_binary! {
+    (Add, add, Add),
+    (Sub, sub, Sub),
+    (Mul, mul, Mul),
+    (Div, div, Div),
+    (Rem, rem, Mod),
+    (BitAnd, bitand, And),
+    (BitOr, bitor, Or),
+    (BitXor, bitxor, Xor),
+    (Shl, shl, Shl),
+    (Shr, shr, Shr)
+}
+
+/// Code generator of the soft-float routines.
+struct SoftFloat {
+    /// The macro assembler.
+    masm: MacroAssembler,
+    /// The jump table.
+    table: JumpTable,
+    /// Memory slot of the first register.
+    base: u32,
+}
+
+impl SoftFloat {
+    /// Emit an expression.
+    ///
+    /// STACK: [] -> [value]
+    fn emit(&mut self, expr: &Expr) -> Result<()> {
+        match expr {
+            Expr::Reg(reg) => {
+                self.push(self.address(*reg))?;
+                self.masm._mload()
+            }
+            Expr::Const(value) => self.push(*value),
+            Expr::IsZero(value) => {
+                self.emit(value)?;
+                self.masm._iszero()
+            }
+            Expr::Bitlen(value) => {
+                self.emit(value)?;
+                self.bitlen()
+            }
+            Expr::Binary(op, lhs, rhs) => {
+                let shift = matches!(op, Binary::Shl | Binary::Shr | Binary::Sar);
+                let (first, second) = if shift { (lhs, rhs) } else { (rhs, lhs) };
+                self.emit(first)?;
+                self.emit(second)?;
+
+                let asm = &mut self.masm.asm;
+                match op {
+                    Binary::Add => asm._add(),
+                    Binary::Sub => asm._sub(),
+                    Binary::Mul => asm._mul(),
+                    Binary::Div => asm._div(),
+                    Binary::Mod => asm._mod(),
+                    Binary::And => asm._and(),
+                    Binary::Or => asm._or(),
+                    Binary::Xor => asm._xor(),
+                    Binary::Shl => asm._shl(),
+                    Binary::Shr => asm._shr(),
+                    Binary::Sar => asm._sar(),
+                    Binary::Lt => asm._lt(),
+                    Binary::Gt => asm._gt(),
+                    Binary::Slt => asm._slt(),
+                    Binary::Sgt => asm._sgt(),
+                    Binary::Eq => asm._eq(),
+                }
+            }
+        }
+    }
+
+    /// Push a constant, the negative ones are pushed in two's complement.
+    fn push(&mut self, value: impl Into<i128>) -> Result<()> {
+        let value: i128 = value.into();
+        let mut bytes = [if value < 0 { 0xff } else { 0 }; 32];
+        bytes[16..].copy_from_slice(&value.to_be_bytes());
+
+        let start = bytes
+            .iter()
+            .position(|b| *b != 0)
+            .unwrap_or(bytes.len() - 1);
+        self.masm.push(&bytes[start..])
+    }
+
+    /// Memory offset of a register.
+    fn address(&self, reg: Reg) -> u32 {
+        (self.base + reg as u32) * 0x20
+    }
+
+    /// Store the value on the top of the stack to a register.
+    ///
+    /// STACK: [value] -> []
+    fn store(&mut self, reg: Reg) -> Result<()> {
+        self.push(self.address(reg))?;
+        self.masm._mstore()
+    }
+
+    /// Evaluate an expression to a register.
+    fn set(&mut self, reg: Reg, expr: Expr) -> Result<()> {
+        self.emit(&expr)?;
+        self.store(reg)
+    }
+
+    /// Store the params and the return address in registers.
+    fn params(&mut self, ret: Reg, params: &[Reg]) -> Result<()> {
+        self.store(ret)?;
+        for reg in params.iter().rev() {
+            self.store(*reg)?;
+        }
+
+        Ok(())
+    }
+
+    /// Run `then` if the condition is not zero.
+    fn when(&mut self, cond: Expr, then: impl FnOnce(&mut Self) -> Result<()>) -> Result<()> {
+        let sp = self.masm.sp();
+        self.emit(&cond.iszero())?;
+        let skip = self.masm.pc();
+        self.masm.increment_sp(1)?;
+        self.masm._jumpi()?;
+
+        then(self)?;
+        self.masm.asm.sp = sp;
+        self.table.label(skip, self.masm.pc());
+        self.masm._jumpdest()
+    }
+
+    /// Trap if the condition is not zero.
+    fn trap(&mut self, cond: Expr) -> Result<()> {
+        self.when(cond, |f| f.masm._invalid())
+    }
+
+    /// Return the results to the address in `ret`, on top of the
+    /// values already on the stack.
+    fn ret(&mut self, ret: Reg, results: &[Expr]) -> Result<()> {
+        for result in results {
+            self.emit(result)?;
+        }
+
+        self.emit(&ret.e())?;
+        self.masm._jump()
+    }
+
+    /// Call a routine with the arguments.
+    fn call(&mut self, routine: Routine, args: &[Expr]) -> Result<()> {
+        for arg in args {
+            self.emit(arg)?;
+        }
+
+        routine.call(&mut self.masm, &mut self.table)
+    }
+
+    /// Bit length of the value on the top of the stack, the bits are
+    /// counted by binary search.
+    ///
+    /// STACK: [value] -> [bits]
+    fn bitlen(&mut self) -> Result<()> {
+        // [bits, value]
+        self.masm._push0()?;
+        self.masm._swap1()?;
+        for shift in [128u8, 64, 32, 16, 8, 4, 2, 1] {
+            // [bits, value, shift if value >> shift != 0]
+            self.masm._dup1()?;
+            self.masm.push(&[shift])?;
+            self.masm.asm._shr()?;
+            self.masm._iszero()?;
+            self.masm._iszero()?;
+            self.masm.push(&[shift])?;
+            self.masm.asm._mul()?;
+
+            // [value >> shift, bits + shift]
+            self.masm._swap1()?;
+            self.masm._dup2()?;
+            self.masm.asm._shr()?;
+            self.masm._swap2()?;
+            self.masm.asm._add()?;
+            self.masm._swap1()?;
+        }
+
+        // the value is 1 or 0 at last.
+        self.masm.asm._add()
+    }
+
+    /// The absolute bits of a float.
+    fn abs(f: Float, value: impl Into<Expr>) -> Expr {
+        value.into() & c(f.sign() - 1)
+    }
+
+    /// If the float is NaN.
+    fn is_nan(f: Float, value: impl Into<Expr>) -> Expr {
+        Self::abs(f, value).gt(c(f.infinity()))
+    }
+
+    /// If the float is infinity.
+    fn is_inf(f: Float, value: impl Into<Expr>) -> Expr {
+        Self::abs(f, value).eq(c(f.infinity()))
+    }
+
+    /// The sign bit of a float shifted to the lowest bit.
+    fn sign(f: Float, value: impl Into<Expr>) -> Expr {
+        value.into() >> c(f.bits() - 1)
+    }
+
+    /// Key of a float as a signed integer, which keeps the order of
+    /// the floats, both of the zeros are mapped to zero.
+    fn key(f: Float, reg: Reg) -> Expr {
+        (Self::abs(f, reg) ^ (c(0) - Self::sign(f, reg))) + Self::sign(f, reg)
+    }
+
+    /// Return the quiet NaN of the operands if any of them is NaN.
+    fn nan(&mut self, f: Float, operands: &[Reg]) -> Result<()> {
+        for reg in operands {
+            self.when(Self::is_nan(f, *reg), |s| {
+                s.ret(Reg::Ret, &[*reg | c(f.quiet())])
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// Unpack a finite float into its sign, significand and exponent,
+    /// where the value is `significand * 2^exponent`.
+    fn unpack(&mut self, f: Float, reg: Reg, sign: Reg, s: Reg, e: Reg) -> Result<()> {
+        let fraction = f.fraction();
+        self.set(sign, Self::sign(f, reg))?;
+        self.set(s, reg & c((1u64 << fraction) - 1))?;
+        self.set(
+            e,
+            (reg >> c(fraction)) & c((1u64 << (f.bits() - fraction - 1)) - 1),
+        )?;
+
+        // The implicit leading bit of the normal floats, the exponent
+        // field of the subnormal ones is treated as 1.
+        self.set(s, s | (e.e().nonzero() << c(fraction)))?;
+        self.set(e, e + e.e().iszero() - c(f.bias() + fraction as i128))
+    }
+
+    /// Round `magnitude * 2^exponent` to a float, with ties to even.
+    fn pack(&mut self, f: Float) -> Result<()> {
+        use Reg::*;
+
+        let fraction = f.fraction() as i128;
+        let emin = 1 - f.bias();
+        self.params(PackRet, &[PackSign, PackS, PackE])?;
+        self.when(PackS.e().iszero(), |s| {
+            s.ret(PackRet, &[PackSign << c(f.bits() - 1)])
+        })?;
+
+        // The exponent of the result.
+        self.set(PackC, PackE + PackS.e().bitlen() - c(1))?;
+        self.when(PackC.e().slt(c(emin)), |s| s.set(PackC, c(emin)))?;
+
+        // Shift the magnitude to the significand of the result.
+        self.set(PackT, PackC - c(fraction) - PackE)?;
+        self.when(PackT.e().sgt(c(0)), |s| {
+            s.when(PackT.e().gt(c(255)), |s| s.set(PackT, c(255)))?;
+
+            // [remainder, quotient, half]
+            s.set(PackE, PackS & ((c(1) << PackT) - c(1)))?;
+            s.set(PackS, PackS >> PackT)?;
+            s.set(PackT, c(1) << (PackT - c(1)))?;
+            s.set(
+                PackS,
+                PackS + (PackE.e().gt(PackT) | (PackE.e().eq(PackT) & PackS & c(1))),
+            )
+        })?;
+        self.when(PackT.e().slt(c(0)), |s| {
+            s.set(PackS, PackS << (c(0) - PackT))
+        })?;
+
+        // The carry of the significand increases the exponent, and
+        // the overflows are rounded to infinity.
+        self.set(PackS, ((PackC + c(f.bias() - 1)) << c(fraction)) + PackS)?;
+        self.when(PackS.e().gt(c(f.infinity())), |s| {
+            s.set(PackS, c(f.infinity()))
+        })?;
+        self.ret(PackRet, &[PackS | (PackSign << c(f.bits() - 1))])
+    }
+
+    /// Addition, subtraction is addition with the sign of `b` flipped.
+    fn add(&mut self, f: Float) -> Result<()> {
+        use Reg::*;
+
+        self.params(Ret, &[A, B])?;
+        self.nan(f, &[A, B])?;
+        self.when(Self::is_inf(f, A), |s| {
+            s.when(Self::is_inf(f, B) & Self::sign(f, A ^ B), |s| {
+                s.ret(Ret, &[c(f.nan())])
+            })?;
+            s.ret(Ret, &[A.e()])
+        })?;
+        self.when(Self::is_inf(f, B), |s| s.ret(Ret, &[B.e()]))?;
+
+        // Swap the operands to make sure `|a| >= |b|`.
+        self.when(Self::abs(f, A).lt(Self::abs(f, B)), |s| {
+            s.set(X, A.e())?;
+            s.set(A, B.e())?;
+            s.set(B, X.e())
+        })?;
+        self.unpack(f, A, Sign, Sa, Ea)?;
+        self.unpack(f, B, SignB, Sb, Eb)?;
+
+        // Align the significands, the bits of `b` far below the
+        // rounding position only matter as a sticky bit.
+        let cap = f.fraction() as i128 + 4;
+        self.set(X, Ea - Eb)?;
+        self.when(X.e().gt(c(cap)), |s| {
+            s.set(Sb, Sb.e().nonzero())?;
+            s.set(X, c(cap))
+        })?;
+        self.set(Sa, Sa << X)?;
+        self.set(S, Sa + Sb)?;
+        self.when(Sign ^ SignB, |s| s.set(S, Sa - Sb))?;
+
+        // The exact zero is negative only if both operands are.
+        self.when(S.e().iszero(), |s| s.set(Sign, Sign & SignB))?;
+        self.call(Routine::Pack(f), &[Sign.e(), S.e(), Ea - X])?;
+        self.ret(Ret, &[])
+    }
+
+    /// Multiplication.
+    fn mul(&mut self, f: Float) -> Result<()> {
+        use Reg::*;
+
+        self.params(Ret, &[A, B])?;
+        self.nan(f, &[A, B])?;
+        self.set(Sign, Self::sign(f, A ^ B))?;
+        let inf = Sign << c(f.bits() - 1) | c(f.infinity());
+        for (a, b) in [(A, B), (B, A)] {
+            self.when(Self::is_inf(f, a), |s| {
+                s.when(Self::abs(f, b).iszero(), |s| s.ret(Ret, &[c(f.nan())]))?;
+                s.ret(Ret, std::slice::from_ref(&inf))
+            })?;
+        }
+
+        self.unpack(f, A, X, Sa, Ea)?;
+        self.unpack(f, B, X, Sb, Eb)?;
+        self.call(Routine::Pack(f), &[Sign.e(), Sa * Sb, Ea + Eb])?;
+        self.ret(Ret, &[])
+    }
+
+    /// Division.
+    fn div(&mut self, f: Float) -> Result<()> {
+        use Reg::*;
+
+        self.params(Ret, &[A, B])?;
+        self.nan(f, &[A, B])?;
+        self.set(Sign, Self::sign(f, A ^ B))?;
+        let zero = Sign << c(f.bits() - 1);
+        let inf = zero.clone() | c(f.infinity());
+        self.when(Self::is_inf(f, A), |s| {
+            s.when(Self::is_inf(f, B), |s| s.ret(Ret, &[c(f.nan())]))?;
+            s.ret(Ret, std::slice::from_ref(&inf))
+        })?;
+        self.when(Self::is_inf(f, B), |s| s.ret(Ret, &[zero]))?;
+        self.when(Self::abs(f, B).iszero(), |s| {
+            s.when(Self::abs(f, A).iszero(), |s| s.ret(Ret, &[c(f.nan())]))?;
+            s.ret(Ret, &[inf])
+        })?;
+
+        // The quotient keeps enough bits for rounding, and the
+        // remainder is folded into it as a sticky bit.
+        let shift = 2 * f.fraction() as i128 + 5;
+        self.unpack(f, A, X, Sa, Ea)?;
+        self.unpack(f, B, X, Sb, Eb)?;
+        self.set(Sa, Sa << c(shift))?;
+        self.set(S, ((Sa / Sb) << c(1)) | (Sa % Sb).nonzero())?;
+        self.call(Routine::Pack(f), &[Sign.e(), S.e(), Ea - Eb - c(shift + 1)])?;
+        self.ret(Ret, &[])
+    }
+
+    /// Square root, the integer square root is computed with the
+    /// Newton's method.
+    fn sqrt(&mut self, f: Float) -> Result<()> {
+        use Reg::*;
+
+        self.params(Ret, &[A])?;
+        self.nan(f, &[A])?;
+        self.when(Self::abs(f, A).iszero(), |s| s.ret(Ret, &[A.e()]))?;
+        self.when(Self::sign(f, A), |s| s.ret(Ret, &[c(f.nan())]))?;
+        self.when(A.e().eq(c(f.infinity())), |s| s.ret(Ret, &[A.e()]))?;
+
+        // Make the exponent even.
+        let shift = f.fraction() as i128 + 4;
+        self.unpack(f, A, Sign, Sa, Ea)?;
+        self.when(Ea & c(1), |s| {
+            s.set(Sa, Sa << c(1))?;
+            s.set(Ea, Ea - c(1))
+        })?;
+        self.set(Sa, Sa << c(shift * 2))?;
+
+        // x = (x + n / x) / 2 until it stops decreasing.
+        self.set(X, c(1) << ((Sa.e().bitlen() + c(1)) >> c(1)))?;
+        let head = self.masm.pc();
+        self.masm._jumpdest()?;
+        self.set(S, (X + Sa / X) >> c(1))?;
+        self.emit(&S.e().lt(X).iszero())?;
+        let exit = self.masm.pc();
+        self.masm.increment_sp(1)?;
+        self.masm._jumpi()?;
+
+        self.set(X, S.e())?;
+        self.table.label(self.masm.pc(), head);
+        self.masm.increment_sp(1)?;
+        self.masm._jump()?;
+
+        self.table.label(exit, self.masm.pc());
+        self.masm._jumpdest()?;
+        self.set(S, (X << c(1)) | (X * X).eq(Sa).iszero())?;
+        self.call(
+            Routine::Pack(f),
+            &[c(0), S.e(), Ea.e().sar(c(1)) - c(shift + 1)],
+        )?;
+        self.ret(Ret, &[])
+    }
+
+    /// Comparison.
+    fn compare(&mut self, f: Float) -> Result<()> {
+        use Reg::*;
+
+        self.params(Ret, &[A, B])?;
+        self.when(Self::is_nan(f, A) | Self::is_nan(f, B), |s| {
+            s.ret(Ret, &[c(0)])
+        })?;
+        self.set(A, Self::key(f, A))?;
+        self.set(B, Self::key(f, B))?;
+        self.ret(
+            Ret,
+            &[A.e().slt(B) | (A.e().eq(B) << c(1)) | (A.e().sgt(B) << c(2))],
+        )
+    }
+
+    /// Minimum or maximum, `-0` is less than `+0`.
+    fn min_max(&mut self, f: Float, max: bool) -> Result<()> {
+        use Reg::*;
+
+        self.params(Ret, &[A, B])?;
+        self.nan(f, &[A, B])?;
+        self.set(X, Self::key(f, A))?;
+        self.set(S, Self::key(f, B))?;
+
+        let (equal, first) = if max {
+            (A & B, X.e().sgt(S))
+        } else {
+            (A | B, X.e().slt(S))
+        };
+        self.when(X.e().eq(S), |s| s.ret(Ret, &[equal]))?;
+        self.when(first, |s| s.ret(Ret, &[A.e()]))?;
+        self.ret(Ret, &[B.e()])
+    }
+
+    /// Round to an integral float.
+    fn round(&mut self, f: Float, rounding: Rounding) -> Result<()> {
+        use Reg::*;
+
+        self.params(Ret, &[A])?;
+        self.nan(f, &[A])?;
+
+        // The floats with non-negative exponents are integral already,
+        // including the infinities.
+        self.unpack(f, A, Sign, Sa, Ea)?;
+        self.when(Ea.e().sgt(c(-1)), |s| s.ret(Ret, &[A.e()]))?;
+
+        // [remainder, quotient]
+        self.set(Ea, c(0) - Ea)?;
+        self.when(Ea.e().gt(c(255)), |s| s.set(Ea, c(255)))?;
+        self.set(X, Sa & ((c(1) << Ea) - c(1)))?;
+        self.set(Sa, Sa >> Ea)?;
+        match rounding {
+            Rounding::Ceil => self.set(Sa, Sa + ((Sign ^ c(1)) & X.e().nonzero()))?,
+            Rounding::Floor => self.set(Sa, Sa + (Sign & X.e().nonzero()))?,
+            Rounding::Trunc => {}
+            Rounding::Nearest => {
+                self.set(Ea, c(1) << (Ea - c(1)))?;
+                self.set(Sa, Sa + (X.e().gt(Ea) | (X.e().eq(Ea) & Sa & c(1))))?;
+            }
+        }
+
+        self.call(Routine::Pack(f), &[Sign.e(), Sa.e(), c(0)])?;
+        self.ret(Ret, &[])
+    }
+
+    /// Truncate to the sign and the magnitude of an integer.
+    fn integer(&mut self, f: Float) -> Result<()> {
+        use Reg::*;
+
+        self.params(Ret, &[A])?;
+        self.trap(Self::abs(f, A).gt(c(f.infinity() - 1)))?;
+        self.unpack(f, A, Sign, Sa, Ea)?;
+        self.trap(Ea.e().sgt(c(64)))?;
+        self.when(Ea.e().slt(c(0)), |s| {
+            s.set(Ea, c(0) - Ea)?;
+            s.when(Ea.e().gt(c(255)), |s| s.set(Ea, c(255)))?;
+            s.ret(Ret, &[Sign.e(), Sa >> Ea])
+        })?;
+        self.ret(Ret, &[Sign.e(), Sa << Ea])
+    }
+
+    /// Convert to the other format, the payloads of NaN are kept
+    /// as much as possible.
+    fn convert(&mut self, f: Float) -> Result<()> {
+        use Reg::*;
+
+        let to = f.other();
+        let sign = Self::sign(f, A) << c(to.bits() - 1);
+        let fraction = A & c((1u64 << f.fraction()) - 1);
+        let payload = if to == Float::F32 {
+            fraction >> c(f.fraction() - to.fraction())
+        } else {
+            fraction << c(to.fraction() - f.fraction())
+        };
+
+        self.params(Ret, &[A])?;
+        self.when(Self::is_nan(f, A), |s| {
+            s.ret(Ret, &[sign.clone() | c(to.nan()) | payload])
+        })?;
+        self.when(Self::is_inf(f, A), |s| {
+            s.ret(Ret, &[sign | c(to.infinity())])
+        })?;
+
+        self.unpack(f, A, Sign, Sa, Ea)?;
+        self.call(Routine::Pack(to), &[Sign.e(), Sa.e(), Ea.e()])?;
+        self.ret(Ret, &[])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_routine_index() {
+        for kind in ROUTINES {
+            for float in [Float::F32, Float::F64] {
+                let routine = kind(float);
+                assert_eq!(Routine::try_from(routine.index()), Ok(routine));
+            }
+        }
+
+        assert_eq!(Routine::try_from(0), Err(()));
+        assert_eq!(Routine::try_from(ROUTINE_BASE + 28), Err(()));
+    }
+
+    #[test]
+    fn test_float_constants() {
+        assert_eq!(Float::F32.infinity(), f32::INFINITY.to_bits() as u64);
+        assert_eq!(Float::F64.infinity(), f64::INFINITY.to_bits());
+        assert_eq!(Float::F32.sign(), (-0f32).to_bits() as u64);
+        assert!(f32::from_bits(Float::F32.nan() as u32).is_nan());
+        assert!(f64::from_bits(Float::F64.nan()).is_nan());
+    }
+}
@@ -1,24 +1,26 @@
 //! Code generation implementation.
 use crate::{
-    backtrace::Backtrace,
     control::ControlStack,
+    ir::Body,
     jump::JumpTable,
     local::{LocalSlot, LocalSlotType, Locals},
     masm::MacroAssembler,
     validator::ValidateThenVisit,
-    wasm::Env,
-    Buffer, Error, Result,
+    wasm::{Env, ToLSBytes},
+    Buffer, Error, Result, Source, StackLocals, Widths,
 };
 use opcodes::ShangHai as OpCode;
-use wasmparser::{FuncType, FuncValidator, LocalsReader, OperatorsReader, ValidatorResources};
-use zabi::Abi;
+use std::ops::Range;
+use wasmparser::{
+    FuncType, FuncValidator, LocalsReader, Operator, OperatorsReader, ValidatorResources,
+    VisitOperator,
+};
+use zabi::{Abi, Param};
 
 /// The code generation abstraction.
 pub struct Function {
     /// Abi of this function,
     pub abi: Option<Abi>,
-    /// The backtrace.
-    pub backtrace: Backtrace,
     /// Control stack frames.
     pub control: ControlStack,
     /// WASM environment.
@@ -33,6 +35,13 @@ pub struct Function {
     pub ty: FuncType,
     /// If this function is the main function.
     pub is_main: bool,
+    /// The widths of the values on the operand stack.
+    pub widths: Widths,
+    /// The locals kept on the stack.
+    pub stack: StackLocals,
+    /// If jump to the revert shared in the code section for the
+    /// checks of the external functions instead of inlining it.
+    pub shared_reverts: bool,
 }
 
 impl Function {
@@ -41,7 +50,6 @@ impl Function {
         let is_external = abi.is_some();
         let mut codegen = Self {
             abi,
-            backtrace: Backtrace::default(),
             control: ControlStack::default(),
             env,
             ty,
@@ -49,6 +57,9 @@ impl Function {
             masm: Default::default(),
             table: Default::default(),
             is_main,
+            widths: Default::default(),
+            stack: Default::default(),
+            shared_reverts: false,
         };
 
         if is_main {
@@ -72,6 +83,108 @@ impl Function {
         Ok(codegen)
     }
 
+    /// Revert if the call carries ether, this should be called right
+    /// after creating the non-payable external functions.
+    pub fn reject_callvalue(&mut self) -> Result<()> {
+        self.masm._callvalue()?;
+        self.masm._iszero()?;
+        self.revert_unless()
+    }
+
+    /// Revert on the malformed calldata, this should be called right
+    /// after creating the external functions.
+    ///
+    /// The calldata has to cover all of the parameters, and the
+    /// parameters narrower than 256 bits have to be padded, with zeros
+    /// for the unsigned ones and with the sign bits for the signed ones.
+    ///
+    /// The parameters are masked to their widths when they are loaded
+    /// anyway, so the unchecked calldata is cleaned without reverting.
+    pub fn validate_calldata(&mut self) -> Result<()> {
+        let Some(abi) = self.abi.as_ref() else {
+            return Ok(());
+        };
+
+        let params = abi
+            .inputs
+            .iter()
+            .map(|arg| arg.ty.clone())
+            .collect::<Vec<_>>();
+        if params.is_empty() {
+            return Ok(());
+        }
+
+        // STACK: [CALLDATASIZE < size == 0]
+        self.masm.push(&(4 + params.len() * 32).to_ls_bytes())?;
+        self.masm._calldatasize()?;
+        self.masm.asm._lt()?;
+        self.masm._iszero()?;
+        self.revert_unless()?;
+
+        for (index, param) in params.iter().enumerate() {
+            let offset = (4 + index * 32).to_ls_bytes();
+            match param {
+                Param::Int8 | Param::Int16 | Param::Int32 | Param::Int64 => {
+                    let bytes = match param {
+                        Param::Int8 => 1,
+                        Param::Int16 => 2,
+                        Param::Int32 => 4,
+                        _ => 8,
+                    };
+
+                    // STACK: [word == signextend(bytes - 1, word)]
+                    self.masm.push(&offset)?;
+                    self.masm._calldataload()?;
+                    self.masm._dup1()?;
+                    self.masm._sign_extend(bytes)?;
+                    self.masm._eq()?;
+                }
+                Param::UInt8
+                | Param::UInt16
+                | Param::UInt32
+                | Param::UInt64
+                | Param::Bool
+                | Param::Address => {
+                    let bits: usize = match param {
+                        Param::UInt8 => 8,
+                        Param::UInt16 => 16,
+                        Param::UInt32 => 32,
+                        Param::UInt64 => 64,
+                        Param::Bool => 1,
+                        _ => 160,
+                    };
+
+                    // STACK: [word >> bits == 0]
+                    self.masm.push(&offset)?;
+                    self.masm._calldataload()?;
+                    self.masm.push(&bits.to_ls_bytes())?;
+                    self.masm._shr()?;
+                    self.masm._iszero()?;
+                }
+                _ => continue,
+            }
+
+            self.revert_unless()?;
+        }
+
+        Ok(())
+    }
+
+    /// Revert with nothing if the condition on the stack is zero.
+    fn revert_unless(&mut self) -> Result<()> {
+        if self.shared_reverts {
+            self.masm.shared_revert_unless(&mut self.table)
+        } else {
+            self.masm.revert_unless(&mut self.table)
+        }
+    }
+
+    /// Allocate the locals kept on the stack, this should be called
+    /// before emitting the locals.
+    pub fn alloc_locals(&mut self, ops: &[Operator]) {
+        self.stack = StackLocals::new(ops, self.ty.params().len() as u32, &self.env);
+    }
+
     /// Emit function locals
     ///
     /// 1. the function parameters.
@@ -110,6 +223,22 @@ impl Function {
         }
 
         tracing::trace!("{:?}", self.locals);
+
+        // The memory slots of internal functions are reused across calls,
+        // reset the body locals since they are zero-initialized in WASM,
+        // the locals kept on the stack are always set before getting.
+        if !self.is_main && self.abi.is_none() {
+            for index in self.ty.params().len()..self.locals.len() {
+                if self.stack.contains(index as u32) {
+                    continue;
+                }
+
+                self.masm._push0()?;
+                self.masm.push(&self.env.alloc(index as u32))?;
+                self.masm._mstore()?;
+            }
+        }
+
         Ok(())
     }
 
@@ -120,11 +249,56 @@ impl Function {
         validator: &mut FuncValidator<ValidatorResources>,
     ) -> Result<()> {
         while !ops.eof() {
+            // the source is recorded before visiting, since the host
+            // calls read their data from the records of the operators.
             let offset = ops.original_position();
+            let mut next = ops.clone();
+            next.read()?;
+            self.record(self.masm.pc(), offset..next.original_position());
+
             let mut validate_then_visit = ValidateThenVisit(validator.visitor(offset), self);
             ops.visit_operator(&mut validate_then_visit)???;
         }
 
+        self.emit_return()
+    }
+
+    /// Emit the operators of the optimized function body, the
+    /// original operators are validated before emitting.
+    pub fn emit_body(
+        &mut self,
+        ops: &mut OperatorsReader<'_>,
+        body: &Body<'_>,
+        validator: &mut FuncValidator<ValidatorResources>,
+    ) -> Result<()> {
+        while !ops.eof() {
+            let (op, offset) = ops.read_with_offset()?;
+            validator.op(offset, &op)?;
+        }
+
+        for (op, range) in body.ops().iter().zip(body.ranges()) {
+            self.record(self.masm.pc(), range.clone());
+            self.wrapping(op, |f| f.visit_operator(op))?;
+        }
+
+        self.emit_return()
+    }
+
+    /// Record the source of the code emitted from the program counter
+    /// for the operator in the byte range.
+    fn record(&mut self, pc: u16, range: Range<usize>) {
+        let source = Source {
+            func: self.env.index.unwrap_or_default(),
+            offset: range.start,
+            len: range.len(),
+        };
+
+        self.table.srcmap.record(pc, Some(source));
+    }
+
+    /// Emit the return of the external functions and the main
+    /// function if it is not emitted by the operators.
+    fn emit_return(&mut self) -> Result<()> {
         if (self.abi.is_some() || self.is_main)
             && self.masm.buffer().last() != Some(&OpCode::RETURN.into())
         {
@@ -134,6 +308,38 @@ impl Function {
         Ok(())
     }
 
+    /// Visit an operator with its operand and result wrapped
+    /// to the width of their types if they may overflow, the
+    /// operands of the signed operators are sign-extended if
+    /// they may be negative.
+    pub fn wrapping(
+        &mut self,
+        operator: &Operator,
+        visit: impl FnOnce(&mut Self) -> Result<()>,
+    ) -> Result<()> {
+        let wrap = self.widths.visit(operator);
+        if let Some(bits) = wrap.rhs {
+            self.masm._sign_extend(bits / 8)?;
+        }
+
+        if let Some(bits) = wrap.lhs {
+            self.masm._swap1()?;
+            self.masm._sign_extend(bits / 8)?;
+            self.masm._swap1()?;
+        }
+
+        if let Some(bits) = wrap.operand {
+            self.masm._wrap(bits)?;
+        }
+
+        visit(self)?;
+        if let Some(bits) = wrap.result {
+            self.masm._wrap(bits)?;
+        }
+
+        Ok(())
+    }
+
     /// Finish code generation.
     pub fn finish(self, jump_table: &mut JumpTable, pc: u16) -> Result<Buffer> {
         let sp = self.masm.sp();
@@ -141,7 +347,10 @@ impl Function {
             return Err(Error::StackNotBalanced(sp));
         }
 
-        jump_table.merge(self.table, pc)?;
+        // the code behind this function has no source
+        let mut table = self.table;
+        table.srcmap.record(self.masm.pc(), None);
+        jump_table.merge(table, pc)?;
         Ok(self.masm.buffer().into())
     }
 }
@@ -2,17 +2,23 @@
 //!
 //! - CONSTRUCTOR
 //! - DISPATCHER
+//! - PREAMBLE
 //! - FUNCTION
+//! - FLOAT
 //! - CODE
 
 mod code;
 mod constructor;
 mod dispatcher;
+mod float;
 mod function;
+mod preamble;
 
 pub use self::{
     code::{Code, ExtFunc},
     constructor::{Constructor, InitStorage},
-    dispatcher::Dispatcher,
+    dispatcher::{Dispatcher, LINEAR_SELECTORS},
+    float::{Float, Routine, FLOAT_REGISTERS},
     function::Function,
+    preamble::Preamble,
 };
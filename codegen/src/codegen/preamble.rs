@@ -0,0 +1,153 @@
+//! Runtime preamble.
+
+use crate::{
+    wasm::{Env, ToLSBytes},
+    Buffer, JumpTable, MacroAssembler, Result,
+};
+use zabi::Abi;
+
+/// Runtime preamble, executed before the dispatcher
+/// or the main function, or the constructor function
+/// in the init code.
+#[derive(Default, Debug, Clone)]
+pub struct Preamble {
+    /// Code generator.
+    masm: MacroAssembler,
+}
+
+impl Preamble {
+    /// Initialize the memory slots of the globals.
+    ///
+    /// The zero values are skipped since the memory
+    /// is zeroed at the beginning of the execution.
+    pub fn globals(&mut self, env: &Env) -> Result<()> {
+        tracing::debug!("Initializing globals in preamble ...");
+        for (index, init) in env.globals.iter() {
+            if init.iter().all(|byte| *byte == 0) {
+                continue;
+            }
+
+            self.masm.push(init)?;
+            self.masm.push(&env.global(*index))?;
+            self.masm._mstore()?;
+        }
+
+        Ok(())
+    }
+
+    /// Initialize the page count of the linear memory.
+    pub fn pages(&mut self, env: &Env) -> Result<()> {
+        if env.initial_pages == 0 {
+            return Ok(());
+        }
+
+        self.masm.push(&env.initial_pages.to_ls_bytes())?;
+        self.masm.push(&(env.pages * 0x20).to_ls_bytes())?;
+        self.masm._mstore()
+    }
+
+    /// Copy the active data segments to the linear memory.
+    ///
+    /// The segments are appended to the code section and copied with
+    /// `CODECOPY`, they are skipped if none of the functions reads the
+    /// linear memory, e.g. the messages of logs consumed at compile time.
+    pub fn data(&mut self, env: &Env, table: &mut JumpTable) -> Result<()> {
+        if !env.reads_memory {
+            return Ok(());
+        }
+
+        tracing::debug!("Copying data segments in preamble ...");
+        for (offset, data) in env.data.iter() {
+            if data.is_empty() {
+                continue;
+            }
+
+            // STACK: [size, code offset, memory offset]
+            self.masm.push(&data.len().to_ls_bytes())?;
+            table.data(self.masm.pc(), data.clone());
+            self.masm.increment_sp(1)?;
+            self.masm
+                .push(&(env.memory + *offset as u32).to_ls_bytes())?;
+            self.masm._codecopy()?;
+        }
+
+        Ok(())
+    }
+
+    /// Copy the active data segments to the linear memory in the
+    /// init code.
+    ///
+    /// The data blobs are copied from the code section of the runtime
+    /// bytecode `runtime`, which is followed by the ABI-encoded arguments
+    /// of `args` bytes in the creation bytecode, so they are located
+    /// backwards from `CODESIZE` like the arguments.
+    pub fn init_data(
+        &mut self,
+        env: &Env,
+        runtime: &JumpTable,
+        len: usize,
+        args: usize,
+    ) -> Result<()> {
+        for (offset, data) in env.data.iter() {
+            let Some(pc) = runtime.code().offset_of_data(data) else {
+                continue;
+            };
+
+            // STACK: [size, CODESIZE - back, memory offset]
+            self.masm.push(&data.len().to_ls_bytes())?;
+            self.masm.push(&(args + len - pc as usize).to_ls_bytes())?;
+            self.masm._codesize()?;
+            self.masm.asm._sub()?;
+            self.masm
+                .push(&(env.memory + *offset as u32).to_ls_bytes())?;
+            self.masm._codecopy()?;
+        }
+
+        Ok(())
+    }
+
+    /// Call the constructor function `func` in the init code.
+    ///
+    /// The ABI-encoded arguments appended to the creation bytecode are
+    /// copied to the memory slots of the parameters, then the function
+    /// is called like the internal functions. Returns the program
+    /// counter of the return address, which should be labeled to the
+    /// end of the init code.
+    pub fn constructor(
+        &mut self,
+        env: &Env,
+        abi: &Abi,
+        func: u32,
+        table: &mut JumpTable,
+    ) -> Result<u16> {
+        if !abi.state_mutability.is_payable() {
+            self.masm.reject_callvalue(table)?;
+        }
+
+        if !abi.inputs.is_empty() {
+            let reserved = *env.slots.get(&func).unwrap_or(&0);
+
+            // STACK: [size, CODESIZE - size, slot]
+            self.masm.push(&(abi.inputs.len() * 0x20).to_ls_bytes())?;
+            self.masm._dup1()?;
+            self.masm._codesize()?;
+            self.masm.asm._sub()?;
+            self.masm.push(&(reserved * 0x20).to_ls_bytes())?;
+            self.masm._codecopy()?;
+        }
+
+        let ret = self.masm.pc();
+        self.masm.increment_sp(1)?;
+        self.masm._jumpdest()?;
+
+        table.call(self.masm.pc(), func);
+        self.masm.increment_sp(1)?;
+        self.masm._jump()?;
+        Ok(ret)
+    }
+
+    /// Returns the preamble code.
+    pub fn finish(&self) -> Buffer {
+        self.masm.buffer().into()
+    }
+}
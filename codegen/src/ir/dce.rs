@@ -0,0 +1,50 @@
+//! Dead code elimination.
+
+use std::ops::Range;
+use wasmparser::Operator;
+
+/// Remove the unreachable operators after the unconditional branches
+/// till the end of their constructs, returns if any operator is removed.
+pub fn eliminate(ops: &mut Vec<(Operator<'_>, Range<usize>)>) -> bool {
+    let mut live = Vec::with_capacity(ops.len());
+    let mut changed = false;
+
+    // The nested constructs in the unreachable code.
+    let mut skip: Option<usize> = None;
+    for (op, range) in ops.drain(..) {
+        if let Some(depth) = skip.as_mut() {
+            match op {
+                Operator::Else | Operator::End if *depth == 0 => skip = None,
+                Operator::Block { .. } | Operator::Loop { .. } | Operator::If { .. } => {
+                    *depth += 1;
+                    changed = true;
+                    continue;
+                }
+                Operator::End => {
+                    *depth -= 1;
+                    changed = true;
+                    continue;
+                }
+                _ => {
+                    changed = true;
+                    continue;
+                }
+            }
+        }
+
+        if matches!(
+            op,
+            Operator::Br { .. }
+                | Operator::BrTable { .. }
+                | Operator::Return
+                | Operator::Unreachable
+        ) {
+            skip = Some(0);
+        }
+
+        live.push((op, range));
+    }
+
+    *ops = live;
+    changed
+}
@@ -0,0 +1,302 @@
+//! Constant folding.
+
+use std::ops::Range;
+use wasmparser::{BlockType, Operator};
+
+/// The arms kept of an `if` construct.
+enum Arms {
+    /// The condition is unknown, both of the arms are kept.
+    Both,
+    /// The condition is non-zero, only the `then` arm is kept.
+    Then,
+    /// The condition is zero, only the `else` arm is kept.
+    Else,
+}
+
+/// Fold the operators with constant operands, returns if any
+/// operator is folded.
+///
+/// The operators which may trap with the constant operands, like
+/// dividing by zero, are kept for trapping at runtime. The `if`
+/// constructs with constant conditions are replaced with blocks
+/// of the taken arms, and the empty blocks are removed.
+pub fn fold(ops: &mut Vec<(Operator<'_>, Range<usize>)>) -> bool {
+    let mut folded = Vec::with_capacity(ops.len());
+    let mut frames = Vec::new();
+    let mut changed = false;
+
+    // The nested constructs in the arm being skipped.
+    let mut skip: Option<usize> = None;
+    for (op, range) in ops.drain(..) {
+        if let Some(depth) = skip.as_mut() {
+            match op {
+                Operator::Block { .. } | Operator::Loop { .. } | Operator::If { .. } => *depth += 1,
+                Operator::Else if *depth == 0 => skip = None,
+                Operator::End if *depth == 0 => {
+                    skip = None;
+                    frames.pop();
+                    changed |= end(&mut folded, (op, range));
+                }
+                Operator::End => *depth -= 1,
+                _ => {}
+            }
+
+            continue;
+        }
+
+        match op {
+            Operator::Block { .. } | Operator::Loop { .. } => {
+                frames.push(Arms::Both);
+                folded.push((op, range));
+            }
+            Operator::If { blockty } => {
+                let Some(cond) = pop_i32(&mut folded) else {
+                    frames.push(Arms::Both);
+                    folded.push((op, range));
+                    continue;
+                };
+
+                changed = true;
+                folded.push((Operator::Block { blockty }, range));
+                if cond != 0 {
+                    frames.push(Arms::Then);
+                } else {
+                    frames.push(Arms::Else);
+                    skip = Some(0);
+                }
+            }
+            Operator::Else if matches!(frames.last(), Some(Arms::Then)) => skip = Some(0),
+            Operator::End => {
+                frames.pop();
+                changed |= end(&mut folded, (op, range));
+            }
+            Operator::BrIf { relative_depth } => match pop_i32(&mut folded) {
+                Some(cond) => {
+                    changed = true;
+                    if cond != 0 {
+                        folded.push((Operator::Br { relative_depth }, range));
+                    }
+                }
+                None => folded.push((op, range)),
+            },
+            Operator::BrTable { ref targets } => match pop_i32(&mut folded) {
+                Some(index) => {
+                    changed = true;
+                    let relative_depth = targets
+                        .targets()
+                        .nth(index as u32 as usize)
+                        .and_then(Result::ok)
+                        .unwrap_or(targets.default());
+                    folded.push((Operator::Br { relative_depth }, range));
+                }
+                None => folded.push((op, range)),
+            },
+            Operator::Drop
+                if matches!(
+                    folded.last(),
+                    Some((Operator::I32Const { .. } | Operator::I64Const { .. }, _))
+                ) =>
+            {
+                changed = true;
+                folded.pop();
+            }
+            op => {
+                let len = folded.len();
+                let result = if len >= 2 {
+                    binary(&op, &folded[len - 2].0, &folded[len - 1].0).map(|result| (2, result))
+                } else {
+                    None
+                }
+                .or_else(|| {
+                    let (operand, _) = folded.last()?;
+                    unary(&op, operand).map(|result| (1, result))
+                });
+
+                match result {
+                    Some((operands, result)) => {
+                        changed = true;
+                        folded.truncate(len - operands);
+                        folded.push((result, range));
+                    }
+                    None => folded.push((op, range)),
+                }
+            }
+        }
+    }
+
+    *ops = folded;
+    changed
+}
+
+/// Close the construct at the end of the operators, the empty
+/// constructs without parameters and results are removed.
+fn end<'a>(ops: &mut Vec<(Operator<'a>, Range<usize>)>, op: (Operator<'a>, Range<usize>)) -> bool {
+    if matches!(
+        ops.last(),
+        Some((
+            Operator::Block {
+                blockty: BlockType::Empty
+            } | Operator::Loop {
+                blockty: BlockType::Empty
+            },
+            _
+        ))
+    ) {
+        ops.pop();
+        return true;
+    }
+
+    ops.push(op);
+    false
+}
+
+/// Pop the `i32.const` at the end of the operators.
+fn pop_i32(ops: &mut Vec<(Operator<'_>, Range<usize>)>) -> Option<i32> {
+    let Some((Operator::I32Const { value }, _)) = ops.last() else {
+        return None;
+    };
+
+    let value = *value;
+    ops.pop();
+    Some(value)
+}
+
+/// Evaluate an unary operator with the constant operand.
+fn unary<'a>(op: &Operator, operand: &Operator) -> Option<Operator<'a>> {
+    use Operator::*;
+
+    Some(match (op, operand) {
+        (I32Eqz, &I32Const { value }) => I32Const {
+            value: (value == 0) as i32,
+        },
+        (I32Clz, &I32Const { value }) => I32Const {
+            value: value.leading_zeros() as i32,
+        },
+        (I32Ctz, &I32Const { value }) => I32Const {
+            value: value.trailing_zeros() as i32,
+        },
+        (I32Popcnt, &I32Const { value }) => I32Const {
+            value: value.count_ones() as i32,
+        },
+        (I32Extend8S, &I32Const { value }) => I32Const {
+            value: value as i8 as i32,
+        },
+        (I32Extend16S, &I32Const { value }) => I32Const {
+            value: value as i16 as i32,
+        },
+        (I64ExtendI32S, &I32Const { value }) => I64Const {
+            value: value as i64,
+        },
+        (I64ExtendI32U, &I32Const { value }) => I64Const {
+            value: value as u32 as i64,
+        },
+        (I64Eqz, &I64Const { value }) => I32Const {
+            value: (value == 0) as i32,
+        },
+        (I64Clz, &I64Const { value }) => I64Const {
+            value: value.leading_zeros() as i64,
+        },
+        (I64Ctz, &I64Const { value }) => I64Const {
+            value: value.trailing_zeros() as i64,
+        },
+        (I64Popcnt, &I64Const { value }) => I64Const {
+            value: value.count_ones() as i64,
+        },
+        (I64Extend8S, &I64Const { value }) => I64Const {
+            value: value as i8 as i64,
+        },
+        (I64Extend16S, &I64Const { value }) => I64Const {
+            value: value as i16 as i64,
+        },
+        (I64Extend32S, &I64Const { value }) => I64Const {
+            value: value as i32 as i64,
+        },
+        (I32WrapI64, &I64Const { value }) => I32Const {
+            value: value as i32,
+        },
+        _ => return None,
+    })
+}
+
+/// Evaluate a binary operator with the constant operands.
+fn binary<'a>(op: &Operator, lhs: &Operator, rhs: &Operator) -> Option<Operator<'a>> {
+    use Operator::*;
+
+    let cmp = |value: bool| I32Const {
+        value: value as i32,
+    };
+
+    Some(match (lhs, rhs) {
+        (&I32Const { value: a }, &I32Const { value: b }) => {
+            let (ua, ub) = (a as u32, b as u32);
+            match op {
+                I32Eq => cmp(a == b),
+                I32Ne => cmp(a != b),
+                I32LtS => cmp(a < b),
+                I32LtU => cmp(ua < ub),
+                I32GtS => cmp(a > b),
+                I32GtU => cmp(ua > ub),
+                I32LeS => cmp(a <= b),
+                I32LeU => cmp(ua <= ub),
+                I32GeS => cmp(a >= b),
+                I32GeU => cmp(ua >= ub),
+                op => I32Const {
+                    value: match op {
+                        I32Add => a.wrapping_add(b),
+                        I32Sub => a.wrapping_sub(b),
+                        I32Mul => a.wrapping_mul(b),
+                        I32DivS if b != 0 && !(a == i32::MIN && b == -1) => a / b,
+                        I32DivU if b != 0 => (ua / ub) as i32,
+                        I32RemS if b != 0 => a.wrapping_rem(b),
+                        I32RemU if b != 0 => (ua % ub) as i32,
+                        I32And => a & b,
+                        I32Or => a | b,
+                        I32Xor => a ^ b,
+                        I32Shl => a.wrapping_shl(ub),
+                        I32ShrS => a.wrapping_shr(ub),
+                        I32ShrU => ua.wrapping_shr(ub) as i32,
+                        I32Rotl => ua.rotate_left(ub % 32) as i32,
+                        I32Rotr => ua.rotate_right(ub % 32) as i32,
+                        _ => return None,
+                    },
+                },
+            }
+        }
+        (&I64Const { value: a }, &I64Const { value: b }) => {
+            let (ua, ub) = (a as u64, b as u64);
+            match op {
+                I64Eq => cmp(a == b),
+                I64Ne => cmp(a != b),
+                I64LtS => cmp(a < b),
+                I64LtU => cmp(ua < ub),
+                I64GtS => cmp(a > b),
+                I64GtU => cmp(ua > ub),
+                I64LeS => cmp(a <= b),
+                I64LeU => cmp(ua <= ub),
+                I64GeS => cmp(a >= b),
+                I64GeU => cmp(ua >= ub),
+                op => I64Const {
+                    value: match op {
+                        I64Add => a.wrapping_add(b),
+                        I64Sub => a.wrapping_sub(b),
+                        I64Mul => a.wrapping_mul(b),
+                        I64DivS if b != 0 && !(a == i64::MIN && b == -1) => a / b,
+                        I64DivU if b != 0 => (ua / ub) as i64,
+                        I64RemS if b != 0 => a.wrapping_rem(b),
+                        I64RemU if b != 0 => (ua % ub) as i64,
+                        I64And => a & b,
+                        I64Or => a | b,
+                        I64Xor => a ^ b,
+                        I64Shl => a.wrapping_shl(ub as u32),
+                        I64ShrS => a.wrapping_shr(ub as u32),
+                        I64ShrU => ua.wrapping_shr(ub as u32) as i64,
+                        I64Rotl => ua.rotate_left((ub % 64) as u32) as i64,
+                        I64Rotr => ua.rotate_right((ub % 64) as u32) as i64,
+                        _ => return None,
+                    },
+                },
+            }
+        }
+        _ => return None,
+    })
+}
@@ -0,0 +1,222 @@
+//! Pre-codegen IR.
+//!
+//! The operators of a function body are collected before the code
+//! generation and simplified in place, the constant operators are
+//! folded and the unreachable code is removed. The calls left in the
+//! simplified bodies form the call graph for stripping the functions
+//! never reached from the entries of the contract.
+
+use crate::{
+    wasm::{CallGraph, Env},
+    Result,
+};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    mem,
+    ops::Range,
+};
+use wasmparser::{FunctionBody, Operator};
+
+mod dce;
+mod fold;
+
+/// Operators of a function body.
+#[derive(Clone, Debug, Default)]
+pub struct Body<'a> {
+    ops: Vec<Operator<'a>>,
+    /// Byte ranges of the operators in the WASM module, the folded
+    /// operators take the ranges of the operators folding them.
+    ranges: Vec<Range<usize>>,
+}
+
+impl<'a> Body<'a> {
+    /// Collect the operators of the function body.
+    pub fn new(body: &FunctionBody<'a>) -> Result<Self> {
+        let mut reader = body.get_operators_reader()?;
+        let (mut ops, mut ranges) = (Vec::new(), Vec::new());
+        while !reader.eof() {
+            let (op, offset) = reader.read_with_offset()?;
+            ops.push(op);
+            ranges.push(offset..reader.original_position());
+        }
+
+        Ok(Self { ops, ranges })
+    }
+
+    /// The operators of the body.
+    pub fn ops(&self) -> &[Operator<'a>] {
+        &self.ops
+    }
+
+    /// The byte ranges of the operators in the WASM module.
+    pub fn ranges(&self) -> &[Range<usize>] {
+        &self.ranges
+    }
+
+    /// Fold the constants and remove the unreachable code until
+    /// nothing changes.
+    pub fn optimize(&mut self) {
+        let mut ops = mem::take(&mut self.ops)
+            .into_iter()
+            .zip(mem::take(&mut self.ranges))
+            .collect();
+
+        loop {
+            let folded = fold::fold(&mut ops);
+            let eliminated = dce::eliminate(&mut ops);
+            if !folded && !eliminated {
+                break;
+            }
+        }
+
+        (self.ops, self.ranges) = ops.into_iter().unzip();
+    }
+
+    /// The defined functions called by the body.
+    pub fn callees(&self, env: &Env) -> BTreeSet<u32> {
+        let mut callees = BTreeSet::new();
+        for op in self.ops.iter() {
+            match op {
+                Operator::Call { function_index } if !env.imports.contains_key(function_index) => {
+                    callees.insert(*function_index);
+                }
+                Operator::CallIndirect {
+                    type_index,
+                    table_index,
+                    ..
+                } => {
+                    callees.extend(
+                        env.indirect_callees(*type_index, *table_index)
+                            .into_iter()
+                            .map(|(_, func)| func),
+                    );
+                }
+                _ => {}
+            }
+        }
+
+        callees
+    }
+}
+
+/// The functions reached from the entries through the calls of the bodies.
+pub fn reachable(
+    bodies: &BTreeMap<u32, Body<'_>>,
+    entries: impl IntoIterator<Item = u32>,
+    env: &Env,
+) -> BTreeSet<u32> {
+    let mut calls = CallGraph::default();
+    for (index, body) in bodies.iter() {
+        for callee in body.callees(env) {
+            calls.call(*index, callee);
+        }
+    }
+
+    calls.reachable(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Operator::*;
+
+    /// Operators do not implement `PartialEq`, so compare their debug
+    /// representations.
+    fn assert_optimized(ops: Vec<Operator<'static>>, expected: Vec<Operator<'static>>) {
+        let mut body = Body {
+            ranges: vec![0..0; ops.len()],
+            ops,
+        };
+        body.optimize();
+        assert_eq!(format!("{:?}", body.ops), format!("{expected:?}"));
+    }
+
+    #[test]
+    fn test_fold_constants() {
+        assert_optimized(
+            vec![
+                I32Const { value: 1 },
+                I32Const { value: 2 },
+                I32Add,
+                I32Const { value: 3 },
+                I32Mul,
+                End,
+            ],
+            vec![I32Const { value: 9 }, End],
+        );
+
+        assert_optimized(
+            vec![
+                I64Const { value: -1 },
+                I64Const { value: 60 },
+                I64ShrU,
+                I64Const { value: 15 },
+                I64Eq,
+                I32Eqz,
+                End,
+            ],
+            vec![I32Const { value: 0 }, End],
+        );
+
+        // traps are kept for the runtime
+        assert_optimized(
+            vec![I32Const { value: 1 }, I32Const { value: 0 }, I32DivU, End],
+            vec![I32Const { value: 1 }, I32Const { value: 0 }, I32DivU, End],
+        );
+    }
+
+    #[test]
+    fn test_fold_branches() {
+        let blockty = wasmparser::BlockType::Empty;
+        assert_optimized(
+            vec![
+                I32Const { value: 1 },
+                If { blockty },
+                Call { function_index: 1 },
+                Else,
+                Call { function_index: 2 },
+                End,
+                I32Const { value: 0 },
+                BrIf { relative_depth: 0 },
+                End,
+            ],
+            vec![Block { blockty }, Call { function_index: 1 }, End, End],
+        );
+
+        // the empty block of the skipped arm is removed
+        assert_optimized(
+            vec![
+                I32Const { value: 0 },
+                If { blockty },
+                Call { function_index: 1 },
+                End,
+                End,
+            ],
+            vec![End],
+        );
+
+        // the unreachable code after the branch is removed
+        assert_optimized(
+            vec![
+                Block { blockty },
+                I32Const { value: 1 },
+                BrIf { relative_depth: 0 },
+                Call { function_index: 1 },
+                Loop { blockty },
+                Br { relative_depth: 0 },
+                End,
+                End,
+                Return,
+                Call { function_index: 2 },
+                End,
+            ],
+            vec![
+                Block { blockty },
+                Br { relative_depth: 0 },
+                End,
+                Return,
+                End,
+            ],
+        );
+    }
+}
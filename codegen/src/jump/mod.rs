@@ -8,6 +8,7 @@ use crate::codegen::ExtFunc;
 use core::fmt::Display;
 pub use table::JumpTable;
 
+mod optimize;
 mod pc;
 mod relocate;
 mod table;
@@ -18,18 +19,25 @@ mod target;
 pub enum Jump {
     /// Jump to a specific label, which corresponds to the original program counter.
     Label(u16),
+    /// Jump to a label from an entry of a program counter table, which is
+    /// always pushed with `PUSH2` for the fixed size of the entries.
+    Entry(u16),
     /// Jump to a function identified by its index.
     Func(u32),
     /// Jump to an external function.
     ExtFunc(ExtFunc),
+    /// Offset of a data blob in the code section.
+    Data(Vec<u8>),
 }
 
 impl Display for Jump {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Jump::Label(offset) => write!(f, "Label(0x{offset:x})"),
+            Jump::Entry(offset) => write!(f, "Entry(0x{offset:x})"),
             Jump::Func(index) => write!(f, "Func({index})"),
             Jump::ExtFunc(_) => write!(f, "ExtFunc"),
+            Jump::Data(_) => write!(f, "Data"),
         }
     }
 }
@@ -37,7 +45,17 @@ impl Display for Jump {
 impl Jump {
     /// Checks if the target is a label.
     pub fn is_label(&self) -> bool {
-        matches!(self, Jump::Label { .. })
+        matches!(self, Jump::Label { .. } | Jump::Entry { .. })
+    }
+
+    /// Checks if the target is in the code section.
+    pub fn is_code(&self) -> bool {
+        matches!(self, Jump::ExtFunc { .. } | Jump::Data { .. })
+    }
+
+    /// Checks if the target is pushed with `PUSH2` regardless of its size.
+    pub fn is_wide(&self) -> bool {
+        matches!(self, Jump::Entry { .. })
     }
 
     /// Checks if the target is a function call.
@@ -48,7 +66,10 @@ impl Jump {
 
 #[cfg(test)]
 mod tests {
-    use crate::jump::{Jump, JumpTable};
+    use crate::{
+        codegen::ExtFunc,
+        jump::{Jump, JumpTable},
+    };
     use smallvec::smallvec;
 
     #[allow(unused)]
@@ -167,6 +188,50 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_entry_wide_target() -> anyhow::Result<()> {
+        let mut table = JumpTable::default();
+
+        // Entries are pushed with PUSH2 even if the target fits in a byte.
+        table.register(0x10, Jump::Entry(0x20));
+        table.register(0x18, Jump::Label(0x20));
+
+        let mut buffer = smallvec![0; table.max_target() as usize];
+        table.relocate(&mut buffer)?;
+
+        assert_eq!(buffer[0x10], 0x61); // PUSH2
+        assert_eq!(buffer[0x11], 0x00);
+        assert_eq!(buffer[0x12], 0x25);
+        assert_eq!(buffer[0x1b], 0x60); // PUSH1
+        assert_eq!(buffer[0x1c], 0x25);
+        Ok(())
+    }
+
+    #[test]
+    fn test_data_after_funcs() -> anyhow::Result<()> {
+        let mut table = JumpTable::default();
+        let func = ExtFunc {
+            stack_in: 0,
+            stack_out: 0,
+            bytecode: vec![0x5b, 0x00],
+        };
+
+        // The data is placed after the function registered later.
+        table.data(0x00, vec![0x61]);
+        table.ext(0x01, func);
+        table.data(0x02, vec![0x61]);
+
+        let mut buffer = smallvec![0; 0x04];
+        table.code_offset(buffer.len() as u16);
+        table.relocate(&mut buffer)?;
+
+        assert_eq!(
+            buffer.as_slice(),
+            &[0x60, 0x0c, 0x00, 0x60, 0x0a, 0x00, 0x60, 0x0c, 0x00, 0x00, 0x5b, 0x00, 0x61]
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_sequential_large_jumps() -> anyhow::Result<()> {
         let mut table = JumpTable::default();
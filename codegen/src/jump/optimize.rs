@@ -0,0 +1,291 @@
+//! Peephole optimizer.
+//!
+//! This module rewrites short instruction sequences of the emitted bytecode
+//! before the relocation of the jump table. The program counters of the
+//! jumps, the labels and the functions behind a rewritten sequence are
+//! shifted with the removed bytes, and a sequence is only rewritten if no
+//! jump refers to the inside of it, so the JUMPDESTs stay where the jumps
+//! expect them.
+
+use crate::{
+    jump::{Jump, JumpTable},
+    Buffer,
+};
+use opcodes::{disassemble, Cancun as OpCode, Group, Instruction, OpCode as _};
+use std::collections::BTreeSet;
+
+/// Predicates of the instructions for the peephole rewrites.
+trait Peephole {
+    /// If the instruction is the opcode.
+    fn is(&self, op: OpCode) -> bool;
+
+    /// If the instruction is in the group.
+    fn in_group(&self, group: Group) -> bool;
+
+    /// If the instruction pushes zero.
+    fn is_push_zero(&self) -> bool;
+}
+
+impl Peephole for Instruction {
+    fn is(&self, op: OpCode) -> bool {
+        self.opcode == Some(op)
+    }
+
+    fn in_group(&self, group: Group) -> bool {
+        self.opcode.is_some_and(|op| op.group() == group)
+    }
+
+    fn is_push_zero(&self) -> bool {
+        self.in_group(Group::Push) && self.immediate.iter().all(|b| *b == 0)
+    }
+}
+
+/// A peephole rewrite.
+#[derive(Debug, PartialEq, Eq)]
+enum Rewrite {
+    /// Remove the instructions between the program counters.
+    Remove(usize, usize),
+    /// Remove the jump to the next instruction.
+    Jump(usize),
+    /// Replace the conditional jump to the next instruction with
+    /// `POP`, which drops the condition.
+    JumpI(usize),
+}
+
+impl JumpTable {
+    /// Optimize the bytecode with peephole rewrites.
+    ///
+    /// - `PUSHn x POP`, `DUPn POP` and `SWAPn SWAPn` are removed.
+    /// - Pushing zero before `ADD`, `OR` or `XOR` is removed.
+    /// - `ISZERO ISZERO` before `JUMPI` is removed if the target of the
+    ///   jump is pushed after them, so they only normalize the condition.
+    /// - `JUMP` to the next instruction is removed, and `JUMPI` to
+    ///   the next instruction is replaced with `POP`.
+    ///
+    /// Each pass sweeps the bytecode once, and the passes are repeated
+    /// until nothing is rewritten, since the removals may bring new
+    /// sequences together.
+    ///
+    /// Returns the count of the bytes removed from the buffer, the
+    /// pushes of the removed jumps are not counted since they are
+    /// inserted by the relocation.
+    ///
+    /// *WARNING*: This function should be called before `relocate`.
+    pub fn optimize(&mut self, buffer: &mut Buffer) -> usize {
+        let mut removed = 0;
+        loop {
+            let rewrites = self.rewrites(buffer);
+            if rewrites.is_empty() {
+                break;
+            }
+
+            removed += self.apply(buffer, rewrites);
+        }
+
+        removed
+    }
+
+    /// Find the rewrites of the bytecode in a forward sweep.
+    ///
+    /// The rewrites neither overlap nor touch each other, so they can
+    /// be applied together, the ones right behind a rewrite are left to
+    /// the next pass.
+    fn rewrites(&self, buffer: &[u8]) -> Vec<Rewrite> {
+        let targets = self.targets();
+        let instrs = disassemble(buffer);
+        let mut rewrites = Vec::new();
+        let mut boundary = None;
+        for (index, instr) in instrs.iter().enumerate() {
+            if boundary.is_some_and(|boundary| instr.pc <= boundary) {
+                continue;
+            }
+
+            let next = instrs.get(index + 1);
+            let after = instrs.get(index + 2);
+
+            if let Some(next) = next {
+                let removable = ((instr.in_group(Group::Push)
+                    || instr.in_group(Group::Duplication))
+                    && next.is(OpCode::POP))
+                    || (instr.in_group(Group::Exchange) && instr.byte == next.byte)
+                    || (instr.is_push_zero()
+                        && (next.is(OpCode::ADD) || next.is(OpCode::OR) || next.is(OpCode::XOR)))
+                    || (instr.is(OpCode::ISZERO)
+                        && next.is(OpCode::ISZERO)
+                        && after.is_some_and(|after| {
+                            after.is(OpCode::JUMPI) && self.jump.contains_key(&(after.pc as u16))
+                        }));
+
+                if removable && self.removable(&targets, instr.pc, next.next_pc()) {
+                    rewrites.push(Rewrite::Remove(instr.pc, next.next_pc()));
+                    boundary = Some(next.next_pc());
+                    continue;
+                }
+            }
+
+            let pc = instr.pc as u16;
+            if self.jump.get(&pc) != Some(&Jump::Label(pc + 1)) || targets.contains(&pc) {
+                continue;
+            }
+
+            if instr.is(OpCode::JUMP) {
+                rewrites.push(Rewrite::Jump(instr.pc));
+            } else if instr.is(OpCode::JUMPI) {
+                rewrites.push(Rewrite::JumpI(instr.pc));
+            } else {
+                continue;
+            }
+
+            boundary = Some(instr.next_pc());
+        }
+
+        rewrites
+    }
+
+    /// If the instructions between the program counters can be removed.
+    ///
+    /// No jump is inserted inside of them and no jump targets the inside
+    /// of them, the jumps inserted at both the start and the end can not
+    /// be merged. A target at the start reaches the instruction at the end
+    /// after the removal, which is the same since the removed instructions
+    /// have no effect.
+    fn removable(&self, targets: &BTreeSet<u16>, start: usize, end: usize) -> bool {
+        let (start, end) = (start as u16, end as u16);
+        self.jump.range(start + 1..end).next().is_none()
+            && !(self.jump.contains_key(&start) && self.jump.contains_key(&end))
+            && targets.range(start + 1..end).next().is_none()
+    }
+
+    /// The program counters targeted by the labels or the functions.
+    fn targets(&self) -> BTreeSet<u16> {
+        self.jump
+            .values()
+            .filter_map(|jump| match jump {
+                Jump::Label(target) | Jump::Entry(target) => Some(*target),
+                _ => None,
+            })
+            .chain(self.func.values().copied())
+            .collect()
+    }
+
+    /// Apply the rewrites found in a sweep, the removed bytes are drained
+    /// and the program counters behind them are shifted at once.
+    ///
+    /// Returns the count of the removed bytes.
+    fn apply(&mut self, buffer: &mut Buffer, rewrites: Vec<Rewrite>) -> usize {
+        let mut ranges = Vec::with_capacity(rewrites.len());
+        for rewrite in rewrites {
+            tracing::trace!("peephole: {rewrite:?}");
+            match rewrite {
+                Rewrite::Remove(start, end) => ranges.push((start as u16, end as u16)),
+                Rewrite::Jump(pc) => {
+                    self.jump.remove(&(pc as u16));
+                    ranges.push((pc as u16, pc as u16 + 1));
+                }
+                Rewrite::JumpI(pc) => {
+                    self.jump.remove(&(pc as u16));
+                    buffer[pc] = OpCode::POP.into();
+                }
+            }
+        }
+
+        let mut kept = Buffer::new();
+        let mut pc = 0;
+        for (start, end) in &ranges {
+            kept.extend_from_slice(&buffer[pc..*start as usize]);
+            pc = *end as usize;
+        }
+        kept.extend_from_slice(&buffer[pc..]);
+        let removed = buffer.len() - kept.len();
+        *buffer = kept;
+
+        // the counts of the bytes removed before the ranges
+        let offsets = ranges
+            .iter()
+            .scan(0, |offset, (start, end)| {
+                let before = *offset;
+                *offset += end - start;
+                Some(before)
+            })
+            .chain([removed as u16])
+            .collect::<Vec<_>>();
+        let shift = |pc: u16| pc - offsets[ranges.partition_point(|(_, end)| *end <= pc)];
+
+        self.jump = std::mem::take(&mut self.jump)
+            .into_iter()
+            .map(|(pc, jump)| {
+                let jump = match jump {
+                    Jump::Label(target) => Jump::Label(shift(target)),
+                    Jump::Entry(target) => Jump::Entry(shift(target)),
+                    jump => jump,
+                };
+
+                (shift(pc), jump)
+            })
+            .collect();
+
+        for offset in self.func.values_mut() {
+            *offset = shift(*offset);
+        }
+
+        self.srcmap.remove(&ranges);
+        removed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use smallvec::smallvec;
+
+    #[test]
+    fn test_remove_redundant_instructions() {
+        let mut table = JumpTable::default();
+        // PUSH1 0x01 POP, DUP1 POP, SWAP1 SWAP1, PUSH0 ADD, JUMPDEST
+        let mut buffer: Buffer =
+            smallvec![0x60, 0x01, 0x50, 0x80, 0x50, 0x90, 0x90, 0x5f, 0x01, 0x5b];
+        table.label(0x20, 0x09);
+        table.func.insert(0, 0x09);
+
+        assert_eq!(table.optimize(&mut buffer), 9);
+        assert_eq!(buffer.to_vec(), vec![0x5b]);
+        assert_eq!(table.jump.get(&0x17), Some(&Jump::Label(0)));
+        assert_eq!(table.func.get(&0), Some(&0));
+    }
+
+    #[test]
+    fn test_preserve_jump_targets() {
+        let mut table = JumpTable::default();
+        // PUSH1 0x01 POP, DUP1 POP
+        let mut buffer: Buffer = smallvec![0x60, 0x01, 0x50, 0x80, 0x50];
+        // the jump is inserted inside of the first sequence, and
+        // targets the inside of the second sequence
+        table.label(0x02, 0x04);
+
+        assert_eq!(table.optimize(&mut buffer), 0);
+        assert_eq!(buffer.len(), 5);
+    }
+
+    #[test]
+    fn test_jumps_to_next() {
+        let mut table = JumpTable::default();
+        // ISZERO ISZERO JUMPI JUMPDEST, JUMP JUMPDEST
+        let mut buffer: Buffer = smallvec![0x15, 0x15, 0x57, 0x5b, 0x56, 0x5b];
+        table.label(0x02, 0x06);
+        table.label(0x04, 0x05);
+
+        assert_eq!(table.optimize(&mut buffer), 3);
+        assert_eq!(buffer.to_vec(), vec![0x57, 0x5b, 0x5b]);
+        assert_eq!(table.jump.get(&0x00), Some(&Jump::Label(0x03)));
+        assert_eq!(table.jump.len(), 1);
+
+        // the conditional jump to the next instruction drops the condition
+        let mut table = JumpTable::default();
+        let mut buffer: Buffer = smallvec![0x57, 0x5b];
+        table.label(0x00, 0x01);
+
+        assert_eq!(table.optimize(&mut buffer), 0);
+        assert_eq!(buffer.to_vec(), vec![0x50, 0x5b]);
+        assert!(table.jump.is_empty());
+    }
+}
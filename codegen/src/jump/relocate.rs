@@ -28,7 +28,11 @@ impl JumpTable {
         self.shift_targets()?;
         tracing::trace!("code section offset: 0x{:x}", self.code.offset());
 
-        // Relocate each function in the jump table.
+        // Relocate each function in the jump table, the insertions are
+        // collected with their program counters before any of them for
+        // shifting the source map at once.
+        let mut inserts = Vec::new();
+        let mut inserted = 0;
         while let Some((pc, jump)) = self.jump.pop_first() {
             tracing::debug!(
                 "Relocating jump {:?} at pc=0x{:x}, current_offset=0x{:x}",
@@ -46,10 +50,14 @@ impl JumpTable {
             );
 
             // Update the buffer with the new target program counter.
-            let offset = relocate::pc(buffer, pc, target)?;
+            let offset = relocate::pc(buffer, pc, target, jump.is_wide())?;
             self.shift_label_pc(pc, offset as u16)?;
+            inserts.push((pc - inserted, offset as u16));
+            inserted += offset as u16;
         }
 
+        self.srcmap.shift(&inserts);
+
         // Extend the buffer with the finished code section.
         buffer.extend_from_slice(&self.code.finish());
         Ok(())
@@ -62,14 +70,19 @@ impl JumpTable {
 /// counter, and updates the provided buffer with the necessary opcode
 /// instructions. It ensures that the buffer does not exceed the defined
 /// size limit and handles the conversion of the target program counter
-/// to the appropriate byte representation.
-fn pc(buffer: &mut Buffer, original_pc: u16, target_pc: u16) -> Result<usize> {
+/// to the appropriate byte representation, the wide targets are always
+/// pushed with `PUSH2`.
+fn pc(buffer: &mut Buffer, original_pc: u16, target_pc: u16, wide: bool) -> Result<usize> {
     let original_pc = original_pc as usize;
     let mut new_buffer: Buffer = buffer[..original_pc].into();
     let rest_buffer: Buffer = buffer[original_pc..].into();
 
     // Convert the target program counter to its byte representation.
-    let target = target_pc.to_ls_bytes();
+    let target = if wide {
+        target_pc.to_be_bytes().into()
+    } else {
+        target_pc.to_ls_bytes()
+    };
     if target.len() == 1 {
         new_buffer.push(OpCode::PUSH1.into());
     } else {
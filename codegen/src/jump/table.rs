@@ -4,8 +4,8 @@
 //! table, and code section. It provides methods to register jumps, functions, and
 //! labels, as well as to merge jump tables.
 
-use crate::{codegen::ExtFunc, jump::Jump, Code, Error, Result};
-use std::collections::BTreeMap;
+use crate::{codegen::ExtFunc, jump::Jump, Code, Error, Result, SourceMap};
+use std::collections::{BTreeMap, BTreeSet};
 
 /// Jump table implementation.
 #[derive(Clone, Default, Debug)]
@@ -16,6 +16,8 @@ pub struct JumpTable {
     pub(crate) func: BTreeMap<u32, u16>,
     /// Code section associated with the jump table.
     pub(crate) code: Code,
+    /// Source map shifted with the program counters of the jumps.
+    pub(crate) srcmap: SourceMap,
 }
 
 impl JumpTable {
@@ -48,11 +50,22 @@ impl JumpTable {
         self.jump.insert(pc, Jump::ExtFunc(func));
     }
 
+    /// Registers the offset of a data blob in the jump table.
+    pub fn data(&mut self, pc: u16, data: Vec<u8>) {
+        self.code.try_add_data(data.clone());
+        self.jump.insert(pc, Jump::Data(data));
+    }
+
     /// Registers a label in the jump table.
     pub fn label(&mut self, pc: u16, label: u16) {
         self.jump.insert(pc, Jump::Label(label));
     }
 
+    /// Registers an entry of a program counter table in the jump table.
+    pub fn entry(&mut self, pc: u16, label: u16) {
+        self.jump.insert(pc, Jump::Entry(label));
+    }
+
     /// Merges another jump table into this one.
     ///
     /// This function updates the program counters of the target jump table and
@@ -78,9 +91,42 @@ impl JumpTable {
             self.code.try_add_func(func);
         }
 
+        for data in table.code.data() {
+            self.code.try_add_data(data);
+        }
+
+        self.srcmap.merge(table.srcmap, pc);
+
         Ok(())
     }
 
+    /// Function indexes called in the jump table but not
+    /// registered in the function table.
+    pub fn unresolved(&self) -> BTreeSet<u32> {
+        self.jump
+            .values()
+            .filter_map(|jump| match jump {
+                Jump::Func(func) if !self.func.contains_key(func) => Some(*func),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Program counters of the functions by their indexes.
+    pub fn funcs(&self) -> &BTreeMap<u32, u16> {
+        &self.func
+    }
+
+    /// Code section associated with the jump table.
+    pub fn code(&self) -> &Code {
+        &self.code
+    }
+
+    /// Source map of the code behind the jump table.
+    pub fn srcmap(&self) -> &SourceMap {
+        &self.srcmap
+    }
+
     /// register jump to program counter
     pub fn register(&mut self, pc: u16, jump: Jump) {
         self.jump.insert(pc, jump);
@@ -89,8 +135,8 @@ impl JumpTable {
     /// Get the max target from the current jump table
     pub fn max_target(&self) -> u16 {
         self.jump
-            .iter()
-            .filter_map(|(_, jump)| self.target(jump).ok())
+            .values()
+            .filter_map(|jump| self.target(jump).ok())
             .max()
             .unwrap_or(0)
     }
@@ -147,6 +193,23 @@ fn test_sequential_jumps() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_jumps_grow_target() -> anyhow::Result<()> {
+    let mut table = JumpTable::default();
+
+    // The jumps between make the first target exceed 0xff
+    table.register(0x10, Jump::Label(0xf8));
+    table.register(0x20, Jump::Label(0x30));
+    table.register(0x40, Jump::Label(0x50));
+    table.register(0x60, Jump::Label(0x70));
+
+    table.shift_targets()?;
+
+    assert_eq!(table.target(table.jump.get(&0x10).unwrap())?, 0x101);
+    assert_eq!(table.target(table.jump.get(&0x20).unwrap())?, 0x35);
+    Ok(())
+}
+
 #[test]
 fn test_jump_backwards() -> anyhow::Result<()> {
     let mut table = JumpTable::default();
@@ -15,9 +15,13 @@ impl JumpTable {
     /// (offset, label, function, or external function).
     pub fn target(&self, jump: &Jump) -> Result<u16> {
         match jump {
-            Jump::Label(label) => Ok(*label),
+            Jump::Label(label) | Jump::Entry(label) => Ok(*label),
             Jump::Func(func) => Ok(*self.func.get(func).ok_or(Error::FuncNotFound(*func))?),
             Jump::ExtFunc(ext) => Ok(self.code.offset_of(ext).ok_or(Error::ExtFuncNotFound)?),
+            Jump::Data(data) => Ok(self
+                .code
+                .offset_of_data(data)
+                .ok_or(Error::DataBlobNotFound)?),
         }
     }
 
@@ -27,38 +31,58 @@ impl JumpTable {
     /// counter and the target, adjusting for any offsets.
     pub fn shift_targets(&mut self) -> Result<()> {
         let mut total_offset = 0;
-        let mut target_sizes = Vec::new();
-        let jumps = self.jump.clone();
-
-        // First pass: calculate all target sizes and accumulate offsets
-        for (original_pc, jump) in jumps.iter() {
-            let pc = original_pc + total_offset;
-            let raw_target = self.target(jump)?;
-
-            // Calculate the absolute target including future offsets
-            let target = if raw_target > *original_pc {
-                raw_target + total_offset
-            } else {
-                raw_target
-            };
-
-            // Calculate instruction size based on absolute target value
-            let instr_size = if target > 0xff {
-                3 // PUSH2 + 2 bytes
-            } else {
-                2 // PUSH1 + 1 byte
-            };
+        let jumps = self
+            .jump
+            .iter()
+            .map(|(pc, jump)| Ok((*pc, jump, self.target(jump)?)))
+            .collect::<Result<Vec<_>>>()?;
+
+        // First pass: calculate all target sizes until they are stable, since
+        // the jumps between a jump and its target may grow it as well.
+        let mut sizes = vec![2u16; jumps.len()];
+        loop {
+            let mut changed = false;
+            for (index, (_, jump, raw_target)) in jumps.iter().enumerate() {
+                // Calculate the absolute target including all offsets before it
+                let offset: u16 = jumps
+                    .iter()
+                    .zip(sizes.iter())
+                    .filter(|((pc, _, _), _)| jump.is_code() || pc < raw_target)
+                    .map(|(_, size)| size)
+                    .sum();
+
+                // Calculate instruction size based on absolute target value
+                let size = if jump.is_wide() || raw_target + offset > 0xff {
+                    3 // PUSH2 + 2 bytes
+                } else {
+                    2 // PUSH1 + 1 byte
+                };
+
+                if size != sizes[index] {
+                    sizes[index] = size;
+                    changed = true;
+                }
+            }
 
-            target_sizes.push((pc, instr_size));
-            total_offset += instr_size;
+            if !changed {
+                break;
+            }
         }
 
-        // Second pass: apply shifts with accumulated offsets
-        total_offset = 0;
+        let target_sizes = jumps
+            .iter()
+            .zip(sizes)
+            .map(|((original_pc, _, _), size)| {
+                let pc = original_pc + total_offset;
+                total_offset += size;
+                (pc, size)
+            })
+            .collect::<Vec<_>>();
+
+        // Second pass: apply shifts at the already offset program counters
         for (pc, size) in target_sizes {
             tracing::debug!("shift target at pc=0x{pc:x} with size={size}");
             self.shift_target(pc, size)?;
-            total_offset += size;
         }
 
         Ok(())
@@ -97,7 +121,7 @@ impl JumpTable {
     /// Shifts the program counter for labels.
     pub fn shift_label_target(&mut self, ptr: u16, offset: u16) -> Result<()> {
         for (_, jump) in self.jump.iter_mut() {
-            let Jump::Label(target) = jump else {
+            let (Jump::Label(target) | Jump::Entry(target)) = jump else {
                 continue;
             };
 
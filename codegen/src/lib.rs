@@ -3,27 +3,37 @@
 #![recursion_limit = "1024"]
 
 pub use crate::{
+    alloc::{Access, StackLocals},
     asm::Assembler,
-    codegen::{Code, Constructor, Dispatcher, Function, InitStorage},
+    codegen::{
+        Code, Constructor, Dispatcher, Float, Function, InitStorage, Preamble, Routine,
+        FLOAT_REGISTERS, LINEAR_SELECTORS,
+    },
     control::{ControlStack, ControlStackFrame, ControlStackFrameType},
     jump::JumpTable,
     local::{LocalSlot, Locals},
     masm::MacroAssembler,
     result::{Error, Result},
+    srcmap::{Source, SourceMap},
+    visitor::{float_operator, memory_read, unsupported_operator},
+    width::{Widths, Wrap},
 };
 use smallvec::SmallVec;
 
+mod alloc;
 mod asm;
-mod backtrace;
 mod codegen;
 mod control;
+pub mod ir;
 mod jump;
 mod local;
 mod masm;
 mod result;
+mod srcmap;
 mod validator;
 mod visitor;
 pub mod wasm;
+mod width;
 
 /// Maximum size of a evm bytecode in bytes.
 pub const BUFFER_LIMIT: usize = 0x6000;
@@ -6,50 +6,42 @@ use opcodes::Cancun as OpCode;
 impl MacroAssembler {
     /// Greater than or equal comparison.
     ///
-    /// a b ge -> a b-1 gt(lt)
+    /// a b ge -> a b lt iszero(gt)
     ///
-    /// Using lt due to order of stack.
+    /// Using gt due to order of stack.
     pub fn _ge(&mut self) -> Result<()> {
-        self.push(&[1])?;
-        // NOTE: this is the overridden sub but not `self.asm.sub`
-        self._sub()?;
-        self.asm._lt()
+        self.asm._gt()?;
+        self.asm._iszero()
     }
 
-    /// Greater than or equal comparison.
+    /// Signed greater than or equal comparison.
     ///
-    /// a b sge -> a b-1 sgt(slt)
+    /// a b sge -> a b slt iszero(sgt)
     ///
-    /// Using lt due to order of stack.
+    /// Using sgt due to order of stack.
     pub fn _sge(&mut self) -> Result<()> {
-        self.push(&[1])?;
-        // NOTE: this is the overridden sub but not `self.asm.sub`
-        self._sub()?;
-        self.asm._slt()
+        self.asm._sgt()?;
+        self.asm._iszero()
     }
 
-    /// Greater than or equal comparison.
+    /// Signed less than or equal comparison.
     ///
-    /// a b sge -> a b-1 sgt(slt)
+    /// a b sle -> a b sgt iszero(slt)
     ///
-    /// Using lt due to order of stack.
+    /// Using slt due to order of stack.
     pub fn _sle(&mut self) -> Result<()> {
-        self.push(&[1])?;
-        // NOTE: this is the overridden sub but not `self.asm.sub`
-        self._sub()?;
-        self.asm._slt()
+        self.asm._slt()?;
+        self.asm._iszero()
     }
 
-    /// Greater than or equal comparison.
+    /// Less than or equal comparison.
     ///
-    /// a b le -> a b-1 lt(gt)
+    /// a b le -> a b gt iszero(lt)
     ///
-    /// Using gt due to order of stack.
+    /// Using lt due to order of stack.
     pub fn _le(&mut self) -> Result<()> {
-        self.push(&[1])?;
-        // NOTE: this is the overridden sub but not `self.asm.sub`
-        self._sub()?;
-        self.asm._lt()
+        self.asm._lt()?;
+        self.asm._iszero()
     }
 
     /// Greater than and equal comparison.
@@ -1,6 +1,6 @@
 // Integer instructions
 
-use crate::{wasm::ToLSBytes, MacroAssembler, Result};
+use crate::{wasm::ToLSBytes, Error, MacroAssembler, Result};
 use wasmparser::{Ieee32, Ieee64};
 
 impl MacroAssembler {
@@ -10,6 +10,48 @@ impl MacroAssembler {
         self.asm._sub()
     }
 
+    /// Divide two numbers.
+    pub fn _div(&mut self) -> Result<()> {
+        self._swap1()?;
+        self.asm._div()
+    }
+
+    /// Divide two signed numbers.
+    pub fn _sdiv(&mut self) -> Result<()> {
+        self._swap1()?;
+        self.asm._sdiv()
+    }
+
+    /// Remainder of two numbers.
+    pub fn _mod(&mut self) -> Result<()> {
+        self._swap1()?;
+        self.asm._mod()
+    }
+
+    /// Remainder of two signed numbers.
+    pub fn _smod(&mut self) -> Result<()> {
+        self._swap1()?;
+        self.asm._smod()
+    }
+
+    /// Wrap the value to the lowest `bits` bits.
+    pub fn _wrap(&mut self, bits: u8) -> Result<()> {
+        let mask = u64::MAX >> (64 - bits as u32);
+        self.push(&mask.to_ls_bytes())?;
+        self.asm._and()
+    }
+
+    /// Sign-extend the lowest `bytes` bytes of the value to 256 bits.
+    pub fn _sign_extend(&mut self, bytes: u8) -> Result<()> {
+        self.push(&[bytes - 1])?;
+        self.asm._signextend()
+    }
+
+    /// Arithmetic shift right.
+    pub fn _sshr(&mut self) -> Result<()> {
+        self.asm._sar()
+    }
+
     /// Push a 32-bit integer value on the stack.
     pub fn _i32_const(&mut self, value: i32) -> Result<()> {
         if value == 0 {
@@ -28,105 +70,132 @@ impl MacroAssembler {
         }
     }
 
-    /// Push a 32-bit float value on the stack.
-    pub fn _f32_const(&mut self, _value: Ieee32) -> Result<()> {
-        todo!()
+    /// Push the bits of a 32-bit float value on the stack.
+    pub fn _f32_const(&mut self, value: Ieee32) -> Result<()> {
+        self._i32_const(value.bits() as i32)
     }
 
-    /// Push a 64-bit float value on the stack.
-    pub fn _f64_const(&mut self, _value: Ieee64) -> Result<()> {
-        todo!()
+    /// Push the bits of a 64-bit float value on the stack.
+    pub fn _f64_const(&mut self, value: Ieee64) -> Result<()> {
+        self._i64_const(value.bits() as i64)
     }
 
     /// wrap a 64-bit integer to a 32-bit integer.
+    ///
+    /// The value is wrapped after the width analysis if
+    /// it may exceed 32 bits.
     pub fn _i32_wrap_i64(&mut self) -> Result<()> {
-        todo!()
+        Ok(())
     }
 
     /// Extend a signed 32-bit integer to a 64-bit integer.
     pub fn _i64_extend_i32_s(&mut self) -> Result<()> {
-        todo!()
+        self._i64_extend32_s()
     }
 
-    /// Extend an unsigned 32-bit integer to a 64-bit integer.
-    pub fn _i64_extend_i32_u(&mut self) -> Result<()> {
-        todo!()
+    /// Extend a signed 8-bit integer to a 32-bit integer.
+    pub fn _i32_extend8_s(&mut self) -> Result<()> {
+        self._sign_extend(1)?;
+        self._wrap(32)
     }
 
-    /// Truncate a 64-bit float to a signed 32-bit integer.
-    pub fn _f32_demote_f64(&mut self) -> Result<()> {
-        todo!()
+    /// Extend a signed 16-bit integer to a 32-bit integer.
+    pub fn _i32_extend16_s(&mut self) -> Result<()> {
+        self._sign_extend(2)?;
+        self._wrap(32)
     }
 
-    /// Truncate a 64-bit float to an unsigned 32-bit integer.
-    pub fn _f64_promote_f32(&mut self) -> Result<()> {
-        todo!()
+    /// Extend a signed 8-bit integer to a 64-bit integer.
+    pub fn _i64_extend8_s(&mut self) -> Result<()> {
+        self._sign_extend(1)?;
+        self._wrap(64)
     }
 
-    /// Convert a signed 32-bit integer to a 32-bit float.
+    /// Extend a signed 16-bit integer to a 64-bit integer.
+    pub fn _i64_extend16_s(&mut self) -> Result<()> {
+        self._sign_extend(2)?;
+        self._wrap(64)
+    }
+
+    /// Extend a signed 32-bit integer to a 64-bit integer.
+    pub fn _i64_extend32_s(&mut self) -> Result<()> {
+        self._sign_extend(4)?;
+        self._wrap(64)
+    }
+
+    /// Extend an unsigned 32-bit integer to a 64-bit integer.
+    ///
+    /// The integers are zero-extended on the stack already.
+    pub fn _i64_extend_i32_u(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Reinterpret the bits of a 32-bit float as a 32-bit integer.
+    ///
+    /// Floats are kept as their bits on the stack.
     pub fn _i32_reinterpret_f32(&mut self) -> Result<()> {
-        todo!()
+        Ok(())
     }
 
-    /// Convert a signed 64-bit integer to a 64-bit float.
+    /// Reinterpret the bits of a 64-bit float as a 64-bit integer.
     pub fn _i64_reinterpret_f64(&mut self) -> Result<()> {
-        todo!()
+        Ok(())
     }
 
-    /// Convert a 32-bit float to a signed 32-bit integer.
+    /// Reinterpret the bits of a 32-bit integer as a 32-bit float.
     pub fn _f32_reinterpret_i32(&mut self) -> Result<()> {
-        todo!()
+        Ok(())
     }
 
-    /// Convert a 64-bit float to a signed 64-bit integer.
+    /// Reinterpret the bits of a 64-bit integer as a 64-bit float.
     pub fn _f64_reinterpret_i64(&mut self) -> Result<()> {
-        todo!()
+        Ok(())
     }
 
     /// sign-agnostic rotate left
     ///
     /// Return the result of rotating i1 left by k bits.
+    ///
+    /// NOTE: this is rejected before the code generation.
     pub fn _rotl(&mut self) -> Result<()> {
-        todo!()
+        Err(Error::UnsupportedOperator("Rotl"))
     }
 
     /// sign-agnostic rotate right
     ///
     /// Return the result of rotating i1 right by k bits.
+    ///
+    /// NOTE: this is rejected before the code generation.
     pub fn _rotr(&mut self) -> Result<()> {
-        todo!()
+        Err(Error::UnsupportedOperator("Rotr"))
     }
 
     /// sign-agnostic count leading zero bits
     ///
     /// Return the number of leading zero bits in i, all zero bits
     /// are considered leading if the value is zero.
+    ///
+    /// NOTE: this is rejected before the code generation.
     pub fn _clz(&mut self) -> Result<()> {
-        todo!()
+        Err(Error::UnsupportedOperator("Clz"))
     }
 
     /// sign-agnostic count leading zero bits
     ///
     /// Return the number of leading zero bits in i, all zero bits
     /// are considered trailing if the value is zero.
+    ///
+    /// NOTE: this is rejected before the code generation.
     pub fn _ctz(&mut self) -> Result<()> {
-        todo!()
+        Err(Error::UnsupportedOperator("Ctz"))
     }
 
     /// sign-agnostic count number of one bits
     ///
     /// Return the count of no zero bits in i.
+    ///
+    /// NOTE: this is rejected before the code generation.
     pub fn _popcnt(&mut self) -> Result<()> {
-        todo!()
-    }
-
-    /// Truncate a 32-bit float to an integer
-    pub fn _trunc_f32(&mut self) -> Result<()> {
-        todo!()
-    }
-
-    /// Truncate a 64-bit float to an integer
-    pub fn _trunc_f64(&mut self) -> Result<()> {
-        todo!()
+        Err(Error::UnsupportedOperator("Popcnt"))
     }
 }
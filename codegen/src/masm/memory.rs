@@ -1,70 +1,93 @@
 //! Memory Instructions
 
-use crate::{MacroAssembler, Result};
+use crate::{wasm::ToLSBytes, MacroAssembler, Result};
 
 impl MacroAssembler {
-    /// Load n bytes to extend self as another number type.
+    /// Load `bytes` little-endian bytes from memory and extend
+    /// them to an integer of `width` bytes.
     ///
-    /// Just for adapting the WASM instructions, this method makes
-    /// no sense for EVM since all of the numbers as U256.
-    pub(crate) fn _load(&mut self) -> Result<()> {
-        Ok(())
-    }
+    /// STACK: [address] -> [value]
+    pub(crate) fn _load(&mut self, bytes: u8, width: u8, signed: bool) -> Result<()> {
+        // [big-endian bytes]
+        self._mload()?;
+        self.push(&(256 - bytes as u16 * 8).to_ls_bytes())?;
+        self.asm._shr()?;
 
-    /// Load 1 byte to extend self as another number type.
-    ///
-    /// Just for adapting the WASM instructions, this method makes
-    /// no sense for EVM since all of the numbers as U256.
-    pub(crate) fn _load8(&mut self) -> Result<()> {
-        Ok(())
-    }
+        // [value]
+        self._bswap(bytes)?;
+        if signed && bytes < width {
+            self._sign_extend(bytes)?;
+            self._wrap(width * 8)?;
+        }
 
-    /// Load 2 bytes to extend self as another number type.
-    ///
-    /// Just for adapting the WASM instructions, this method makes
-    /// no sense for EVM since all of the numbers as U256.
-    pub(crate) fn _load16(&mut self) -> Result<()> {
         Ok(())
     }
 
-    /// Load 4 bytes to extend self as another number type.
+    /// Wrap the value to `bytes` and store them in memory as
+    /// little-endian bytes.
     ///
-    /// Just for adapting the WASM instructions, this method makes
-    /// no sense for EVM since all of the numbers as U256.
-    pub(crate) fn _load32(&mut self) -> Result<()> {
-        Ok(())
-    }
+    /// STACK: [value, address] -> []
+    pub(crate) fn _store(&mut self, bytes: u8) -> Result<()> {
+        if bytes == 1 {
+            return self._mstore8();
+        }
 
-    /// Store n bytes in memory.
-    pub fn _store(&mut self) -> Result<()> {
-        todo!()
-    }
+        // [address, big-endian bytes]
+        self._swap1()?;
+        self.push(&vec![0xff; bytes as usize])?;
+        self.asm._and()?;
+        self._bswap(bytes)?;
+        self.push(&(256 - bytes as u16 * 8).to_ls_bytes())?;
+        self.asm._shl()?;
 
-    /// Wrap self to i8 and store 1 byte
-    pub fn _store8(&mut self) -> Result<()> {
-        todo!()
-    }
+        // [address, word] with the stored bytes replaced
+        self._dup2()?;
+        self._mload()?;
+        self.push(&vec![0xff; 32 - bytes as usize])?;
+        self.asm._and()?;
+        self.asm._or()?;
 
-    /// Wrap self to i16 and store 2 bytes
-    pub fn _store16(&mut self) -> Result<()> {
-        todo!()
+        self._swap1()?;
+        self._mstore()
     }
 
-    /// Wrap self to i32 and store 4 bytes
-    pub fn _store32(&mut self) -> Result<()> {
-        todo!()
-    }
+    /// Reverse the order of the lowest `bytes` bytes, the higher
+    /// bytes are expected to be zero.
+    ///
+    /// Swaps the adjacent bytes, then the adjacent pairs of bytes,
+    /// and so on, e.g. `0x11223344 -> 0x22114433 -> 0x44332211`.
+    ///
+    /// STACK: [value] -> [value]
+    fn _bswap(&mut self, bytes: u8) -> Result<()> {
+        let mut width = 1;
+        while width < bytes {
+            // the lower half of each pair, i.e. `0x00ff00ff` for
+            // swapping the adjacent bytes of 4 bytes.
+            let mask = (0..bytes)
+                .rev()
+                .map(|byte| if (byte / width) % 2 == 0 { 0xff } else { 0 })
+                .skip_while(|byte| *byte == 0)
+                .collect::<Vec<_>>();
+            let shift = (width as u16 * 8).to_ls_bytes();
 
-    /// The memory size instruction returns the current
-    /// size of memory.
-    pub fn _memory_size(&mut self, _: u32, _: u8) -> Result<()> {
-        todo!()
-    }
+            // [value, higher halves]
+            self._dup1()?;
+            self.push(&shift)?;
+            self.asm._shr()?;
+            self.push(&mask)?;
+            self.asm._and()?;
+
+            // [higher halves, lower halves]
+            self._swap1()?;
+            self.push(&mask)?;
+            self.asm._and()?;
+            self.push(&shift)?;
+            self.asm._shl()?;
 
-    /// The memory grow instruction grows memory by a given
-    /// delta and returns the previous size, or -1 if enough
-    /// memory cannot be allocated.
-    pub fn _memory_grow(&mut self, _: u32, _: u8) -> Result<()> {
-        todo!()
+            self.asm._or()?;
+            width *= 2;
+        }
+
+        Ok(())
     }
 }
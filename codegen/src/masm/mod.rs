@@ -10,7 +10,6 @@ use std::ops::{Deref, DerefMut};
 
 mod cmp;
 mod embed;
-mod float;
 mod integer;
 mod memory;
 mod ret;
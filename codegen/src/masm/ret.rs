@@ -1,9 +1,64 @@
 //! Return handlers
 
-use crate::{wasm::ToLSBytes, Error, MacroAssembler, Result};
+use crate::{codegen::ExtFunc, wasm::ToLSBytes, Error, JumpTable, MacroAssembler, Result};
+use opcodes::ShangHai as OpCode;
 use wasmparser::ValType;
 
 impl MacroAssembler {
+    /// Revert if the call carries ether, which guards the non-payable
+    /// external functions.
+    pub fn reject_callvalue(&mut self, table: &mut JumpTable) -> Result<()> {
+        self._callvalue()?;
+        self._iszero()?;
+        self.revert_unless(table)
+    }
+
+    /// Revert with nothing if the condition on the stack is zero.
+    ///
+    /// The jump over the revert is registered to the jump table.
+    pub fn revert_unless(&mut self, table: &mut JumpTable) -> Result<()> {
+        let pc = self.pc();
+        self.increment_sp(1)?;
+        self._jumpi()?;
+
+        self._push0()?;
+        self._push0()?;
+        self._revert()?;
+
+        table.label(pc, self.pc());
+        self._jumpdest()
+    }
+
+    /// Revert with nothing if the condition on the stack is zero, by
+    /// jumping to the revert shared in the code section.
+    ///
+    /// This is smaller than [`Self::revert_unless`] for the checks
+    /// more than one, but costs more gas if the check passes.
+    pub fn shared_revert_unless(&mut self, table: &mut JumpTable) -> Result<()> {
+        self._iszero()?;
+
+        let pc = self.pc();
+        self.increment_sp(1)?;
+        self._jumpi()?;
+
+        table.ext(
+            pc,
+            ExtFunc {
+                stack_out: 0,
+                stack_in: 0,
+                bytecode: [
+                    OpCode::JUMPDEST,
+                    OpCode::PUSH0,
+                    OpCode::PUSH0,
+                    OpCode::REVERT,
+                ]
+                .map(Into::into)
+                .into(),
+            },
+        );
+        Ok(())
+    }
+
     /// Return with nothing.
     pub(crate) fn handle_empty_return(&mut self) -> Result<()> {
         self._push0()?;
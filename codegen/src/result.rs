@@ -9,6 +9,9 @@ pub enum Error {
     /// Failed to parse function ABI.
     #[error(transparent)]
     Abi(#[from] zabi::result::Error),
+    /// Failed to parse inline assembly.
+    #[error(transparent)]
+    Asm(#[from] opcodes::asm::AsmError),
     /// Failed to parse WASM with binary reader.
     #[error(transparent)]
     BinaryReader(#[from] wasmparser::BinaryReaderError),
@@ -18,9 +21,15 @@ pub enum Error {
     /// Failed to pop control stack frame.
     #[error("Control stack underflow")]
     ControlStackUnderflow,
+    /// Failed to find data blob in jump table.
+    #[error("Data blob not found in jump table")]
+    DataBlobNotFound,
     /// Data not found in data section.
     #[error("Data not found in data setction, offset {0}, size {1}")]
     DataNotFound(i32, usize),
+    /// Failed to route more than one fallback or receive function.
+    #[error("Duplicate {0} function in the dispatcher")]
+    DuplicateEntry(String),
     /// Failed to register program counter to function index.
     #[error("Function {0} already exists in jump table")]
     DuplicateFunc(u32),
@@ -48,6 +57,9 @@ pub enum Error {
     /// Failed parse function signature.
     #[error("Invalid function signature")]
     InvalidFunctionSignature,
+    /// Failed to get global with given index.
+    #[error("Invalid global index {0}")]
+    InvalidGlobalIndex(u32),
     /// Failed to get local with given index.
     #[error("Invalid local index {0}")]
     InvalidLocalIndex(usize),
@@ -96,6 +108,9 @@ pub enum Error {
     /// Failed to pop stack.
     #[error("Stack not balanced, current stack items {0}")]
     StackNotBalanced(u16),
+    /// Failed to compile operators of the unsupported proposals.
+    #[error("Unsupported operator {0}")]
+    UnsupportedOperator(&'static str),
     /// Failed to queue host functions.
     #[error("Unsupported host function {0:?}")]
     UnsupportedHostFunc(crate::wasm::HostFunc),
@@ -0,0 +1,148 @@
+//! Source map from the program counters to the WASM operators.
+//!
+//! The source of an operator is recorded at the program counter where
+//! its code starts, and covers the instructions till the next record,
+//! the code generated without operators, like the dispatcher and the
+//! returns of the functions, is recorded without source.
+//!
+//! The records are shifted with the jump table, while merging the
+//! functions, running the peephole optimizer and relocating the jumps.
+
+use opcodes::disassemble;
+use std::collections::BTreeMap;
+
+/// Source of the code generated from a WASM operator.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Source {
+    /// Index of the function in the WASM module.
+    pub func: u32,
+    /// Byte offset of the operator in the WASM module.
+    pub offset: usize,
+    /// Byte length of the operator.
+    pub len: usize,
+}
+
+/// Source map of the bytecode.
+#[derive(Clone, Debug, Default)]
+pub struct SourceMap(BTreeMap<u16, Option<Source>>);
+
+impl SourceMap {
+    /// Record the source of the code starting at the program counter.
+    pub fn record(&mut self, pc: u16, source: Option<Source>) {
+        self.0.insert(pc, source);
+    }
+
+    /// Get the source of the instruction at the program counter.
+    pub fn get(&self, pc: u16) -> Option<Source> {
+        self.0
+            .range(..=pc)
+            .next_back()
+            .and_then(|(_, source)| *source)
+    }
+
+    /// Get the sources of the instructions of the bytecode.
+    pub fn sources(&self, bytecode: &[u8]) -> Vec<(u16, Option<Source>)> {
+        disassemble(bytecode)
+            .into_iter()
+            .map(|instr| (instr.pc as u16, self.get(instr.pc as u16)))
+            .collect()
+    }
+
+    /// Get the program counter where the code of the `n`-th operator
+    /// recorded before the program counter starts.
+    pub(crate) fn nth_back(&self, pc: u16, n: usize) -> Option<u16> {
+        self.0.range(..pc).nth_back(n).map(|(start, _)| *start)
+    }
+
+    /// Merge the source map of the code placed at the program counter.
+    pub(crate) fn merge(&mut self, srcmap: Self, pc: u16) {
+        self.0.extend(
+            srcmap
+                .0
+                .into_iter()
+                .map(|(start, source)| (start + pc, source)),
+        );
+    }
+
+    /// Shift the records for the bytes inserted right after the
+    /// program counters, which are sorted and taken before any of
+    /// the insertions.
+    pub(crate) fn shift(&mut self, inserts: &[(u16, u16)]) {
+        let mut inserts = inserts.iter().peekable();
+        let mut offset = 0;
+        self.0 = std::mem::take(&mut self.0)
+            .into_iter()
+            .map(|(start, source)| {
+                while let Some((_, len)) = inserts.next_if(|(pc, _)| *pc < start) {
+                    offset += len;
+                }
+
+                (start + offset, source)
+            })
+            .collect();
+    }
+
+    /// Remove the bytes between the program counters of the ranges,
+    /// which are sorted and disjoint, the records inside of a range are
+    /// moved to its start, where the last of them takes the place.
+    pub(crate) fn remove(&mut self, ranges: &[(u16, u16)]) {
+        let mut ranges = ranges.iter().peekable();
+        let mut offset = 0;
+        self.0 = std::mem::take(&mut self.0)
+            .into_iter()
+            .map(|(pc, source)| {
+                while let Some((start, end)) = ranges.next_if(|(_, end)| *end <= pc) {
+                    offset += end - start;
+                }
+
+                match ranges.peek() {
+                    Some((start, _)) if pc > *start => (start - offset, source),
+                    _ => (pc - offset, source),
+                }
+            })
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shift_sources() {
+        let source = |offset| Source {
+            func: 0,
+            offset,
+            len: 1,
+        };
+
+        let mut srcmap = SourceMap::default();
+        srcmap.record(0, Some(source(0x10)));
+        srcmap.record(2, Some(source(0x11)));
+        srcmap.record(4, None);
+
+        // the push of a jump is inserted at 0
+        srcmap.shift(&[(0, 2)]);
+        assert_eq!(srcmap.get(1), Some(source(0x10)));
+        assert_eq!(srcmap.get(4), Some(source(0x11)));
+        assert_eq!(srcmap.get(6), None);
+
+        // the instructions of both operators are partially removed
+        srcmap.remove(&[(1, 5)]);
+        assert_eq!(srcmap.get(0), Some(source(0x10)));
+        assert_eq!(srcmap.get(1), Some(source(0x11)));
+        assert_eq!(srcmap.get(2), None);
+    }
+
+    #[test]
+    fn test_shift_inserts() {
+        let mut srcmap = SourceMap::default();
+        for pc in [0, 2, 4] {
+            srcmap.record(pc, Some(Source::default()));
+        }
+
+        // the pushes of the jumps at 0 and 2 are inserted at once
+        srcmap.shift(&[(0, 2), (2, 3)]);
+        assert_eq!(srcmap.0.keys().copied().collect::<Vec<_>>(), [0, 4, 9]);
+    }
+}
@@ -18,7 +18,7 @@ macro_rules! validate_then_visit {
                 // determine if reachability should be restored.
                 let visit_when_unreachable = visit_op_when_unreachable(Operator::$op $({ $($arg: $arg.clone()),* })?);
                 if true || visit_when_unreachable  {
-                    Ok(self.1.$visit($($($arg),*)?))
+                    Ok(self.1.wrapping(&Operator::$op $({ $($arg: $arg.clone()),* })?, |f| f.$visit($($($arg),*)?)))
                 } else {
                     Ok(Ok(()))
                 }
@@ -0,0 +1,58 @@
+//! Inline assembly
+
+use crate::{Error, Function, Result};
+use opcodes::asm::{self, Item};
+use std::collections::BTreeMap;
+
+impl Function {
+    /// Splice the inline assembly from the data section into the code.
+    ///
+    /// The references of the labels are registered in the jump table,
+    /// so they are relocated with the code of the function, and the
+    /// assembly should leave the stack as it was.
+    pub fn asm(&mut self) -> Result<()> {
+        let (offset, size) = self.data()?;
+        let source = self.env.data.load(offset, size as usize)?;
+        let items = asm::parse(&String::from_utf8_lossy(&source))?;
+
+        let sp = self.masm.sp();
+        let mut labels = BTreeMap::new();
+        let mut refs = BTreeMap::new();
+        for item in items {
+            match item {
+                Item::Op { opcode, immediate } => {
+                    self.masm.emit_op(opcode)?;
+                    self.masm.asm.emitn(&immediate);
+                }
+                Item::Label(label) => {
+                    labels.insert(label, self.masm.pc());
+                    self.masm._jumpdest()?;
+                }
+                Item::Ref(label) => {
+                    // the push of the address is inserted while relocating,
+                    // which requires an instruction behind the program counter.
+                    let pc = self.masm.pc();
+                    if refs.insert(pc, label).is_some() {
+                        return Err(Error::DuplicateJump(pc));
+                    }
+
+                    self.masm.increment_sp(1)?;
+                }
+            }
+        }
+
+        if refs.contains_key(&self.masm.pc()) {
+            return Err(Error::DuplicateJump(self.masm.pc()));
+        }
+
+        if self.masm.sp() != sp {
+            return Err(Error::StackNotBalanced(self.masm.sp()));
+        }
+
+        for (pc, label) in refs {
+            self.table.label(pc, labels[&label]);
+        }
+
+        Ok(())
+    }
+}
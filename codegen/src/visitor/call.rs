@@ -6,22 +6,98 @@
 //! calls.
 
 use crate::{
-    wasm::{HostFunc, ToLSBytes},
+    wasm::{HostFunc, ToLSBytes, FRAME_STACK_LIMIT},
     Error, Function, Result,
 };
-use anyhow::anyhow;
 use opcodes::Cancun as OpCode;
 
+/// The size of the entries in the program counter table of
+/// `call_indirect`, `JUMPDEST PUSH2 stub JUMP`.
+const ENTRY_SIZE: u8 = 5;
+
 impl Function {
-    /// The call indirect instruction calls a function indirectly                                                                                                                                          
-    /// through an operand indexing into a table.                                                                                                                                                          
+    /// The call indirect instruction calls a function indirectly
+    /// through an operand indexing into a table.
+    ///
+    /// The operand is checked with the length of the table and jumps to
+    /// its entry in a program counter table, the entries of the elements
+    /// matching the function type `type_index` jump to a stub calling the
+    /// function directly, the uninitialized ones or with another signature
+    /// reach INVALID, so does the operand out of bounds.
+    ///
+    /// STACK: [.., params, elem] -> [.., results]
     pub fn _call_indirect(
         &mut self,
-        _type_index: u32,
-        _table_index: u32,
+        type_index: u32,
+        table_index: u32,
         _table_byte: u8,
     ) -> Result<()> {
-        todo!()
+        let ty = self
+            .env
+            .types
+            .get(&type_index)
+            .ok_or(Error::InvalidFunctionSignature)?;
+        let (params, results) = (ty.params().len() as u16, ty.results().len() as u16);
+        let callees = self.env.indirect_callees(type_index, table_index);
+        let len = callees.iter().map(|(elem, _)| elem + 1).max().unwrap_or(0);
+        tracing::debug!("Calling indirect function: type={type_index}, table={table_index}, callees={callees:?}");
+
+        // Trap if the element is out of bounds.
+        self.masm._dup1()?;
+        self.masm.push(&len.to_ls_bytes())?;
+        self.masm.asm._gt()?;
+        self.table.label(self.masm.pc(), self.masm.pc() + 2);
+        self.masm.increment_sp(1)?;
+        self.masm._jumpi()?;
+        self.masm._invalid()?;
+        self.masm._jumpdest()?;
+
+        // Jump to the entry of the element.
+        self.masm.push(&ENTRY_SIZE.to_ls_bytes())?;
+        self.masm._mul()?;
+        let base = self.masm.pc();
+        self.masm.increment_sp(1)?;
+        self.masm._add()?;
+        self.masm._jump()?;
+
+        // The program counter table.
+        self.table.label(base, self.masm.pc());
+        let mut entries = Vec::with_capacity(callees.len());
+        for elem in 0..len {
+            self.masm._jumpdest()?;
+            if callees.iter().any(|(callee, _)| *callee == elem) {
+                entries.push(self.masm.pc());
+                self.masm.increment_sp(1)?;
+                self.masm._jump()?;
+            } else {
+                for _ in 1..ENTRY_SIZE {
+                    self.masm._invalid()?;
+                }
+            }
+        }
+
+        // Call the function of the element and jump to the end.
+        let sp = self.masm.sp();
+        let mut exits = Vec::with_capacity(callees.len());
+        for (entry, (_, func)) in entries.into_iter().zip(callees.iter()) {
+            self.masm.asm.sp = sp;
+            self.table.entry(entry, self.masm.pc());
+            self.masm._jumpdest()?;
+            self._call(*func)?;
+
+            exits.push(self.masm.pc());
+            self.masm.increment_sp(1)?;
+            self.masm._jump()?;
+        }
+
+        let end = self.masm.pc();
+        for exit in exits {
+            self.table.label(exit, end);
+        }
+
+        self.masm._jumpdest()?;
+        self.masm.asm.sp = sp - params + results;
+        Ok(())
     }
 
     /// Calls a function specified by its index.
@@ -48,31 +124,32 @@ impl Function {
     /// Calls an internal function specified by its index.
     ///
     /// This function handles the mechanics of calling an internal function, including:
-    /// - Checking for recursion and returning an error if detected.
+    /// - Saving the slots of the callee to the frame stack if the call is recursive.
     /// - Recording the current program counter (PC) to manage the return address.
     /// - Adjusting the stack to accommodate parameters and the return address.
     /// - Storing parameters in memory and registering the call index in the jump table.
+    /// - Restoring the slots of the callee from the frame stack after returning.
     ///
     /// # Errors
     ///
-    /// Returns an error if recursion is detected or if the function index is invalid.
+    /// Returns an error if the function index is invalid.
     fn call_internal(&mut self, index: u32) -> Result<()> {
-        if self.env.index == Some(index) {
-            return Err(anyhow!(
-                "Recursion is no longer supported in this version. See https://github.com/zink-lang/zink/issues/248"
-            ).into());
-        }
-
         tracing::debug!("Calling internal function: index={index}");
-        let reserved = self.env.slots.get(&index).unwrap_or(&0);
-        let (params, results) = self.env.funcs.get(&index).unwrap_or(&(0, 0));
+        let reserved = *self.env.slots.get(&index).unwrap_or(&0);
+        let (params, results) = *self.env.funcs.get(&index).unwrap_or(&(0, 0));
+        let recursive = self.env.is_recursive_call(index);
 
         // TODO This is a temporary fix to avoid stack underflow.
         // We need to find a more elegant solution for this.
         self.masm.increment_sp(1)?;
 
+        // Save the slots of the alive activation of the callee.
+        if recursive {
+            self.save_frame(index)?;
+        }
+
         // Store parameters in memory and register the call index in the jump table.
-        for i in (0..*params).rev() {
+        for i in (0..params).rev() {
             tracing::trace!("Storing local at {} for function {index}", i + reserved);
             self.masm.push(&((i + reserved) * 0x20).to_ls_bytes())?;
             self.masm._mstore()?;
@@ -89,10 +166,105 @@ impl Function {
 
         // Adjust the stack pointer for the results.
         self.masm._jumpdest()?;
-        self.masm.increment_sp(*results as u16)?;
+        self.masm.increment_sp(results as u16)?;
+
+        if recursive {
+            self.restore_frame(index)?;
+        }
+
         Ok(())
     }
 
+    /// Push the memory slots of the function to the frame stack.
+    ///
+    /// STACK: [..] -> [..], FRAME: [..] -> [.., slots]
+    fn save_frame(&mut self, index: u32) -> Result<()> {
+        let reserved = *self.env.slots.get(&index).unwrap_or(&0);
+        let size = *self.env.frames.get(&index).unwrap_or(&0);
+        if size == 0 {
+            return Ok(());
+        }
+
+        let (pointer, base) = self.frame_stack();
+        tracing::trace!("Saving {size} slots of function {index} to the frame stack");
+
+        // [fp]
+        self.masm.push(&pointer.to_ls_bytes())?;
+        self.masm._mload()?;
+        for slot in 0..size {
+            // [fp, slot]
+            self.masm.push(&((reserved + slot) * 0x20).to_ls_bytes())?;
+            self.masm._mload()?;
+
+            // [fp]
+            self.masm._dup2()?;
+            self.masm.push(&(base + slot * 0x20).to_ls_bytes())?;
+            self.masm._add()?;
+            self.masm._mstore()?;
+        }
+
+        // [fp + size]
+        self.masm.push(&(size * 0x20).to_ls_bytes())?;
+        self.masm._add()?;
+
+        // Trap if the frame stack overflows into the linear memory.
+        self.masm._dup1()?;
+        self.masm.push(&(FRAME_STACK_LIMIT * 0x20).to_ls_bytes())?;
+        self.masm.asm._lt()?;
+        self.masm._iszero()?;
+        self.table.label(self.masm.pc(), self.masm.pc() + 2);
+        self.masm.increment_sp(1)?;
+        self.masm._jumpi()?;
+        self.masm._invalid()?;
+        self.masm._jumpdest()?;
+
+        // [] with the frame pointer updated
+        self.masm.push(&pointer.to_ls_bytes())?;
+        self.masm._mstore()
+    }
+
+    /// Pop the memory slots of the function from the frame stack.
+    ///
+    /// STACK: [..] -> [..], FRAME: [.., slots] -> [..]
+    fn restore_frame(&mut self, index: u32) -> Result<()> {
+        let reserved = *self.env.slots.get(&index).unwrap_or(&0);
+        let size = *self.env.frames.get(&index).unwrap_or(&0);
+        if size == 0 {
+            return Ok(());
+        }
+
+        let (pointer, base) = self.frame_stack();
+        tracing::trace!("Restoring {size} slots of function {index} from the frame stack");
+
+        // [fp - size]
+        self.masm.push(&(size * 0x20).to_ls_bytes())?;
+        self.masm.push(&pointer.to_ls_bytes())?;
+        self.masm._mload()?;
+        self.masm.asm._sub()?;
+
+        // [fp]
+        self.masm._dup1()?;
+        self.masm.push(&pointer.to_ls_bytes())?;
+        self.masm._mstore()?;
+        for slot in 0..size {
+            self.masm._dup1()?;
+            self.masm.push(&(base + slot * 0x20).to_ls_bytes())?;
+            self.masm._add()?;
+            self.masm._mload()?;
+            self.masm.push(&((reserved + slot) * 0x20).to_ls_bytes())?;
+            self.masm._mstore()?;
+        }
+
+        self.masm._pop()
+    }
+
+    /// Get the memory offsets of the frame stack pointer and the
+    /// base of the frame stack.
+    fn frame_stack(&self) -> (u32, u32) {
+        let pointer = self.env.frame_stack * 0x20;
+        (pointer, pointer + 0x20)
+    }
+
     /// Calls an imported function specified by its index.
     ///
     /// This function retrieves the imported function from the environment and executes it.
@@ -121,6 +293,7 @@ impl Function {
             HostFunc::Evm(op) => self.masm.emit_op(op),
             HostFunc::U256MAX => self.masm.push(&[255; 32]),
             HostFunc::Revert(count) => self.revert(count),
+            HostFunc::Asm => self.asm(),
             HostFunc::NoOp | HostFunc::Label(_) => Ok(()),
             _ => {
                 tracing::error!("Unsupported host function {func:?}");
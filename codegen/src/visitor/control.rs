@@ -2,9 +2,10 @@
 
 use crate::{
     control::{ControlStackFrame, ControlStackFrameType},
-    Function, Result,
+    Error, Function, Result,
 };
 use wasmparser::{BlockType, BrTable};
+use zabi::Param;
 
 impl Function {
     /// The beginning of an if construct with an implicit block.
@@ -147,8 +148,11 @@ impl Function {
     /// Performs an indirect branch through an operand indexing into the
     /// label vector that is an immediate to the instruction, or to the
     /// default target if the operand is out of bounds.
+    ///
+    /// NOTE: this is rejected before the code generation, see
+    /// [`crate::unsupported_operator`].
     pub fn _br_table(&mut self, _table: BrTable<'_>) -> Result<()> {
-        todo!()
+        Err(Error::UnsupportedOperator("BrTable"))
     }
 
     /// Handle the end of instructions for different situations.
@@ -161,13 +165,33 @@ impl Function {
             return self.handle_frame_popping(frame);
         }
 
-        let results = self.ty.results();
         if self.is_main || self.abi.is_some() {
             tracing::trace!("end of main function");
-            self.masm.main_return(results)
+            self.sign_extend_output()?;
+            self.masm.main_return(self.ty.results())
         } else {
             tracing::trace!("end of call");
-            self.masm.call_return(results)
+            self.masm.call_return(self.ty.results())
+        }
+    }
+
+    /// Sign-extend the signed output of the external function to 256
+    /// bits, since the integers are zero-extended on the stack.
+    fn sign_extend_output(&mut self) -> Result<()> {
+        let Some(abi) = self.abi.as_ref() else {
+            return Ok(());
+        };
+
+        if self.ty.results().len() != 1 || abi.outputs.len() != 1 {
+            return Ok(());
+        }
+
+        match abi.outputs[0].ty {
+            Param::Int8 => self.masm._sign_extend(1),
+            Param::Int16 => self.masm._sign_extend(2),
+            Param::Int32 => self.masm._sign_extend(4),
+            Param::Int64 => self.masm._sign_extend(8),
+            _ => Ok(()),
         }
     }
 
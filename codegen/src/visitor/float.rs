@@ -0,0 +1,331 @@
+//! Float instructions
+//!
+//! Floats are kept as their bits on the stack, the arithmetic is lowered
+//! to the routines of the soft-float runtime, while the sign operations
+//! are done with bitwise instructions in place.
+
+use crate::{wasm::ToLSBytes, Float, Function, Result, Routine};
+
+impl Function {
+    /// Addition of floats.
+    ///
+    /// STACK: [a, b] -> [a + b]
+    pub fn _float_add(&mut self, float: Float) -> Result<()> {
+        self.routine(Routine::Add(float))
+    }
+
+    /// Subtraction of floats, which adds `a` and `-b`.
+    ///
+    /// STACK: [a, b] -> [a - b]
+    pub fn _float_sub(&mut self, float: Float) -> Result<()> {
+        self._float_neg(float)?;
+        self.routine(Routine::Add(float))
+    }
+
+    /// Multiplication of floats.
+    ///
+    /// STACK: [a, b] -> [a * b]
+    pub fn _float_mul(&mut self, float: Float) -> Result<()> {
+        self.routine(Routine::Mul(float))
+    }
+
+    /// Division of floats.
+    ///
+    /// STACK: [a, b] -> [a / b]
+    pub fn _float_div(&mut self, float: Float) -> Result<()> {
+        self.routine(Routine::Div(float))
+    }
+
+    /// Minimum of floats.
+    ///
+    /// STACK: [a, b] -> [min(a, b)]
+    pub fn _float_min(&mut self, float: Float) -> Result<()> {
+        self.routine(Routine::Min(float))
+    }
+
+    /// Maximum of floats.
+    ///
+    /// STACK: [a, b] -> [max(a, b)]
+    pub fn _float_max(&mut self, float: Float) -> Result<()> {
+        self.routine(Routine::Max(float))
+    }
+
+    /// Square root of a float.
+    ///
+    /// STACK: [a] -> [sqrt(a)]
+    pub fn _float_sqrt(&mut self, float: Float) -> Result<()> {
+        self.routine(Routine::Sqrt(float))
+    }
+
+    /// Round a float up to an integral float.
+    ///
+    /// STACK: [a] -> [ceil(a)]
+    pub fn _float_ceil(&mut self, float: Float) -> Result<()> {
+        self.routine(Routine::Ceil(float))
+    }
+
+    /// Round a float down to an integral float.
+    ///
+    /// STACK: [a] -> [floor(a)]
+    pub fn _float_floor(&mut self, float: Float) -> Result<()> {
+        self.routine(Routine::Floor(float))
+    }
+
+    /// Round a float towards zero to an integral float.
+    ///
+    /// STACK: [a] -> [trunc(a)]
+    pub fn _float_trunc(&mut self, float: Float) -> Result<()> {
+        self.routine(Routine::Trunc(float))
+    }
+
+    /// Round a float to the nearest integral float, ties to even.
+    ///
+    /// STACK: [a] -> [nearest(a)]
+    pub fn _float_nearest(&mut self, float: Float) -> Result<()> {
+        self.routine(Routine::Nearest(float))
+    }
+
+    /// If two floats are equal.
+    ///
+    /// STACK: [a, b] -> [a == b]
+    pub fn _float_eq(&mut self, float: Float) -> Result<()> {
+        self.compare(float, 0b010)
+    }
+
+    /// If two floats are not equal, which is true for NaN.
+    ///
+    /// STACK: [a, b] -> [a != b]
+    pub fn _float_ne(&mut self, float: Float) -> Result<()> {
+        self.compare(float, 0b010)?;
+        self.masm._iszero()
+    }
+
+    /// If a float is less than the other one.
+    ///
+    /// STACK: [a, b] -> [a < b]
+    pub fn _float_lt(&mut self, float: Float) -> Result<()> {
+        self.compare(float, 0b001)
+    }
+
+    /// If a float is greater than the other one.
+    ///
+    /// STACK: [a, b] -> [a > b]
+    pub fn _float_gt(&mut self, float: Float) -> Result<()> {
+        self.compare(float, 0b100)
+    }
+
+    /// If a float is less than or equal to the other one.
+    ///
+    /// STACK: [a, b] -> [a <= b]
+    pub fn _float_le(&mut self, float: Float) -> Result<()> {
+        self.compare(float, 0b011)
+    }
+
+    /// If a float is greater than or equal to the other one.
+    ///
+    /// STACK: [a, b] -> [a >= b]
+    pub fn _float_ge(&mut self, float: Float) -> Result<()> {
+        self.compare(float, 0b110)
+    }
+
+    /// Absolute value of a float, which clears the sign bit.
+    ///
+    /// STACK: [a] -> [|a|]
+    pub fn _float_abs(&mut self, float: Float) -> Result<()> {
+        self.masm.push(&(float.sign() - 1).to_ls_bytes())?;
+        self.masm._and()
+    }
+
+    /// Negation of a float, which flips the sign bit.
+    ///
+    /// STACK: [a] -> [-a]
+    pub fn _float_neg(&mut self, float: Float) -> Result<()> {
+        self.masm.push(&float.sign().to_ls_bytes())?;
+        self.masm._xor()
+    }
+
+    /// Copy the sign bit of `b` to `a`.
+    ///
+    /// STACK: [a, b] -> [copysign(a, b)]
+    pub fn _float_copysign(&mut self, float: Float) -> Result<()> {
+        self.masm.push(&float.sign().to_ls_bytes())?;
+        self.masm._and()?;
+        self.masm._swap1()?;
+        self._float_abs(float)?;
+        self.masm._or()
+    }
+
+    /// Convert a signed 32-bit integer to a float.
+    ///
+    /// STACK: [i] -> [a]
+    pub fn _float_convert_i32_s(&mut self, float: Float) -> Result<()> {
+        self.convert_int(float, Some(4))
+    }
+
+    /// Convert an unsigned 32-bit integer to a float.
+    ///
+    /// STACK: [i] -> [a]
+    pub fn _float_convert_i32_u(&mut self, float: Float) -> Result<()> {
+        self.convert_int(float, None)
+    }
+
+    /// Convert a signed 64-bit integer to a float.
+    ///
+    /// STACK: [i] -> [a]
+    pub fn _float_convert_i64_s(&mut self, float: Float) -> Result<()> {
+        self.convert_int(float, Some(8))
+    }
+
+    /// Convert an unsigned 64-bit integer to a float.
+    ///
+    /// STACK: [i] -> [a]
+    pub fn _float_convert_i64_u(&mut self, float: Float) -> Result<()> {
+        self.convert_int(float, None)
+    }
+
+    /// Demote a 64-bit float to a 32-bit float.
+    pub fn _f32_demote_f64(&mut self) -> Result<()> {
+        self.routine(Routine::Convert(Float::F64))
+    }
+
+    /// Promote a 32-bit float to a 64-bit float.
+    pub fn _f64_promote_f32(&mut self) -> Result<()> {
+        self.routine(Routine::Convert(Float::F32))
+    }
+
+    /// Truncate a 32-bit float to a signed 32-bit integer.
+    pub fn _i32_trunc_f32_s(&mut self) -> Result<()> {
+        self.trunc_float(Float::F32, 32, true)
+    }
+
+    /// Truncate a 32-bit float to an unsigned 32-bit integer.
+    pub fn _i32_trunc_f32_u(&mut self) -> Result<()> {
+        self.trunc_float(Float::F32, 32, false)
+    }
+
+    /// Truncate a 64-bit float to a signed 32-bit integer.
+    pub fn _i32_trunc_f64_s(&mut self) -> Result<()> {
+        self.trunc_float(Float::F64, 32, true)
+    }
+
+    /// Truncate a 64-bit float to an unsigned 32-bit integer.
+    pub fn _i32_trunc_f64_u(&mut self) -> Result<()> {
+        self.trunc_float(Float::F64, 32, false)
+    }
+
+    /// Truncate a 32-bit float to a signed 64-bit integer.
+    pub fn _i64_trunc_f32_s(&mut self) -> Result<()> {
+        self.trunc_float(Float::F32, 64, true)
+    }
+
+    /// Truncate a 32-bit float to an unsigned 64-bit integer.
+    pub fn _i64_trunc_f32_u(&mut self) -> Result<()> {
+        self.trunc_float(Float::F32, 64, false)
+    }
+
+    /// Truncate a 64-bit float to a signed 64-bit integer.
+    pub fn _i64_trunc_f64_s(&mut self) -> Result<()> {
+        self.trunc_float(Float::F64, 64, true)
+    }
+
+    /// Truncate a 64-bit float to an unsigned 64-bit integer.
+    pub fn _i64_trunc_f64_u(&mut self) -> Result<()> {
+        self.trunc_float(Float::F64, 64, false)
+    }
+
+    /// Call a routine of the soft-float runtime.
+    fn routine(&mut self, routine: Routine) -> Result<()> {
+        routine.call(&mut self.masm, &mut self.table)
+    }
+
+    /// Compare two floats and test the flags of the result.
+    ///
+    /// STACK: [a, b] -> [flags & result != 0]
+    fn compare(&mut self, float: Float, flags: u8) -> Result<()> {
+        self.routine(Routine::Compare(float))?;
+        self.masm.push(&[flags])?;
+        self.masm._and()?;
+        self.masm._iszero()?;
+        self.masm._iszero()
+    }
+
+    /// Convert an integer to a float, the signed integers are
+    /// sign-extended from `bytes` and split into the sign and the
+    /// magnitude.
+    ///
+    /// STACK: [i] -> [a]
+    fn convert_int(&mut self, float: Float, signed: Option<u8>) -> Result<()> {
+        if let Some(bytes) = signed {
+            // [sign, value]
+            self.masm._sign_extend(bytes)?;
+            self.masm._dup1()?;
+            self.masm.push(&[0xff])?;
+            self.masm.asm._shr()?;
+            self.masm._swap1()?;
+
+            // [sign, (value ^ -sign) + sign]
+            self.masm._dup2()?;
+            self.masm._push0()?;
+            self.masm.asm._sub()?;
+            self.masm._xor()?;
+            self.masm._dup2()?;
+            self.masm._add()?;
+        } else {
+            self.masm._push0()?;
+            self.masm._swap1()?;
+        }
+
+        // [sign, magnitude, 0]
+        self.masm._push0()?;
+        self.routine(Routine::Pack(float))
+    }
+
+    /// Truncate a float to an integer of `bits`, traps if the result
+    /// is out of the range of the integer type.
+    ///
+    /// STACK: [a] -> [i]
+    fn trunc_float(&mut self, float: Float, bits: u8, signed: bool) -> Result<()> {
+        // [sign, magnitude, in range]
+        self.routine(Routine::ToInt(float))?;
+        if signed {
+            // magnitude < 2^(bits - 1) + sign
+            self.masm._dup1()?;
+            self.masm._dup3()?;
+            self.masm.push(&(1u64 << (bits - 1)).to_ls_bytes())?;
+            self.masm._add()?;
+            self.masm.asm._gt()?;
+        } else {
+            // magnitude < 2^bits && (sign == 0 || magnitude == 0)
+            let limit = (1u128 << bits).to_be_bytes();
+            let start = limit.iter().position(|b| *b != 0).unwrap_or_default();
+            self.masm._dup1()?;
+            self.masm.push(&limit[start..])?;
+            self.masm.asm._gt()?;
+            self.masm._dup2()?;
+            self.masm._iszero()?;
+            self.masm._dup4()?;
+            self.masm._iszero()?;
+            self.masm._or()?;
+            self.masm._and()?;
+        }
+
+        self.table.label(self.masm.pc(), self.masm.pc() + 2);
+        self.masm.increment_sp(1)?;
+        self.masm._jumpi()?;
+        self.masm._invalid()?;
+        self.masm._jumpdest()?;
+
+        if !signed {
+            self.masm._swap1()?;
+            return self.masm._pop();
+        }
+
+        // [(magnitude ^ -sign) + sign] wrapped to the integer type
+        self.masm._dup2()?;
+        self.masm._push0()?;
+        self.masm.asm._sub()?;
+        self.masm._xor()?;
+        self.masm._add()?;
+        self.masm._wrap(bits)
+    }
+}
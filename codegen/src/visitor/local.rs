@@ -1,14 +1,20 @@
 //! Local instructions
 
-use crate::{wasm::ToLSBytes, Error, Function, Result};
+use crate::{wasm::ToLSBytes, Access, Error, Function, Result};
+use wasmparser::ValType;
+use zabi::Param;
 
 impl Function {
     /// This instruction gets the value of a variable.
     pub fn _local_get(&mut self, local_index: u32) -> Result<()> {
+        let access = self.stack.access();
         let local_index = local_index as usize;
         if (self.is_main || self.abi.is_some()) && local_index < self.ty.params().len() {
             // Parsing data from selector.
             self._local_get_calldata(local_index)
+        } else if access != Access::Memory {
+            // Reaching the local kept on the stack.
+            self._local_get_stack(access)
         } else {
             // Passing data between local functions.
             self._local_get_var(local_index)
@@ -17,28 +23,56 @@ impl Function {
 
     /// This instruction sets the value of a variable.
     pub fn _local_set(&mut self, local_index: u32) -> Result<()> {
-        self.masm.push(&self.env.alloc(local_index))?;
-        self.masm._mstore()?;
-
-        Ok(())
+        match self.stack.access() {
+            Access::Keep => Ok(()),
+            Access::Replace(depth) => {
+                self.masm.swap(depth as u16)?;
+                self.masm._pop()
+            }
+            _ => {
+                self.masm.push(&self.env.alloc(local_index))?;
+                self.masm._mstore()
+            }
+        }
     }
 
     /// This _local_tee is like _local_set, but it also returns the value
     /// on the stack.
     pub fn _local_tee(&mut self, index: u32) -> Result<()> {
+        if self.stack.contains(index) {
+            self._local_set(index)?;
+            let access = self.stack.access();
+            return self._local_get_stack(access);
+        }
+
         self.masm._dup1()?;
         self._local_set(index)?;
+
+        // Skip the access of getting.
+        self.stack.access();
         Ok(())
     }
 
-    /// This instruction gets the value of a variable.
-    pub fn _global_get(&mut self, _: u32) -> Result<()> {
-        todo!()
+    /// This instruction gets the value of a global.
+    pub fn _global_get(&mut self, global_index: u32) -> Result<()> {
+        if !self.env.globals.contains_key(&global_index) {
+            return Err(Error::InvalidGlobalIndex(global_index));
+        }
+
+        self.masm.push(&self.env.global(global_index))?;
+        self.masm._mload()?;
+        Ok(())
     }
 
-    /// This instruction sets the value of a variable.
-    pub fn _global_set(&mut self, _: u32) -> Result<()> {
-        todo!()
+    /// This instruction sets the value of a global.
+    pub fn _global_set(&mut self, global_index: u32) -> Result<()> {
+        if !self.env.globals.contains_key(&global_index) {
+            return Err(Error::InvalidGlobalIndex(global_index));
+        }
+
+        self.masm.push(&self.env.global(global_index))?;
+        self.masm._mstore()?;
+        Ok(())
     }
 
     /// Local get from calldata.
@@ -50,8 +84,60 @@ impl Function {
 
         self.masm.push(&offset)?;
         self.masm._calldataload()?;
+        self._mask_calldata(local_index)
+    }
 
-        Ok(())
+    /// Mask the calldata word of the parameter to its width, the words
+    /// are not trusted even if the calldata has been validated.
+    ///
+    /// The parameters are masked by their ABI types if the function
+    /// is dispatched, the signed ones are sign-extended first, and by
+    /// their WASM types otherwise.
+    fn _mask_calldata(&mut self, local_index: usize) -> Result<()> {
+        let width = match self.ty.params()[local_index] {
+            ValType::I32 | ValType::F32 => 32,
+            ValType::I64 | ValType::F64 => 64,
+            _ => return Ok(()),
+        };
+
+        let Some(abi) = self.abi.as_ref() else {
+            return self.masm._wrap(width);
+        };
+
+        match abi.inputs.get(local_index).map(|arg| &arg.ty) {
+            Some(param @ (Param::Int8 | Param::Int16 | Param::Int32 | Param::Int64)) => {
+                let bits = match param {
+                    Param::Int8 => 8,
+                    Param::Int16 => 16,
+                    Param::Int32 => 32,
+                    _ => 64,
+                };
+
+                if bits < width {
+                    self.masm._sign_extend(bits / 8)?;
+                }
+                self.masm._wrap(width)
+            }
+            Some(Param::UInt8) => self.masm._wrap(8),
+            Some(Param::UInt16) => self.masm._wrap(16),
+            Some(Param::UInt32) => self.masm._wrap(32.min(width)),
+            Some(Param::UInt64) => self.masm._wrap(width),
+            Some(Param::Bool) => self.masm._wrap(1),
+            Some(Param::Address) => {
+                self.masm.push(&[0xff; 20])?;
+                self.masm.asm._and()
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Local get for the variables kept on the stack.
+    fn _local_get_stack(&mut self, access: Access) -> Result<()> {
+        match access {
+            Access::Dup(depth) => self.masm.dup(depth as u16),
+            Access::Take(2) => self.masm._swap1(),
+            _ => Ok(()),
+        }
     }
 
     /// Local get for variables.
@@ -1,60 +1,37 @@
 //! System instructions
 
 use crate::{masm::MemoryInfo, wasm::ToLSBytes, Error, Function, Result};
+use opcodes::{disassemble, Group, Instruction, OpCode};
 
 impl Function {
     /// Parse log data from the bytecode.
     ///
     /// WASM example:
-    /// ```
+    /// ```text
     /// i32.const 1048576   ;; offset
     /// i32.const 4         ;; 4 bytes
     /// ```
-    fn data(&mut self) -> Result<(i32, i32)> {
-        let buffer: Vec<u8> = self.masm.buffer().into();
-
-        // Pop offset and size from the bytecode.
-        //
-        // TODO: backtrace should cross the whole codegen,
-        // embed stack operations. (#155)
-        let data_len = self.backtrace.popn(2).concat().len();
-        self.masm.decrement_sp(2)?;
-
-        let data = &buffer[(buffer.len() - data_len)..];
-        *self.masm.buffer_mut() = buffer[..(buffer.len() - data_len)].into();
-
-        // Parse offset.
-        //
-        // PUSH0 0x5e
-        // ..
-        // PUSH32 0x8f
-        if !(0x5e..0x8f).contains(&data[0]) {
-            return Err(Error::InvalidDataOffset(data[0].into()));
-        }
-
-        let offset_len = (data[0] - 0x5f) as usize;
-        tracing::trace!("offset len: {offset_len}");
-        let offset = {
-            let mut bytes = [0; 4];
-            bytes[(4 - offset_len)..].copy_from_slice(&data[1..(offset_len + 1)]);
-            bytes.reverse();
-            i32::from_le_bytes(bytes)
+    ///
+    /// The pushes of the constants are located with the source map
+    /// and removed from the code.
+    pub(crate) fn data(&mut self) -> Result<(i32, i32)> {
+        let pc = self.masm.pc();
+        let start = self.table.srcmap.nth_back(pc, 1).unwrap_or(pc);
+        let instrs = disassemble(&self.masm.buffer()[start as usize..]);
+        let [offset, size] = instrs.as_slice() else {
+            let byte = instrs.first().map(|instr| instr.byte).unwrap_or_default();
+            return Err(Error::InvalidDataOffset(byte.into()));
         };
-        tracing::debug!("log offset: {:?}", offset);
 
-        // Parse size.
-        if !(0x5e..0x8f).contains(&data[offset_len + 1]) {
-            return Err(Error::InvalidDataOffset(data[offset_len + 1].into()));
-        }
-        let size = {
-            // TODO: from ls bytes as offset
-            let mut bytes = [0; 4];
-            let size_bytes = &data[(offset_len + 2)..];
-            bytes[..size_bytes.len()].copy_from_slice(size_bytes);
-            i32::from_le_bytes(bytes)
-        };
+        let offset = push_value(offset).ok_or(Error::InvalidDataOffset(offset.byte.into()))?;
+        tracing::debug!("log offset: {:?}", offset);
 
+        let size = push_value(size).ok_or(Error::InvalidDataSize(size.byte as usize))?;
         tracing::debug!("log size: {:?}", size);
+
+        self.masm.buffer_mut().truncate(start as usize);
+        self.masm.decrement_sp(2)?;
+        self.table.srcmap.remove(&[(start, pc)]);
         Ok((offset, size))
     }
 
@@ -114,3 +91,15 @@ impl Function {
         Ok(())
     }
 }
+
+/// Get the value pushed by the instruction if it is a `PUSHn` of
+/// at most 4 bytes.
+fn push_value(instr: &Instruction) -> Option<i32> {
+    if instr.opcode?.group() != Group::Push || instr.immediate.len() > 4 {
+        return None;
+    }
+
+    let mut bytes = [0; 4];
+    bytes[(4 - instr.immediate.len())..].copy_from_slice(&instr.immediate);
+    Some(i32::from_be_bytes(bytes))
+}
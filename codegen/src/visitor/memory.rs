@@ -0,0 +1,185 @@
+//! Memory instructions
+
+use crate::{wasm::ToLSBytes, Function, Result};
+use wasmparser::MemArg;
+
+impl Function {
+    /// Load `bytes` from the linear memory and extend them
+    /// to an integer of `width` bytes.
+    ///
+    /// STACK: [address] -> [value]
+    pub fn _load(&mut self, memarg: MemArg, bytes: u8, width: u8, signed: bool) -> Result<()> {
+        self.memory_address(memarg)?;
+        self.masm._load(bytes, width, signed)
+    }
+
+    /// Store the lowest `bytes` of a value to the linear memory.
+    ///
+    /// STACK: [address, value] -> []
+    pub fn _store(&mut self, memarg: MemArg, bytes: u8) -> Result<()> {
+        self.masm._swap1()?;
+        self.memory_address(memarg)?;
+        self.masm._store(bytes)
+    }
+
+    /// The memory size instruction returns the current
+    /// size of memory in pages.
+    ///
+    /// STACK: [] -> [pages]
+    pub fn _memory_size(&mut self, _mem: u32, _mem_byte: u8) -> Result<()> {
+        self.masm.push(&(self.env.pages * 0x20).to_ls_bytes())?;
+        self.masm._mload()
+    }
+
+    /// The memory grow instruction grows memory by a given
+    /// delta and returns the previous size, or -1 if enough
+    /// memory cannot be allocated.
+    ///
+    /// Only the page count is updated since EVM memory expands
+    /// on accessing, the memory fails to grow past the maximum
+    /// pages of the module or the compiler configuration.
+    ///
+    /// STACK: [delta] -> [pages | -1]
+    pub fn _memory_grow(&mut self, _mem: u32, _mem_byte: u8) -> Result<()> {
+        let pages = (self.env.pages * 0x20).to_ls_bytes();
+
+        // [old, new]
+        self.masm.push(&pages)?;
+        self.masm._mload()?;
+        self.masm._swap1()?;
+        self.masm._dup2()?;
+        self.masm._add()?;
+
+        // Jump to the failure if the maximum pages is exceeded.
+        self.masm._dup1()?;
+        self.masm.push(&self.env.max_pages.to_ls_bytes())?;
+        self.masm.asm._lt()?;
+        let failure = self.masm.pc();
+        self.masm.increment_sp(1)?;
+        self.masm._jumpi()?;
+
+        // [old] with the page count updated
+        self.masm.push(&pages)?;
+        self.masm._mstore()?;
+        let exit = self.masm.pc();
+        self.masm.increment_sp(1)?;
+        self.masm._jump()?;
+
+        // [old, new] -> [-1]
+        self.table.label(failure, self.masm.pc());
+        self.masm.increment_sp(1)?;
+        self.masm._jumpdest()?;
+        self.masm._pop()?;
+        self.masm._pop()?;
+        self.masm._i32_const(-1)?;
+
+        self.table.label(exit, self.masm.pc());
+        self.masm._jumpdest()
+    }
+
+    /// The memory copy instruction copies `size` bytes from
+    /// `src` to `dst`, compiled to `MCOPY` which handles the
+    /// overlapping regions as well.
+    ///
+    /// STACK: [dst, src, size] -> []
+    pub fn _memory_copy(&mut self, _dst_mem: u32, _src_mem: u32) -> Result<()> {
+        // [size, src, dst]
+        self.masm._swap2()?;
+        self.memory_offset(0)?;
+        self.masm._swap1()?;
+        self.memory_offset(0)?;
+        self.masm._swap1()?;
+
+        self.masm._mcopy()
+    }
+
+    /// The memory fill instruction sets `size` bytes starting
+    /// at `dst` to the lowest byte of `value`.
+    ///
+    /// The first byte is stored with `MSTORE8`, then the filled
+    /// bytes are doubled with `MCOPY` until the rest of them are
+    /// fewer than the filled ones, which are copied at last.
+    ///
+    /// STACK: [dst, value, size] -> []
+    pub fn _memory_fill(&mut self, _mem: u32) -> Result<()> {
+        // [dst, value, size]
+        self.masm._swap2()?;
+        self.memory_offset(0)?;
+        self.masm._swap2()?;
+
+        // Skip filling if the size is zero.
+        self.masm._dup1()?;
+        self.masm._iszero()?;
+        let empty = self.masm.pc();
+        self.masm.increment_sp(1)?;
+        self.masm._jumpi()?;
+
+        // [dst, size, filled]
+        self.masm._swap1()?;
+        self.masm._dup3()?;
+        self.masm._mstore8()?;
+        self.masm.push(&[1])?;
+
+        // Exit the loop if `size < filled * 2`.
+        let doubling = self.masm.pc();
+        self.masm._jumpdest()?;
+        self.masm._dup1()?;
+        self.masm._dup1()?;
+        self.masm._add()?;
+        self.masm._dup3()?;
+        self.masm.asm._lt()?;
+        let rest = self.masm.pc();
+        self.masm.increment_sp(1)?;
+        self.masm._jumpi()?;
+
+        // [dst, size, filled * 2]
+        self.masm._dup1()?;
+        self.masm._dup4()?;
+        self.masm._dup2()?;
+        self.masm._dup2()?;
+        self.masm._add()?;
+        self.masm._mcopy()?;
+        self.masm._dup1()?;
+        self.masm._add()?;
+        self.table.label(self.masm.pc(), doubling);
+        self.masm.increment_sp(1)?;
+        self.masm._jump()?;
+
+        // [dst, size, filled] with the rest bytes copied
+        self.table.label(rest, self.masm.pc());
+        self.masm._jumpdest()?;
+        self.masm._dup1()?;
+        self.masm._dup3()?;
+        self.masm.asm._sub()?;
+        self.masm._dup4()?;
+        self.masm._dup1()?;
+        self.masm._dup4()?;
+        self.masm._add()?;
+        self.masm._mcopy()?;
+
+        self.table.label(empty, self.masm.pc());
+        self.masm._jumpdest()?;
+        self.masm._pop()?;
+        self.masm._pop()?;
+        self.masm._pop()
+    }
+
+    /// Translate the address of the linear memory to EVM memory.
+    ///
+    /// The alignment of `memarg` is ignored since it is only a hint
+    /// in WASM, EVM memory supports unaligned accesses anyway.
+    ///
+    /// STACK: [address] -> [address]
+    fn memory_address(&mut self, memarg: MemArg) -> Result<()> {
+        self.memory_offset(memarg.offset)
+    }
+
+    /// Add the offset of the linear memory and `offset` to the address.
+    ///
+    /// STACK: [address] -> [address]
+    fn memory_offset(&mut self, offset: u64) -> Result<()> {
+        let offset = self.env.memory as u64 + offset;
+        self.masm.push(&offset.to_ls_bytes())?;
+        self.masm._add()
+    }
+}
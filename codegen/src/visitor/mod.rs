@@ -4,30 +4,44 @@
 //! `Function`; which defines a visitor per op-code, which validates
 //! and dispatches to the corresponding machine code emitter.
 
-use crate::{Function, Result};
+use crate::{Error, Float, Function, Result};
 use paste::paste;
 use tracing::trace;
-use wasmparser::{for_each_operator, BlockType, BrTable, Ieee32, Ieee64, MemArg, VisitOperator};
+use wasmparser::{
+    for_each_operator, BlockType, BrTable, Ieee32, Ieee64, MemArg, Operator, VisitOperator,
+};
 
+mod asm;
 mod call;
 mod control;
+mod float;
 mod local;
 mod log;
+mod memory;
 
 /// A macro to define unsupported WebAssembly operators.
 ///
 /// This macro calls itself recursively;
 /// 1. It no-ops when matching a supported operator.
-/// 2. Defines the visitor function and panics when
-///    matching an unsupported operator.
+/// 2. Defines the visitor function and returns an error
+///    when matching an unsupported operator.
 macro_rules! impl_visit_operator {
     ( @mvp $op:ident $({ $($arg:ident: $argty:ty),* })? => $visit:ident $($rest:tt)* ) => {
         impl_visit_operator!($($rest)*);
     };
+    ( @sign_extension $op:ident $({ $($arg:ident: $argty:ty),* })? => $visit:ident $($rest:tt)* ) => {
+        impl_visit_operator!($($rest)*);
+    };
+    ( @bulk_memory MemoryCopy $({ $($arg:ident: $argty:ty),* })? => $visit:ident $($rest:tt)* ) => {
+        impl_visit_operator!($($rest)*);
+    };
+    ( @bulk_memory MemoryFill $({ $($arg:ident: $argty:ty),* })? => $visit:ident $($rest:tt)* ) => {
+        impl_visit_operator!($($rest)*);
+    };
     ( @$proposal:ident $op:ident $({ $($arg:ident: $argty:ty),* })? => $visit:ident $($rest:tt)* ) => {
-        fn $visit(&mut self $($(, $arg: $argty)*)?) -> Self::Output {
+        fn $visit(&mut self $($(, _: $argty)*)?) -> Self::Output {
             trace!("{}", stringify!($op));
-            Ok(())
+            Err(Error::UnsupportedOperator(stringify!($op)))
         }
 
         impl_visit_operator!($($rest)*);
@@ -35,6 +49,113 @@ macro_rules! impl_visit_operator {
     () => {};
 }
 
+/// A macro to check if the WebAssembly operators are supported.
+///
+/// The operators of the proposals are unsupported except
+/// the ones implemented in `impl_visit_operator`, so are the
+/// `br_table` and the bit counting and rotating operators of the MVP.
+macro_rules! impl_unsupported_operator {
+    (@check $operator:ident @mvp BrTable) => {
+        impl_unsupported_operator!(@check $operator @unimplemented BrTable);
+    };
+    (@check $operator:ident @mvp I32Clz) => {
+        impl_unsupported_operator!(@check $operator @unimplemented I32Clz);
+    };
+    (@check $operator:ident @mvp I32Ctz) => {
+        impl_unsupported_operator!(@check $operator @unimplemented I32Ctz);
+    };
+    (@check $operator:ident @mvp I32Popcnt) => {
+        impl_unsupported_operator!(@check $operator @unimplemented I32Popcnt);
+    };
+    (@check $operator:ident @mvp I32Rotl) => {
+        impl_unsupported_operator!(@check $operator @unimplemented I32Rotl);
+    };
+    (@check $operator:ident @mvp I32Rotr) => {
+        impl_unsupported_operator!(@check $operator @unimplemented I32Rotr);
+    };
+    (@check $operator:ident @mvp I64Clz) => {
+        impl_unsupported_operator!(@check $operator @unimplemented I64Clz);
+    };
+    (@check $operator:ident @mvp I64Ctz) => {
+        impl_unsupported_operator!(@check $operator @unimplemented I64Ctz);
+    };
+    (@check $operator:ident @mvp I64Popcnt) => {
+        impl_unsupported_operator!(@check $operator @unimplemented I64Popcnt);
+    };
+    (@check $operator:ident @mvp I64Rotl) => {
+        impl_unsupported_operator!(@check $operator @unimplemented I64Rotl);
+    };
+    (@check $operator:ident @mvp I64Rotr) => {
+        impl_unsupported_operator!(@check $operator @unimplemented I64Rotr);
+    };
+    (@check $operator:ident @mvp $op:ident) => {};
+    (@check $operator:ident @sign_extension $op:ident) => {};
+    (@check $operator:ident @bulk_memory MemoryCopy) => {};
+    (@check $operator:ident @bulk_memory MemoryFill) => {};
+    (@check $operator:ident @$proposal:ident $op:ident) => {
+        if matches!($operator, Operator::$op { .. }) {
+            return Some(stringify!($op));
+        }
+    };
+    ($( @$proposal:ident $op:ident $({ $($arg:ident: $argty:ty),* })? => $visit:ident )*) => {
+        /// Returns the name of the operator if it is not supported
+        /// by the code generation.
+        pub fn unsupported_operator(operator: &Operator) -> Option<&'static str> {
+            $(impl_unsupported_operator!(@check operator @$proposal $op);)*
+            None
+        }
+    };
+}
+
+for_each_operator!(impl_unsupported_operator);
+
+/// A macro to check if the WebAssembly operators are float operators.
+///
+/// The float operators except the loads, stores, constants and
+/// reinterpretations are lowered to the soft-float runtime.
+macro_rules! impl_float_operator {
+    (@check $operator:ident @mvp $op:ident) => {
+        if matches!($operator, Operator::$op { .. }) {
+            let name = stringify!($op);
+            let float = name.contains("F32") || name.contains("F64");
+            let bits = ["Load", "Store", "Const", "Reinterpret"]
+                .iter()
+                .any(|kind| name.contains(kind));
+            return (float && !bits).then_some(name);
+        }
+    };
+    (@check $operator:ident @$proposal:ident $op:ident) => {};
+    ($( @$proposal:ident $op:ident $({ $($arg:ident: $argty:ty),* })? => $visit:ident )*) => {
+        /// Returns the name of the operator if it requires the
+        /// soft-float runtime.
+        pub fn float_operator(operator: &Operator) -> Option<&'static str> {
+            $(impl_float_operator!(@check operator @$proposal $op);)*
+            None
+        }
+    };
+}
+
+for_each_operator!(impl_float_operator);
+
+/// A macro to check if the WebAssembly operators read the linear memory.
+macro_rules! impl_memory_read {
+    (@check $operator:ident @$proposal:ident $op:ident) => {
+        if matches!($operator, Operator::$op { .. }) {
+            let name = stringify!($op);
+            return name.contains("Load") || name == "MemoryCopy";
+        }
+    };
+    ($( @$proposal:ident $op:ident $({ $($arg:ident: $argty:ty),* })? => $visit:ident )*) => {
+        /// Returns if the operator reads the linear memory.
+        pub fn memory_read(operator: &Operator) -> bool {
+            $(impl_memory_read!(@check operator @$proposal $op);)*
+            false
+        }
+    };
+}
+
+for_each_operator!(impl_memory_read);
+
 /// Implement arithmetic operators for types.
 macro_rules! map_wasm_operators {
     (@basic $ty:tt, $wasm:tt, $evm:tt $($arg:ident: $argty:ty),*) => {
@@ -42,12 +163,8 @@ macro_rules! map_wasm_operators {
             fn [< visit_ $ty _ $wasm >](&mut self $(,$arg: $argty),*) -> Self::Output {
                 trace!("{}.{}", stringify!($ty), stringify!($evm));
 
-                let before = self.masm.buffer().len();
                 self.masm.[< _ $evm >]()?;
 
-                let instr = self.masm.buffer()[before..].to_vec();
-                self.backtrace.push(instr);
-
                 Ok(())
             }
         }
@@ -62,26 +179,51 @@ macro_rules! map_wasm_operators {
         map_wasm_operators!(@integer32 $wasm, $evm $($arg: $argty),*);
         map_wasm_operators!(@integer64 $wasm, $evm $($arg: $argty),*);
     };
-    (@xdr $wasm:tt, $evm:tt $($arg:ident: $argty:ty),*) => {
-        paste!{
-            map_wasm_operators!(@integer [< $wasm _s >], $evm $($arg: $argty),*);
-            map_wasm_operators!(@integer [< $wasm _u >], $evm $($arg: $argty),*);
+    (@soft $ty:tt, $float:tt, $wasm:tt, $evm:tt) => {
+        paste! {
+            fn [< visit_ $ty _ $wasm >](&mut self) -> Self::Output {
+                trace!("{}.{}", stringify!($ty), stringify!($evm));
+
+                self.[< _float_ $evm >](Float::$float)?;
+
+                Ok(())
+            }
         }
     };
-    (@float32 $wasm:tt, $evm:tt $($arg:ident: $argty:ty),*) => {
-        map_wasm_operators!(@basic f32, $wasm, $evm $($arg: $argty),*);
+    (@float32 $wasm:tt, $evm:tt) => {
+        map_wasm_operators!(@soft f32, F32, $wasm, $evm);
     };
-    (@float64 $wasm:tt, $evm:tt $($arg:ident: $argty:ty),*) => {
-        map_wasm_operators!(@basic f64, $wasm, $evm $($arg: $argty),*);
+    (@float64 $wasm:tt, $evm:tt) => {
+        map_wasm_operators!(@soft f64, F64, $wasm, $evm);
     };
-    (@float $wasm:tt, $evm:tt $($arg:ident: $argty:ty),*) => {
-        map_wasm_operators!(@float32 $wasm, $evm $($arg: $argty),*);
-        map_wasm_operators!(@float64 $wasm, $evm $($arg: $argty),*);
+    (@float $wasm:tt, $evm:tt) => {
+        map_wasm_operators!(@float32 $wasm, $evm);
+        map_wasm_operators!(@float64 $wasm, $evm);
     };
     (@integer_and_float $op:tt $($arg:ident: $argty:ty),*) => {
         map_wasm_operators!(@integer $op, $op);
         map_wasm_operators!(@float $op, $op);
     };
+    (@load $ty:tt $op:tt ($bytes:expr, $width:expr, $signed:expr)) => {
+        paste! {
+            fn [< visit_ $ty _ $op >](&mut self, memarg: MemArg) -> Self::Output {
+                trace!("{}.{} {:?}", stringify!($ty), stringify!($op), memarg);
+
+                self._load(memarg, $bytes, $width, $signed)?;
+                Ok(())
+            }
+        }
+    };
+    (@store $ty:tt $op:tt $bytes:expr) => {
+        paste! {
+            fn [< visit_ $ty _ $op >](&mut self, memarg: MemArg) -> Self::Output {
+                trace!("{}.{} {:?}", stringify!($ty), stringify!($op), memarg);
+
+                self._store(memarg, $bytes)?;
+                Ok(())
+            }
+        }
+    };
     (@field ($($field:ident).*) ($op:tt -> $evm:tt) $($arg:tt: $argty:ty),* ) => {
         paste! {
             fn [< visit_ $op >](&mut self, $($arg: $argty),*) -> Self::Output {
@@ -97,18 +239,13 @@ macro_rules! map_wasm_operators {
 
                 trace!("{}", log);
 
-                let before = self.masm.buffer().len();
                 self.$($field.)*[< _ $evm >]($($arg),*)?;
-
-                let instr = self.masm.buffer()[before..].to_vec();
-                self.backtrace.push(instr);
                 Ok(())
             }
         }
     };
     (
         all: [$($all:tt),+],
-        xdr: [$($xdr:tt),+],
         integer: [$($integer:tt),+],
         integer_and_float: [$($op:tt),+],
         float: [$($float:tt),+],
@@ -116,12 +253,8 @@ macro_rules! map_wasm_operators {
             integer: [$($map_int_wasm:tt => $map_int_evm:tt),+],
         },
         mem: {
-            all: [$($mem:tt),+],
-            integer: [$($mem_integer:tt),+],
-            integer64: [$($mem_integer64:tt),+],
-            signed: [$($mem_signed:tt),+],
-            signed64: [$($mem_signed64:tt),+],
-            signed_and_float: [$($mem_signed_and_float:tt),+],
+            load: [$($load_ty:tt $load:tt: $load_args:tt),+],
+            store: [$($store_ty:tt $store:tt: $store_bytes:tt),+],
         },
         masm: {
             $( $masm:tt $(: { $($marg:ident: $margty:ty),+ })? ),+
@@ -140,7 +273,6 @@ macro_rules! map_wasm_operators {
             )+
 
             $(map_wasm_operators!(@integer $integer, $integer);)+
-            $(map_wasm_operators!(@xdr $xdr, $xdr);)+
             $(map_wasm_operators!(@float $float, $float);)+
 
             $(
@@ -148,34 +280,8 @@ macro_rules! map_wasm_operators {
                 map_wasm_operators!(@integer [< $map_int_wasm _u >], $map_int_evm);
             )+
 
-            $(
-                map_wasm_operators!(@integer $mem, $mem _arg: MemArg);
-                map_wasm_operators!(@float $mem, $mem _arg: MemArg);
-            )+
-
-
-            $(
-                map_wasm_operators!(@xdr $mem_integer, $mem_integer _arg: MemArg);
-            )+
-
-            $(
-                map_wasm_operators!(@integer64 [< $mem_integer64 _s >], $mem_integer64 _arg: MemArg );
-                map_wasm_operators!(@integer64 [< $mem_integer64 _u >], $mem_integer64 _arg: MemArg );
-            )+
-
-
-            $(
-                map_wasm_operators!(@integer $mem_signed, $mem_signed _arg: MemArg);
-            )+
-
-            $(
-                map_wasm_operators!(@integer $mem_signed_and_float, $mem_signed_and_float _arg: MemArg);
-                map_wasm_operators!(@float $mem_signed_and_float, $mem_signed_and_float _arg: MemArg);
-            )+
-
-            $(
-                map_wasm_operators!(@integer64 $mem_signed64, $mem_signed64 _arg: MemArg);
-            )+
+            $(map_wasm_operators!(@load $load_ty $load $load_args);)+
+            $(map_wasm_operators!(@store $store_ty $store $store_bytes);)+
 
             $(
                 map_wasm_operators!(@field (masm) ($masm -> $masm) $( $($marg: $margty),+ )?);
@@ -195,7 +301,6 @@ impl VisitOperator<'_> for Function {
 
     map_wasm_operators! {
         all: [div, lt, gt, ge, le],
-        xdr: [shr, trunc_f32, trunc_f64],
         integer: [and, clz, ctz, eqz, or, popcnt, rotl, rotr, shl, xor],
         integer_and_float: [add, sub, mul, eq, ne],
         float: [
@@ -204,26 +309,39 @@ impl VisitOperator<'_> for Function {
             trunc
         ],
         map: {
-            integer: [rem => mod],
+            integer: [rem => mod, shr => shr],
         },
         mem: {
-            all: [load],
-            integer: [load8, load16],
-            integer64: [load32],
-            signed: [store8, store16],
-            signed64: [store32],
-            signed_and_float: [store],
+            load: [
+                i32 load: (4, 4, false),
+                i64 load: (8, 8, false),
+                f32 load: (4, 4, false),
+                f64 load: (8, 8, false),
+                i32 load8_s: (1, 4, true),
+                i32 load8_u: (1, 4, false),
+                i32 load16_s: (2, 4, true),
+                i32 load16_u: (2, 4, false),
+                i64 load8_s: (1, 8, true),
+                i64 load8_u: (1, 8, false),
+                i64 load16_s: (2, 8, true),
+                i64 load16_u: (2, 8, false),
+                i64 load32_s: (4, 8, true),
+                i64 load32_u: (4, 8, false)
+            ],
+            store: [
+                i32 store: 4,
+                i64 store: 8,
+                f32 store: 4,
+                f64 store: 8,
+                i32 store8: 1,
+                i32 store16: 2,
+                i64 store8: 1,
+                i64 store16: 2,
+                i64 store32: 4
+            ],
         },
         masm: {
             drop,
-            memory_grow: {
-                mem: u32,
-                mem_byte: u8
-            },
-            memory_size: {
-                mem: u32,
-                mem_byte: u8
-            },
             i32_const: {
                 value: i32
             },
@@ -239,8 +357,11 @@ impl VisitOperator<'_> for Function {
             i32_wrap_i64,
             i64_extend_i32_s,
             i64_extend_i32_u,
-            f32_demote_f64,
-            f64_promote_f32,
+            i32_extend8_s,
+            i32_extend16_s,
+            i64_extend8_s,
+            i64_extend16_s,
+            i64_extend32_s,
             i32_reinterpret_f32,
             i64_reinterpret_f64,
             f32_reinterpret_i32,
@@ -248,6 +369,9 @@ impl VisitOperator<'_> for Function {
         },
         global: {
             else, select, end, nop, unreachable,
+            f32_demote_f64, f64_promote_f32,
+            i32_trunc_f32_s, i32_trunc_f32_u, i32_trunc_f64_s, i32_trunc_f64_u,
+            i64_trunc_f32_s, i64_trunc_f32_u, i64_trunc_f64_s, i64_trunc_f64_u,
             if: {
                 blockty: BlockType
             },
@@ -281,6 +405,21 @@ impl VisitOperator<'_> for Function {
             global_set: {
                 global_index: u32
             },
+            memory_grow: {
+                mem: u32,
+                mem_byte: u8
+            },
+            memory_size: {
+                mem: u32,
+                mem_byte: u8
+            },
+            memory_copy: {
+                dst_mem: u32,
+                src_mem: u32
+            },
+            memory_fill: {
+                mem: u32
+            },
             call: {
                 func_index: u32
             },
@@ -296,8 +435,6 @@ impl VisitOperator<'_> for Function {
     fn visit_return(&mut self) -> Self::Output {
         trace!("return");
 
-        let before = self.masm.buffer().len();
-
         // for early returns in a function, emit return code with value 1 (true)
         if self.is_main || self.abi.is_some() {
             tracing::trace!("early return from main function");
@@ -307,9 +444,6 @@ impl VisitOperator<'_> for Function {
             self.masm.call_return(self.ty.results())?;
         }
 
-        let instr = self.masm.buffer()[before..].to_vec();
-        self.backtrace.push(instr);
-
         Ok(())
     }
 }
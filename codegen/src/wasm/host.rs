@@ -16,6 +16,8 @@ pub enum HostFunc {
     //
     /// Emit ABI to the compiler.
     EmitABI,
+    /// Splice inline assembly into the code.
+    Asm,
     /// Push u256 max to stack
     U256MAX,
     /// Revert messages with length of slots
@@ -50,6 +52,7 @@ impl TryFrom<(&str, &str)> for HostFunc {
         match import {
             ("zinkc", name) => match name {
                 "emit_abi" => Ok(Self::EmitABI),
+                "asm" => Ok(Self::Asm),
                 "label_reserve_mem_32" => Ok(Self::Label(CompilerLabel::ReserveMemory32)),
                 "label_reserve_mem_64" => Ok(Self::Label(CompilerLabel::ReserveMemory64)),
                 _ => Err(Error::HostFuncNotFound(module.into(), name.into())),
@@ -14,10 +14,13 @@ pub use self::{
 use crate::{Error, Result};
 use host::CompilerLabel;
 use smallvec::SmallVec;
-use std::collections::BTreeMap;
-use wasmparser::Operator;
+use std::collections::{BTreeMap, BTreeSet};
+use wasmparser::{FuncType, Operator};
 use zabi::Abi;
 
+/// Maximum memory slots of the frame stack for recursive calls.
+pub const FRAME_STACK_LIMIT: u32 = 0x200;
+
 macro_rules! impl_deref {
     ($doc:literal, $name:ident, $target:ty) => {
         #[derive(Clone, Debug, Default)]
@@ -47,7 +50,13 @@ impl_deref! {
     ("WASM import section", Imports, BTreeMap<u32, HostFunc>),
     ("WASM export section", Exports, BTreeMap<u32, String>),
     ("WASM slot registry", Slots, BTreeMap<u32, u32>),
-    ("WASM function registry", Funcs, BTreeMap<u32, (u32, u32)>)
+    ("WASM function registry", Funcs, BTreeMap<u32, (u32, u32)>),
+    ("WASM function frame sizes", Frames, BTreeMap<u32, u32>),
+    ("WASM call graph", CallGraph, BTreeMap<u32, BTreeSet<u32>>),
+    ("WASM type section", Types, BTreeMap<u32, FuncType>),
+    ("WASM function section", Signatures, BTreeMap<u32, u32>),
+    ("WASM table elements", Tables, BTreeMap<u32, BTreeMap<u32, u32>>),
+    ("WASM global section", Globals, BTreeMap<u32, SmallVec<[u8; 8]>>)
 }
 
 /// A struct that holds the environment wasm module.
@@ -61,8 +70,46 @@ pub struct Env {
     pub slots: Slots,
     /// Function params count
     pub funcs: Funcs,
+    /// Function frame sizes in memory slots
+    pub frames: Frames,
+    /// Internal calls between functions
+    pub calls: CallGraph,
+    /// Memory slot of the frame stack pointer
+    ///
+    /// The frame stack grows upwards right after this slot, all
+    /// of the static function slots are placed before it.
+    pub frame_stack: u32,
+    /// Memory slot of the registers of the soft-float routines
+    ///
+    /// [`FLOAT_REGISTERS`](crate::FLOAT_REGISTERS) slots are reserved
+    /// after the frame stack if the module has float instructions.
+    pub float: u32,
+    /// Memory slot of the page count of the linear memory
+    pub pages: u32,
+    /// Initial page count of the linear memory
+    pub initial_pages: u32,
+    /// Maximum page count of the linear memory
+    pub max_pages: u32,
+    /// Memory offset of the WASM linear memory
+    ///
+    /// The linear memory is placed after the frame stack, which is
+    /// bounded by [`FRAME_STACK_LIMIT`].
+    pub memory: u32,
+    /// If any function reads the linear memory
+    ///
+    /// The data segments are only copied to the linear memory if
+    /// so, the others are consumed at compile time.
+    pub reads_memory: bool,
     /// WASM data slots
     pub data: Data,
+    /// WASM function types
+    pub types: Types,
+    /// Type indexes of the defined functions
+    pub signatures: Signatures,
+    /// Function indexes in the element segments of tables
+    pub tables: Tables,
+    /// Initial values of globals
+    pub globals: Globals,
     /// Current function index
     pub index: Option<u32>,
 }
@@ -159,6 +206,105 @@ impl Env {
         );
         (slots * 0x20).to_ls_bytes()
     }
+
+    /// Get the memory offset of the global
+    ///
+    /// The globals are placed right after the reserved
+    /// slots for storage calculations.
+    pub fn global(&self, index: u32) -> SmallVec<[u8; 4]> {
+        ((self.imports.reserved() + index) * 0x20).to_ls_bytes()
+    }
+
+    /// If calling `callee` from the current function may clobber
+    /// the slots of an activation of `callee` which is still alive.
+    ///
+    /// This happens when the current function is reachable from
+    /// `callee`, i.e. direct recursion or mutual recursion.
+    pub fn is_recursive_call(&self, callee: u32) -> bool {
+        let Some(caller) = self.index else {
+            return false;
+        };
+
+        self.calls.reaches(callee, caller)
+    }
+
+    /// Get the functions of `table` which match the function type `ty`,
+    /// returns pairs of (element index, function index).
+    ///
+    /// Function types are compared structurally, so functions declared
+    /// with an equivalent type of another index are matched as well.
+    pub fn indirect_callees(&self, ty: u32, table: u32) -> Vec<(u32, u32)> {
+        let Some(expected) = self.types.get(&ty) else {
+            return Default::default();
+        };
+
+        self.tables
+            .get(&table)
+            .into_iter()
+            .flatten()
+            .filter(|(_, func)| {
+                self.signatures
+                    .get(func)
+                    .and_then(|ty| self.types.get(ty))
+                    .is_some_and(|actual| actual == expected)
+            })
+            .map(|(elem, func)| (*elem, *func))
+            .collect()
+    }
+}
+
+impl CallGraph {
+    /// Register a call from `caller` to `callee`.
+    pub fn call(&mut self, caller: u32, callee: u32) {
+        self.entry(caller).or_default().insert(callee);
+    }
+
+    /// If any function could call itself directly or indirectly.
+    pub fn is_recursive(&self) -> bool {
+        self.keys().any(|func| self.reaches(*func, *func))
+    }
+
+    /// Memory slots of the frame stack, which is only reserved
+    /// for recursive calls.
+    pub fn frame_stack(&self) -> u32 {
+        if self.is_recursive() {
+            FRAME_STACK_LIMIT
+        } else {
+            0
+        }
+    }
+
+    /// If `to` could be called from `from` within at least one call.
+    pub fn reaches(&self, from: u32, to: u32) -> bool {
+        let mut visited = BTreeSet::new();
+        let mut queue = vec![from];
+        while let Some(func) = queue.pop() {
+            for callee in self.get(&func).into_iter().flatten() {
+                if *callee == to {
+                    return true;
+                }
+
+                if visited.insert(*callee) {
+                    queue.push(*callee);
+                }
+            }
+        }
+
+        false
+    }
+
+    /// The functions reached from the entries, including the entries.
+    pub fn reachable(&self, entries: impl IntoIterator<Item = u32>) -> BTreeSet<u32> {
+        let mut reached = BTreeSet::new();
+        let mut queue = entries.into_iter().collect::<Vec<_>>();
+        while let Some(func) = queue.pop() {
+            if reached.insert(func) {
+                queue.extend(self.get(&func).into_iter().flatten());
+            }
+        }
+
+        reached
+    }
 }
 
 impl Imports {
@@ -198,3 +344,26 @@ impl Exports {
             .collect::<Vec<_>>()
     }
 }
+
+#[test]
+fn test_call_graph_reaches() {
+    let mut calls = CallGraph::default();
+    calls.call(1, 2);
+    calls.call(2, 3);
+    calls.call(3, 2);
+    calls.call(4, 4);
+
+    assert!(calls.reaches(1, 3));
+    assert!(calls.reaches(2, 2));
+    assert!(calls.reaches(4, 4));
+    assert!(!calls.reaches(1, 1));
+    assert!(!calls.reaches(3, 1));
+    assert!(calls.is_recursive());
+
+    assert_eq!(calls.reachable([1]), [1, 2, 3].into());
+    assert_eq!(calls.reachable([4, 5]), [4, 5].into());
+
+    calls.clear();
+    calls.call(1, 2);
+    assert!(!calls.is_recursive());
+}
@@ -0,0 +1,332 @@
+//! Value width analysis.
+//!
+//! WASM integers wrap at 2^32 or 2^64, while the EVM stack wraps at
+//! 2^256, so the results of the operators which may overflow have to
+//! be masked to the width of their types. All values are kept within
+//! the width of their types on the stack, which is the invariant this
+//! analysis relies on.
+//!
+//! Since the values are zero-extended, the operands of the signed
+//! operators are sign-extended to 256 bits unless they are known to
+//! be non-negative, which are narrower than their types.
+
+use wasmparser::Operator;
+
+/// The bits to extend or wrap the operands and the result of an operator.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Wrap {
+    /// Bits to sign-extend the lhs operand from before the operator.
+    pub lhs: Option<u8>,
+    /// Bits to sign-extend the rhs operand from before the operator.
+    pub rhs: Option<u8>,
+    /// Bits to wrap the top operand before the operator.
+    pub operand: Option<u8>,
+    /// Bits to wrap the result after the operator.
+    pub result: Option<u8>,
+}
+
+/// Upper bounds of the bit widths of the values on the top of the
+/// operand stack.
+///
+/// Only the values produced by the tracked operators are recorded,
+/// `None` for the ones with unknown widths, and the untracked operators
+/// clear the recorded values, so the values under them are treated as
+/// full width values of their types.
+#[derive(Debug, Default, Clone)]
+pub struct Widths(Vec<Option<u8>>);
+
+impl Widths {
+    /// Visit an operator and returns the bits to wrap.
+    pub fn visit(&mut self, operator: &Operator) -> Wrap {
+        use Operator::*;
+
+        let mut wrap = Wrap::default();
+        match operator {
+            I32Const { value } => self.push(32 - (*value as u32).leading_zeros() as u8),
+            I64Const { value } => self.push(64 - (*value as u64).leading_zeros() as u8),
+            LocalGet { .. } | GlobalGet { .. } => self.0.push(None),
+            LocalSet { .. } | GlobalSet { .. } | Drop => {
+                self.0.pop();
+            }
+            LocalTee { .. } => {}
+            I32Load8U { .. } | I64Load8U { .. } => self.load(8),
+            I32Load16U { .. } | I64Load16U { .. } => self.load(16),
+            I32Load { .. } | I64Load32U { .. } => self.load(32),
+            I32Load8S { .. } | I32Load16S { .. } => self.load(32),
+            I64Load { .. } | I64Load8S { .. } | I64Load16S { .. } | I64Load32S { .. } => {
+                self.load(64)
+            }
+            I32Add => wrap.result = self.add(32),
+            I64Add => wrap.result = self.add(64),
+            I32Sub => wrap.result = self.sub(32),
+            I64Sub => wrap.result = self.sub(64),
+            I32Mul => wrap.result = self.mul(32),
+            I64Mul => wrap.result = self.mul(64),
+            I32Shl => wrap = self.shl(32),
+            I64Shl => wrap = self.shl(64),
+            I32ShrU => wrap.operand = self.shr(32),
+            I64ShrU => wrap.operand = self.shr(64),
+            I32ShrS => wrap = self.shr_s(32),
+            I64ShrS => wrap = self.shr_s(64),
+            I32DivS => wrap = self.div_s(32),
+            I64DivS => wrap = self.div_s(64),
+            I32RemS => wrap = self.rem_s(32),
+            I64RemS => wrap = self.rem_s(64),
+            I32LtS | I32GtS | I32LeS | I32GeS => wrap = self.cmp_s(32),
+            I64LtS | I64GtS | I64LeS | I64GeS => wrap = self.cmp_s(64),
+            I32Extend8S => self.extend(8, 32),
+            I32Extend16S => self.extend(16, 32),
+            I64Extend8S => self.extend(8, 64),
+            I64Extend16S => self.extend(16, 64),
+            I64Extend32S | I64ExtendI32S => self.extend(32, 64),
+            I32And | I64And => {
+                let (a, b) = (self.pop(), self.pop());
+                self.push(a.min(b));
+            }
+            I32Or | I64Or | I32Xor | I64Xor => {
+                let (a, b) = (self.pop(), self.pop());
+                self.push(a.max(b));
+            }
+            I32DivU | I64DivU => {
+                self.pop();
+                let a = self.pop();
+                self.push(a);
+            }
+            I32RemU | I64RemU => {
+                let (b, a) = (self.pop(), self.pop());
+                self.push(a.min(b));
+            }
+            I32Eqz | I64Eqz => {
+                self.pop();
+                self.push(1);
+            }
+            I32Eq | I32Ne | I32LtU | I32GtU | I32LeU | I32GeU | I64Eq | I64Ne | I64LtU | I64GtU
+            | I64LeU | I64GeU => {
+                self.pop();
+                self.pop();
+                self.push(1);
+            }
+            I32WrapI64 => {
+                let a = self.pop();
+                if a > 32 {
+                    wrap.result = Some(32);
+                }
+
+                self.push(a.min(32));
+            }
+            I64ExtendI32U => {}
+            _ => self.0.clear(),
+        }
+
+        wrap
+    }
+
+    /// Push a value with a known width.
+    fn push(&mut self, bits: u8) {
+        self.0.push(Some(bits));
+    }
+
+    /// Pop a value, the values with unknown widths are treated as
+    /// full width, which is not more than 64 bits.
+    fn pop(&mut self) -> u8 {
+        self.0.pop().flatten().unwrap_or(64)
+    }
+
+    /// Replace the address with the loaded value.
+    fn load(&mut self, bits: u8) {
+        self.0.pop();
+        self.push(bits);
+    }
+
+    /// Width of the result of addition.
+    fn add(&mut self, width: u8) -> Option<u8> {
+        let (b, a) = (self.pop(), self.pop());
+        self.result(a.max(b) + 1, width)
+    }
+
+    /// Width of the result of subtraction, which wraps on any underflow.
+    fn sub(&mut self, width: u8) -> Option<u8> {
+        self.pop();
+        self.pop();
+        self.result(u8::MAX, width)
+    }
+
+    /// Width of the result of multiplication.
+    fn mul(&mut self, width: u8) -> Option<u8> {
+        let (b, a) = (self.pop(), self.pop());
+        self.result(a + b, width)
+    }
+
+    /// Width of the result of shift left, the shift count is taken
+    /// modulo the width of the type.
+    fn shl(&mut self, width: u8) -> Wrap {
+        let operand = self.count(width);
+        let count = self.pop().min(width.trailing_zeros() as u8);
+        let a = self.pop();
+
+        Wrap {
+            operand,
+            result: self.result(a + (1 << count) - 1, width),
+            ..Default::default()
+        }
+    }
+
+    /// Width of the result of shift right.
+    fn shr(&mut self, width: u8) -> Option<u8> {
+        let operand = self.count(width);
+        self.pop();
+
+        let a = self.pop();
+        self.push(a);
+        operand
+    }
+
+    /// Width of the result of arithmetic shift right.
+    fn shr_s(&mut self, width: u8) -> Wrap {
+        let operand = self.count(width);
+        self.pop();
+
+        let a = self.pop();
+        let lhs = Self::signed(a, width);
+        self.push(if lhs.is_some() { width } else { a });
+        Wrap {
+            lhs,
+            operand,
+            result: lhs,
+            ..Default::default()
+        }
+    }
+
+    /// Width of the result of signed division.
+    fn div_s(&mut self, width: u8) -> Wrap {
+        let (b, a) = (self.pop(), self.pop());
+        let (lhs, rhs) = (Self::signed(a, width), Self::signed(b, width));
+        let result = lhs.or(rhs);
+        self.push(if result.is_some() { width } else { a });
+
+        Wrap {
+            lhs,
+            rhs,
+            result,
+            ..Default::default()
+        }
+    }
+
+    /// Width of the result of signed remainder, which takes the
+    /// sign of the dividend.
+    fn rem_s(&mut self, width: u8) -> Wrap {
+        let (b, a) = (self.pop(), self.pop());
+        let (lhs, rhs) = (Self::signed(a, width), Self::signed(b, width));
+        self.push(if lhs.is_some() { width } else { a });
+
+        Wrap {
+            lhs,
+            rhs,
+            result: lhs,
+            ..Default::default()
+        }
+    }
+
+    /// Signed comparison.
+    fn cmp_s(&mut self, width: u8) -> Wrap {
+        let (b, a) = (self.pop(), self.pop());
+        self.push(1);
+
+        Wrap {
+            lhs: Self::signed(a, width),
+            rhs: Self::signed(b, width),
+            ..Default::default()
+        }
+    }
+
+    /// Width of the result of sign extension from `bits`.
+    fn extend(&mut self, bits: u8, width: u8) {
+        let a = self.pop();
+        self.push(if a < bits { a } else { width });
+    }
+
+    /// Returns the bits to sign-extend the value from if it may be
+    /// negative, the values narrower than their types are not.
+    fn signed(bits: u8, width: u8) -> Option<u8> {
+        (bits >= width).then_some(width)
+    }
+
+    /// Returns the bits to wrap the shift count if it may exceed
+    /// the width of the type.
+    fn count(&self, width: u8) -> Option<u8> {
+        let bits = width.trailing_zeros() as u8;
+        match self.0.last() {
+            Some(Some(count)) if *count <= bits => None,
+            _ => Some(bits),
+        }
+    }
+
+    /// Push the width of the result and returns the bits to wrap
+    /// if it may exceed the width of the type.
+    fn result(&mut self, bits: u8, width: u8) -> Option<u8> {
+        self.push(bits.min(width));
+        (bits > width).then_some(width)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasmparser::MemArg;
+
+    #[test]
+    fn test_wrap_on_overflow() {
+        let memarg = MemArg {
+            align: 0,
+            max_align: 0,
+            offset: 0,
+            memory: 0,
+        };
+
+        let mut widths = Widths::default();
+        widths.visit(&Operator::I32Const { value: 0 });
+        widths.visit(&Operator::I32Load8U { memarg });
+        widths.visit(&Operator::I32Const { value: 0xff });
+        assert_eq!(widths.visit(&Operator::I32Add), Wrap::default());
+        widths.visit(&Operator::I32Const { value: 3 });
+        assert_eq!(widths.visit(&Operator::I32Shl), Wrap::default());
+
+        widths.visit(&Operator::LocalGet { local_index: 0 });
+        assert_eq!(
+            widths.visit(&Operator::I32Shl),
+            Wrap {
+                operand: Some(5),
+                result: Some(32),
+                ..Default::default()
+            }
+        );
+        widths.visit(&Operator::I32Const { value: 1 });
+        assert_eq!(widths.visit(&Operator::I32Add).result, Some(32));
+    }
+
+    #[test]
+    fn test_sign_extend_operands() {
+        let mut widths = Widths::default();
+        widths.visit(&Operator::LocalGet { local_index: 0 });
+        widths.visit(&Operator::I32Const { value: 0x7f });
+        assert_eq!(
+            widths.visit(&Operator::I32LtS),
+            Wrap {
+                lhs: Some(32),
+                ..Default::default()
+            }
+        );
+
+        widths.visit(&Operator::I32Const { value: -1 });
+        widths.visit(&Operator::LocalGet { local_index: 0 });
+        assert_eq!(
+            widths.visit(&Operator::I32DivS),
+            Wrap {
+                lhs: Some(32),
+                rhs: Some(32),
+                result: Some(32),
+                ..Default::default()
+            }
+        );
+    }
+}
@@ -96,7 +96,7 @@ fn examples() -> Result<Vec<PathBuf>> {
         .collect::<Vec<_>>();
 
     for wasm in &files {
-        zinkc::utils::wasm_opt(wasm, wasm)?;
+        zinkc::utils::wasm_opt(wasm, wasm, Default::default())?;
     }
 
     Ok(files)
@@ -187,6 +187,15 @@ impl Tests {
             }
         });
 
+        // the tests of the unsupported module are expected to be rejected
+        let compile = Ident::new(
+            if module == "unsupported" {
+                "compile_unsupported"
+            } else {
+                "compile"
+            },
+            Span::call_site(),
+        );
         let ident_name = Ident::new(&ident_name, Span::call_site());
         self.get_module(&module)
             .content
@@ -196,7 +205,7 @@ impl Tests {
             .push(parse_quote! {
                 #[test]
                 fn #ident_name() -> Result<()> {
-                    Test::load(#module, #name)?.compile()
+                    Test::load(#module, #name)?.#compile()
                 }
             });
 
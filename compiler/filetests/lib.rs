@@ -28,14 +28,30 @@ impl Test {
         let Test { module, name, wasm } = self;
         tracing::info!("Compiling {module}::{name}");
 
-        let compiler = zinkc::Compiler::default();
-        // TODO: after #248
-        if name == "fibonacci" {
-            return Ok(());
+        // the float tests require the soft-float runtime, and the tests
+        // are compiled both without and with the optimizations.
+        for opt_level in [zinkc::OptLevel::O0, zinkc::OptLevel::Os] {
+            let config = zinkc::Config::default()
+                .soft_float(true)
+                .opt_level(opt_level);
+            zinkc::Compiler::new(config).compile(wasm)?;
         }
-        compiler.compile(&wasm)?;
+
         Ok(())
     }
+
+    /// Compile test which is expected to be rejected for the
+    /// unsupported operators.
+    pub fn compile_unsupported(&self) -> anyhow::Result<()> {
+        let Test { module, name, wasm } = self;
+        tracing::info!("Compiling {module}::{name}");
+
+        match zinkc::Compiler::default().compile(wasm) {
+            Err(zinkc::Error::UnsupportedOperator(_)) => Ok(()),
+            Err(e) => Err(e.into()),
+            Ok(_) => Err(anyhow::anyhow!("{module}::{name} should be unsupported")),
+        }
+    }
 }
 
 /// Generate tests for different modules.
@@ -0,0 +1,14 @@
+(module
+  (type (;0;) (func (param i32 i32)))
+  (type (;1;) (func (param i32) (result i32)))
+  (type (;2;) (func (result i32)))
+  (import "zinkc" "asm" (func (;0;) (type 0)))
+  (import "evm" "sload" (func (;1;) (type 1)))
+  (import "env" "memory" (memory (;0;) 17))
+  (func (;2;) (type 2) (result i32)
+    i32.const 1048576
+    i32.const 75
+    call 0
+    i32.const 0
+    call 1)
+  (data (;0;) (i32.const 1048576) "PUSH1 0x2a PUSH0 CALLDATALOAD JUMPI @skip POP PUSH1 0x07 skip: PUSH0 SSTORE"))
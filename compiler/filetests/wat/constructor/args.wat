@@ -0,0 +1,36 @@
+(module
+  (type (;0;) (func (param i32 i32)))
+  (type (;1;) (func (param i32) (result i32)))
+  (type (;2;) (func))
+  (import "zinkc" "emit_abi" (func (;0;) (type 0)))
+  (import "evm" "sstore" (func (;1;) (type 0)))
+  (import "evm" "sload" (func (;2;) (type 1)))
+  (import "env" "memory" (memory (;0;) 1))
+  (func (;3;) (type 0) (param i32 i32)
+    local.get 0
+    i32.const 0
+    call 4
+    local.get 1
+    i32.const 1
+    call 4)
+  (func (;4;) (type 0) (param i32 i32)
+    local.get 0
+    local.get 1
+    call 1)
+  (func (;5;) (type 1) (param i32) (result i32)
+    local.get 0
+    call 2)
+  (func (;6;) (type 2)
+    i32.const 0
+    i32.const 46
+    call 0)
+  (func (;7;) (type 2)
+    i32.const 64
+    i32.const 28
+    call 0)
+  (export "constructor" (func 3))
+  (export "constructor_selector" (func 6))
+  (export "get" (func 5))
+  (export "get_selector" (func 7))
+  (data (;0;) (i32.const 0) "0x0b636f6e7374727563746f7200020161080162080002")
+  (data (;1;) (i32.const 64) "0x036765740101036b6579060002"))
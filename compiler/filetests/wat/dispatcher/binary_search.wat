@@ -0,0 +1,87 @@
+(module
+  (type (;0;) (func (param i32 i32)))
+  (type (;1;) (func (result i32)))
+  (type (;2;) (func))
+  (import "zinkc" "emit_abi" (func (;0;) (type 0)))
+  (import "env" "memory" (memory (;0;) 1))
+  (func (;1;) (type 1)
+    i32.const 0)
+  (func (;2;) (type 1)
+    i32.const 1)
+  (func (;3;) (type 1)
+    i32.const 2)
+  (func (;4;) (type 1)
+    i32.const 3)
+  (func (;5;) (type 1)
+    i32.const 4)
+  (func (;6;) (type 1)
+    i32.const 5)
+  (func (;7;) (type 1)
+    i32.const 6)
+  (func (;8;) (type 1)
+    i32.const 7)
+  (func (;9;) (type 1)
+    i32.const 8)
+  (func (;10;) (type 2)
+    i32.const 0
+    i32.const 16
+    call 0)
+  (func (;11;) (type 2)
+    i32.const 32
+    i32.const 16
+    call 0)
+  (func (;12;) (type 2)
+    i32.const 64
+    i32.const 16
+    call 0)
+  (func (;13;) (type 2)
+    i32.const 96
+    i32.const 16
+    call 0)
+  (func (;14;) (type 2)
+    i32.const 128
+    i32.const 16
+    call 0)
+  (func (;15;) (type 2)
+    i32.const 160
+    i32.const 16
+    call 0)
+  (func (;16;) (type 2)
+    i32.const 192
+    i32.const 16
+    call 0)
+  (func (;17;) (type 2)
+    i32.const 224
+    i32.const 16
+    call 0)
+  (func (;18;) (type 2)
+    i32.const 256
+    i32.const 16
+    call 0)
+  (export "f0" (func 1))
+  (export "f0_selector" (func 10))
+  (export "f1" (func 2))
+  (export "f1_selector" (func 11))
+  (export "f2" (func 3))
+  (export "f2_selector" (func 12))
+  (export "f3" (func 4))
+  (export "f3_selector" (func 13))
+  (export "f4" (func 5))
+  (export "f4_selector" (func 14))
+  (export "f5" (func 6))
+  (export "f5_selector" (func 15))
+  (export "f6" (func 7))
+  (export "f6_selector" (func 16))
+  (export "f7" (func 8))
+  (export "f7_selector" (func 17))
+  (export "f8" (func 9))
+  (export "f8_selector" (func 18))
+  (data (;0;) (i32.const 0) "0x02663001000002")
+  (data (;1;) (i32.const 32) "0x02663101000002")
+  (data (;2;) (i32.const 64) "0x02663201000002")
+  (data (;3;) (i32.const 96) "0x02663301000002")
+  (data (;4;) (i32.const 128) "0x02663401000002")
+  (data (;5;) (i32.const 160) "0x02663501000002")
+  (data (;6;) (i32.const 192) "0x02663601000002")
+  (data (;7;) (i32.const 224) "0x02663701000002")
+  (data (;8;) (i32.const 256) "0x02663801000002"))
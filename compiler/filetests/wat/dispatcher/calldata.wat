@@ -0,0 +1,86 @@
+(module
+  (type (;0;) (func (param i32 i32)))
+  (type (;1;) (func (result i32)))
+  (type (;2;) (func))
+  (type (;3;) (func (param i32) (result i32)))
+  (type (;4;) (func (param i32 i32) (result i32)))
+  (import "zinkc" "emit_abi" (func (;0;) (type 0)))
+  (import "env" "memory" (memory (;0;) 1))
+  (func (;1;) (type 3)
+    local.get 0)
+  (func (;2;) (type 3)
+    local.get 0)
+  (func (;3;) (type 3)
+    local.get 0)
+  (func (;4;) (type 3)
+    local.get 0)
+  (func (;5;) (type 4)
+    local.get 0
+    local.get 1
+    i32.add)
+  (func (;6;) (type 3)
+    local.get 0)
+  (func (;7;) (type 4)
+    local.get 0
+    local.get 1
+    i32.div_s)
+  (func (;8;) (type 4)
+    local.get 0
+    local.get 1
+    i32.lt_s)
+  (func (;9;) (type 2)
+    i32.const 0
+    i32.const 22
+    call 0)
+  (func (;10;) (type 2)
+    i32.const 32
+    i32.const 28
+    call 0)
+  (func (;11;) (type 2)
+    i32.const 64
+    i32.const 26
+    call 0)
+  (func (;12;) (type 2)
+    i32.const 96
+    i32.const 26
+    call 0)
+  (func (;13;) (type 2)
+    i32.const 128
+    i32.const 32
+    call 0)
+  (func (;14;) (type 2)
+    i32.const 160
+    i32.const 30
+    call 0)
+  (func (;15;) (type 2)
+    i32.const 192
+    i32.const 36
+    call 0)
+  (func (;16;) (type 2)
+    i32.const 256
+    i32.const 34
+    call 0)
+  (export "u8" (func 1))
+  (export "u8_selector" (func 9))
+  (export "i8" (func 2))
+  (export "i8_selector" (func 10))
+  (export "addr" (func 3))
+  (export "addr_selector" (func 11))
+  (export "u256" (func 4))
+  (export "u256_selector" (func 12))
+  (export "pair" (func 5))
+  (export "pair_selector" (func 13))
+  (export "i32" (func 6))
+  (export "i32_selector" (func 14))
+  (export "div" (func 7))
+  (export "div_selector" (func 15))
+  (export "lt" (func 8))
+  (export "lt_selector" (func 16))
+  (data (;0;) (i32.const 0) "0x02753801010161040002")
+  (data (;1;) (i32.const 32) "0x02693801010161000101610002")
+  (data (;2;) (i32.const 64) "0x0461646472010101610a0002")
+  (data (;3;) (i32.const 96) "0x047532353601010161080002")
+  (data (;4;) (i32.const 128) "0x047061697201020161040162040002")
+  (data (;5;) (i32.const 160) "0x0369333201010161020101610202")
+  (data (;6;) (i32.const 192) "0x0364697601020161020162020101630202")
+  (data (;7;) (i32.const 256) "0x026c7401020161020162020101630902"))
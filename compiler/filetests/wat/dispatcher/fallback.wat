@@ -0,0 +1,47 @@
+(module
+  (type (;0;) (func (param i32 i32)))
+  (type (;1;) (func (result i32)))
+  (type (;2;) (func))
+  (import "zinkc" "emit_abi" (func (;0;) (type 0)))
+  (import "evm" "sstore" (func (;1;) (type 0)))
+  (import "env" "memory" (memory (;0;) 1))
+  (func (;2;) (type 1)
+    i32.const 7)
+  (func (;3;) (type 1)
+    i32.const 8)
+  (func (;4;) (type 2)
+    i32.const 1
+    i32.const 0
+    call 1)
+  (func (;5;) (type 2)
+    i32.const 2
+    i32.const 0
+    call 1)
+  (func (;6;) (type 2)
+    i32.const 0
+    i32.const 16
+    call 0)
+  (func (;7;) (type 2)
+    i32.const 32
+    i32.const 16
+    call 0)
+  (func (;8;) (type 2)
+    i32.const 64
+    i32.const 28
+    call 0)
+  (func (;9;) (type 2)
+    i32.const 96
+    i32.const 26
+    call 0)
+  (export "f0" (func 2))
+  (export "f0_selector" (func 6))
+  (export "f1" (func 3))
+  (export "f1_selector" (func 7))
+  (export "fallback" (func 4))
+  (export "fallback_selector" (func 8))
+  (export "receive" (func 5))
+  (export "receive_selector" (func 9))
+  (data (;0;) (i32.const 0) "0x02663001000002")
+  (data (;1;) (i32.const 32) "0x02663101000003")
+  (data (;2;) (i32.const 64) "0x0866616c6c6261636b02000002")
+  (data (;3;) (i32.const 96) "0x077265636569766503000003"))
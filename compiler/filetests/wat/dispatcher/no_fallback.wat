@@ -0,0 +1,15 @@
+(module
+  (type (;0;) (func (param i32 i32)))
+  (type (;1;) (func (result i32)))
+  (type (;2;) (func))
+  (import "zinkc" "emit_abi" (func (;0;) (type 0)))
+  (import "env" "memory" (memory (;0;) 1))
+  (func (;1;) (type 1)
+    i32.const 7)
+  (func (;2;) (type 2)
+    i32.const 0
+    i32.const 16
+    call 0)
+  (export "f0" (func 1))
+  (export "f0_selector" (func 2))
+  (data (;0;) (i32.const 0) "0x02663001000002"))
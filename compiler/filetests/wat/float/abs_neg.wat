@@ -0,0 +1,5 @@
+(module
+  (func (param f64) (result f64)
+    (f64.neg (f64.abs (local.get 0)))
+  )
+)
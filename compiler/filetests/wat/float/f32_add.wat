@@ -0,0 +1,7 @@
+(module
+  (func (param f32) (param f32) (result f32)
+    (local.get 0)
+    (local.get 1)
+    (f32.add)
+  )
+)
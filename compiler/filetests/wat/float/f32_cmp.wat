@@ -0,0 +1,15 @@
+(module
+  (func (param f32) (param f32) (result i32)
+    (i32.shl (f32.eq (local.get 0) (local.get 1)) (i32.const 5))
+    (i32.shl (f32.ne (local.get 0) (local.get 1)) (i32.const 4))
+    (i32.or)
+    (i32.shl (f32.lt (local.get 0) (local.get 1)) (i32.const 3))
+    (i32.or)
+    (i32.shl (f32.le (local.get 0) (local.get 1)) (i32.const 2))
+    (i32.or)
+    (i32.shl (f32.gt (local.get 0) (local.get 1)) (i32.const 1))
+    (i32.or)
+    (f32.ge (local.get 0) (local.get 1))
+    (i32.or)
+  )
+)
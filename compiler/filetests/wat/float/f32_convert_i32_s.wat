@@ -0,0 +1,6 @@
+(module
+  (func (param i32) (result f32)
+    (local.get 0)
+    (f32.convert_i32_s)
+  )
+)
@@ -0,0 +1,6 @@
+(module
+  (func (param f64) (result f32)
+    (local.get 0)
+    (f32.demote_f64)
+  )
+)
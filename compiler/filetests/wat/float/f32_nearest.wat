@@ -0,0 +1,6 @@
+(module
+  (func (param f32) (result f32)
+    (local.get 0)
+    (f32.nearest)
+  )
+)
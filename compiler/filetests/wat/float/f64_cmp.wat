@@ -0,0 +1,15 @@
+(module
+  (func (param f64) (param f64) (result i32)
+    (i32.shl (f64.eq (local.get 0) (local.get 1)) (i32.const 5))
+    (i32.shl (f64.ne (local.get 0) (local.get 1)) (i32.const 4))
+    (i32.or)
+    (i32.shl (f64.lt (local.get 0) (local.get 1)) (i32.const 3))
+    (i32.or)
+    (i32.shl (f64.le (local.get 0) (local.get 1)) (i32.const 2))
+    (i32.or)
+    (i32.shl (f64.gt (local.get 0) (local.get 1)) (i32.const 1))
+    (i32.or)
+    (f64.ge (local.get 0) (local.get 1))
+    (i32.or)
+  )
+)
@@ -0,0 +1,6 @@
+(module
+  (func (param i32) (result f64)
+    (local.get 0)
+    (f64.convert_i32_u)
+  )
+)
@@ -0,0 +1,7 @@
+(module
+  (func (param f64) (param f64) (result f64)
+    (local.get 0)
+    (local.get 1)
+    (f64.max)
+  )
+)
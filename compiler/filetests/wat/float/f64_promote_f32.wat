@@ -0,0 +1,6 @@
+(module
+  (func (param f32) (result f64)
+    (local.get 0)
+    (f64.promote_f32)
+  )
+)
@@ -0,0 +1,6 @@
+(module
+  (func (param f64) (result i32)
+    (local.get 0)
+    (i32.trunc_f64_s)
+  )
+)
@@ -0,0 +1,6 @@
+(module
+  (func (param f32) (result i64)
+    (local.get 0)
+    (i64.trunc_f32_s)
+  )
+)
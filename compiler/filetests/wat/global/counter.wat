@@ -0,0 +1,8 @@
+(module
+  (global $counter (mut i32) (i32.const 42))
+
+  (func (export "main") (param i32) (result i32)
+    (global.set $counter (i32.add (global.get $counter) (local.get 0)))
+    (global.get $counter)
+  )
+)
@@ -0,0 +1,22 @@
+(module
+  (global $__stack_pointer (mut i32) (i32.const 1048576))
+
+  (func (export "main") (result i32)
+    (global.get $__stack_pointer)
+    (call $frame)
+    (i32.sub)
+  )
+
+  ;; allocates a frame of 16 bytes on the shadow stack,
+  ;; returns the stack pointer inside of the frame.
+  (func $frame (result i32)
+    (local i32)
+    (global.get $__stack_pointer)
+    (i32.const 16)
+    (i32.sub)
+    (local.tee 0)
+    (global.set $__stack_pointer)
+    (global.set $__stack_pointer (i32.add (local.get 0) (i32.const 16)))
+    (local.get 0)
+  )
+)
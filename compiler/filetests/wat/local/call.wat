@@ -0,0 +1,14 @@
+(module
+  (func (param i32) (result i32)
+    (call $square_plus_one (local.get 0))
+    (call $square_plus_one (i32.const 2))
+    i32.add
+  )
+
+  (func $square_plus_one (param i32) (result i32)
+    (local $square i32)
+    (local.tee $square (i32.mul (local.get 0) (local.get 0)))
+    (i32.const 1)
+    i32.add
+  )
+)
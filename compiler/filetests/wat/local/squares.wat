@@ -0,0 +1,22 @@
+(module
+  (func (param i32) (result i32)
+    (local $n i32)
+    (local $sum i32)
+    (local $square i32)
+    (local.set $n (local.get 0))
+
+    ;; sum of the squares of 1..n
+    (if (local.get $n)
+      (then
+        (loop
+          (local.set $square (i32.mul (local.get $n) (local.get $n)))
+          (local.set $sum (i32.add (local.get $sum) (local.get $square)))
+          (local.set $n (i32.sub (local.get $n) (i32.const 1)))
+          (br_if 0 (local.get $n))
+        )
+      )
+    )
+
+    (local.get $sum)
+  )
+)
@@ -0,0 +1,14 @@
+(module
+  (func (param i32) (result i32)
+    (local $a i32)
+    (local $b i32)
+
+    ;; a = p + 1, b = a * a
+    (local.set $a (i32.add (local.get 0) (i32.const 1)))
+    (local.set $b (i32.mul (local.get $a) (local.get $a)))
+
+    ;; a = b - a
+    (local.set $a (i32.sub (local.get $b) (local.get $a)))
+    (i32.add (local.get $a) (local.get $b))
+  )
+)
@@ -0,0 +1,9 @@
+(module
+  (memory 1)
+
+  (func (export "main") (param i32) (result i64)
+    (i64.store (i32.const 0) (i64.const 0x0807060504030201))
+    (memory.copy (i32.const 2) (i32.const 0) (local.get 0))
+    (i64.load (i32.const 0))
+  )
+)
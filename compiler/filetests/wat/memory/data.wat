@@ -0,0 +1,9 @@
+(module
+  (memory 17)
+  (data (i32.const 1048576) "\02\00\00\00\00\00\00\00\03\00\00\00\00\00\00\00\05\00\00\00\00\00\00\00\07\00\00\00\00\00\00\00")
+  (data (i32.const 1048608) "\0b\00\00\00\00\00\00\00\0d\00\00\00\00\00\00\00")
+
+  (func (export "main") (param i32) (result i64)
+    (i64.load offset=1048576 (i32.shl (local.get 0) (i32.const 3)))
+  )
+)
@@ -0,0 +1,8 @@
+(module
+  (memory 1)
+
+  (func (export "main") (param i32) (result i64)
+    (memory.fill (i32.const 1) (i32.const 0x1ab) (local.get 0))
+    (i64.load (i32.const 0))
+  )
+)
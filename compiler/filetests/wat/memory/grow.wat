@@ -0,0 +1,7 @@
+(module
+  (memory 1 3)
+
+  (func (export "main") (param i32) (result i32)
+    (memory.grow (local.get 0))
+  )
+)
@@ -0,0 +1,8 @@
+(module
+  (memory 1)
+
+  (func (export "main") (param i32) (result i32)
+    (i32.store (i32.const 0) (i32.const 0x11223344))
+    (i32.load (local.get 0))
+  )
+)
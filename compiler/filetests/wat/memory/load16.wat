@@ -0,0 +1,8 @@
+(module
+  (memory 1)
+
+  (func (export "main") (param i32) (result i64)
+    (i64.store (i32.const 0) (i64.const 0x8899aabbccddeeff))
+    (i64.load16_u offset=1 (local.get 0))
+  )
+)
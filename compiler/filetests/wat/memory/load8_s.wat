@@ -0,0 +1,8 @@
+(module
+  (memory 1)
+
+  (func (export "main") (param i32) (result i32)
+    (i32.store16 (i32.const 0) (i32.const 0xff7f))
+    (i32.load8_s (local.get 0))
+  )
+)
@@ -0,0 +1,8 @@
+(module
+  (memory 1)
+
+  (func (export "main") (param i32) (result i64)
+    (i64.store32 (i32.const 0) (i64.const 0x8001ff7f))
+    (i64.load16_s (local.get 0))
+  )
+)
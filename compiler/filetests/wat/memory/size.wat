@@ -0,0 +1,8 @@
+(module
+  (memory 2 4)
+
+  (func (export "main") (param i32) (result i32)
+    (drop (memory.grow (local.get 0)))
+    (memory.size)
+  )
+)
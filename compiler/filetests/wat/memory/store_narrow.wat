@@ -0,0 +1,11 @@
+(module
+  (memory 1)
+
+  ;; the narrow stores keep the bytes around them.
+  (func (export "main") (result i64)
+    (i64.store (i32.const 0) (i64.const 0x1122334455667788))
+    (i32.store16 (i32.const 2) (i32.const 0xffffaabb))
+    (i64.store8 offset=5 (i32.const 0) (i64.const 0xcc))
+    (i64.load (i32.const 0))
+  )
+)
@@ -0,0 +1,34 @@
+(module
+ (type (;0;) (func (param i32) (result i32)))
+ (func (;0;) (type 0) (param i32) (result i32)
+             local.get 0
+             call 1)
+ (func (;1;) (type 0) (param i32) (result i32)
+             (local i32)
+             i32.const 1
+             local.set 1
+             local.get 0
+             if  ;; label = @1
+             local.get 0
+             i32.const 1
+             i32.sub
+             call 2
+             local.set 1
+             end
+             local.get 1)
+ (func (;2;) (type 0) (param i32) (result i32)
+             (local i32)
+             i32.const 0
+             local.set 1
+             local.get 0
+             if  ;; label = @1
+             local.get 0
+             i32.const 1
+             i32.sub
+             call 1
+             local.set 1
+             end
+             local.get 1)
+ (export "is_even" (func 0))
+ (export "even" (func 1))
+ (export "odd" (func 2)))
@@ -0,0 +1,11 @@
+(module
+  (func (param i32) (param i32) (result i32)
+    (i32.or
+      (i32.or
+        (i32.lt_s (local.get 0) (local.get 1))
+        (i32.shl (i32.le_s (local.get 0) (local.get 1)) (i32.const 1)))
+      (i32.or
+        (i32.shl (i32.ge_s (local.get 0) (local.get 1)) (i32.const 2))
+        (i32.shl (i32.gt_s (local.get 0) (local.get 1)) (i32.const 3))))
+  )
+)
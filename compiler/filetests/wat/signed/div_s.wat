@@ -0,0 +1,7 @@
+(module
+  (func (param i32) (param i32) (result i32)
+    (local.get 0)
+    (local.get 1)
+    (i32.div_s)
+  )
+)
@@ -0,0 +1,6 @@
+(module
+  (func (param i32) (result i64)
+    (local.get 0)
+    (i64.extend_i32_s)
+  )
+)
@@ -0,0 +1,13 @@
+(module
+    (func (param i32) (param i32) (result i32)
+    (local.get 0)
+    (local.get 1)
+    (i32.add)
+    )
+
+    ;; DWARF of `src/lib.rs`, where the locals are got at line 2
+    ;; and added at line 3.
+    (@custom ".debug_abbrev" "\01\11\00\10\17\00\00\00")
+    (@custom ".debug_info" "\0c\00\00\00\04\00\00\00\00\00\04\01\00\00\00\00")
+    (@custom ".debug_line" "\3c\00\00\00\04\00\22\00\00\00\01\01\01\fb\0e\0d\00\01\01\01\01\00\00\00\01\00\00\01\73\72\63\00\00\6c\69\62\2e\72\73\00\01\00\00\00\00\05\02\03\00\00\00\03\01\01\02\04\03\01\01\02\02\00\01\01")
+)
@@ -0,0 +1,13 @@
+(module
+    (import "ext" "revert1" (func (param i32 i32)))
+    (import "env" "memory" (memory 17))
+    (func (param i32)
+    (local.get 0)
+    (if
+        (then
+        (i32.const 1048576)
+        (i32.const 4)
+        (call 0)))
+    )
+    (data (i32.const 1048576) "fail")
+)
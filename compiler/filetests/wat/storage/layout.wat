@@ -0,0 +1,12 @@
+(module
+  (type (;0;) (func (param i32 i32)))
+  (import "evm" "sstore" (func (;0;) (type 0)))
+  (func (param i32)
+        local.get 0
+        i32.const 0
+        call 0)
+
+  ;; the storage items declared with `#[zink::storage]`, where the
+  ;; mapping is recorded before the value of the slot 0.
+  (@custom "zink.storage" "Balances:1:Address=>U256\nCounter:0:i32\n")
+ )
@@ -0,0 +1,22 @@
+;;! target = "evm"
+(module
+  (func (export "main") (param i32 i64) (result i32)
+    (i32.clz (local.get 0))
+    (i32.ctz (local.get 0))
+    (i32.popcnt (local.get 0))
+    (i32.rotl (local.get 0) (i32.const 1))
+    (i32.rotr (local.get 0) (i32.const 1))
+    (i64.clz (local.get 1))
+    (i64.ctz (local.get 1))
+    (i64.popcnt (local.get 1))
+    (i64.rotl (local.get 1) (i64.const 1))
+    (i64.rotr (local.get 1) (i64.const 1))
+    (drop)
+    (drop)
+    (drop)
+    (drop)
+    (drop)
+    (drop)
+    (drop)
+    (drop)
+    (drop)))
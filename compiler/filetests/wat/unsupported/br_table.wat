@@ -0,0 +1,8 @@
+;;! target = "evm"
+(module
+  (func (export "main") (param i32) (result i32)
+    (block
+      (block
+        (br_table 0 1 (local.get 0)))
+      (return (i32.const 1)))
+    (i32.const 0)))
@@ -0,0 +1,12 @@
+(module
+  (memory 1)
+
+  (func (param i32) (result i32)
+    (i32.store (i32.const 0) (local.get 0))
+    (i32.add
+      (i32.load8_u (i32.const 0))
+      (i32.load16_u (i32.const 1)))
+    (i32.const 2)
+    (i32.shl)
+  )
+)
@@ -13,4 +13,105 @@ pub struct Artifact {
     pub config: Config,
     /// Runtime bytecode of the contract.
     pub runtime_bytecode: Vec<u8>,
+    /// Bytes of the runtime bytecode saved by the peephole optimizer.
+    pub saved_bytes: usize,
+    /// Functions unreachable from the entries of the contract, which
+    /// are eliminated from the runtime bytecode, the functions compiled
+    /// into the init code are not included.
+    pub eliminated: Vec<EliminatedFunc>,
+    /// Init code compiled from the constructor function, which is
+    /// executed on deployment before the runtime bytecode is returned.
+    pub init_code: Vec<u8>,
+    /// Creation bytecode of the contract, which runs the init code,
+    /// presets the storage and returns the runtime bytecode.
+    pub creation_bytecode: Vec<u8>,
+    /// Source locations of the instructions in the runtime bytecode,
+    /// the code generated without WASM operators is not included.
+    pub source_map: Vec<SourceLocation>,
+    /// Source map of the runtime bytecode in the `srcmap` format of
+    /// solc, where the WASM module is the source `0`.
+    pub srcmap: String,
+    /// Functions compiled into the runtime bytecode, sorted by their
+    /// program counters.
+    pub funcs: Vec<CompiledFunc>,
+    /// Program counters and lengths of the functions in the code
+    /// section at the end of the runtime bytecode.
+    pub code_section: Vec<(u16, usize)>,
+    /// Storage items declared with `#[zink::storage]`, sorted by their
+    /// slots.
+    pub storage_layout: Vec<StorageItem>,
+}
+
+impl Artifact {
+    /// Get the source location of the instruction at the program counter.
+    pub fn source(&self, pc: u16) -> Option<&SourceLocation> {
+        self.source_map
+            .binary_search_by_key(&pc, |location| location.pc)
+            .ok()
+            .map(|index| &self.source_map[index])
+    }
+}
+
+/// Function compiled into the runtime bytecode.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompiledFunc {
+    /// Index of the function in the WASM module, or of the soft-float
+    /// routine.
+    pub index: u32,
+    /// Export name of the function, or the name of the soft-float routine.
+    pub name: Option<String>,
+    /// Program counter of the function.
+    pub pc: u16,
+}
+
+/// Function eliminated from the runtime bytecode.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EliminatedFunc {
+    /// Index of the function in the WASM module.
+    pub index: u32,
+    /// Export name of the function.
+    pub name: Option<String>,
+}
+
+/// Source location of an instruction in the runtime bytecode.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SourceLocation {
+    /// Program counter of the instruction.
+    pub pc: u16,
+    /// Index of the function in the WASM module.
+    pub func: u32,
+    /// Byte offset of the operator in the WASM module.
+    pub offset: usize,
+    /// Byte length of the operator.
+    pub len: usize,
+    /// Rust source line of the operator if the WASM module carries DWARF.
+    pub line: Option<Line>,
+}
+
+/// Line of a Rust source file.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Line {
+    /// Path of the source file.
+    pub file: String,
+    /// Line number starting from 1.
+    pub line: u64,
+}
+
+/// Storage item declared in the contract, which is recorded in the
+/// custom section `zink.storage` of the WASM module.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StorageItem {
+    /// Name of the storage.
+    pub label: String,
+    /// Storage slot.
+    pub slot: u32,
+    /// Key types of the mappings, empty for the values.
+    pub keys: Vec<String>,
+    /// Value type.
+    pub value: String,
 }
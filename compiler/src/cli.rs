@@ -1,9 +1,13 @@
 //! Zink compiler command line interface.
 #![cfg(feature = "cli")]
 
-use crate::{Compiler, Config};
+use crate::{Compiler, Config, InitStorage, JsonArtifact};
 use ccli::{clap, Parser};
-use std::{env, fs, path::PathBuf};
+use std::{
+    collections::BTreeMap,
+    env, fs,
+    path::{Path, PathBuf},
+};
 
 /// Compile WASM to EVM bytecode.
 #[derive(Debug, Parser)]
@@ -12,15 +16,35 @@ pub struct Compile {
     /// Write ABI to disk.
     #[clap(short, long)]
     abi: bool,
+    /// Write the Solidity-compatible JSON artifact to disk.
+    #[clap(long)]
+    json: bool,
+    /// Write the creation bytecode to disk.
+    #[clap(long)]
+    creation: bool,
+    /// Preset the storage in the creation bytecode from a TOML or JSON file.
+    #[clap(long, value_name = "FILE")]
+    storage: Option<PathBuf>,
+    /// Print the extra output of the compilation.
+    #[clap(long, value_enum)]
+    emit: Option<Emit>,
     /// The path of the wasm file.
     #[clap(value_name = "INPUT")]
     input: PathBuf,
     /// Write output to <filename>
     #[clap(short, long)]
     output: Option<PathBuf>,
-    /// If enable dispatcher.
-    #[clap(short, long)]
-    dispatcher: bool,
+    /// Compiler configuration.
+    #[clap(flatten)]
+    config: Config,
+}
+
+/// Extra output of the compilation.
+#[derive(Clone, Copy, Debug, clap::ValueEnum)]
+pub enum Emit {
+    /// The listing of the runtime bytecode with the program counters,
+    /// annotated with the functions, the selectors and the WASM operators.
+    Asm,
 }
 
 impl Compile {
@@ -32,11 +56,34 @@ impl Compile {
             env::current_dir()?.join(self.input.with_extension(""))
         };
 
-        let compiler = Compiler::new(Config::default().dispatcher(self.dispatcher));
-        let artifact = compiler.compile(&fs::read(&self.input)?)?;
+        let mut compiler = Compiler::new(self.config.clone());
+        if let Some(storage) = self.storage.as_ref() {
+            compiler = compiler.storage(load_storage(storage)?);
+        }
+
+        let wasm = fs::read(&self.input)?;
+        let artifact = compiler.compile(&wasm)?;
+        if let Some(Emit::Asm) = self.emit {
+            print!("{}", artifact.listing(&wasm));
+        }
 
         output.parent().map(fs::create_dir_all);
-        fs::write(&output, artifact.runtime_bytecode)?;
+        fs::write(&output, &artifact.runtime_bytecode)?;
+
+        if self.creation {
+            fs::write(
+                output.with_extension("creation.bin"),
+                &artifact.creation_bytecode,
+            )?;
+        }
+
+        if self.json {
+            let json = JsonArtifact::from(&artifact);
+            fs::write(
+                output.with_extension("json"),
+                serde_json::to_string_pretty(&json)?,
+            )?;
+        }
 
         if !self.abi {
             return Ok(());
@@ -56,3 +103,40 @@ impl Compile {
         Ok(())
     }
 }
+
+/// Load the initial storage from a TOML or JSON file, which maps the
+/// storage keys to the values, both in hex.
+///
+/// ```toml
+/// "0x00" = "0x2a"
+/// ```
+pub fn load_storage(path: &Path) -> anyhow::Result<InitStorage> {
+    let content = fs::read_to_string(path)?;
+    let mapping: BTreeMap<String, String> = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("toml") => toml::from_str(&content)?,
+        Some("json") => serde_json::from_str(&content)?,
+        _ => anyhow::bail!("unsupported storage file {path:?}, expected toml or json"),
+    };
+
+    mapping
+        .iter()
+        .map(|(key, value)| Ok((word(key)?.into(), word(value)?.into())))
+        .collect()
+}
+
+/// Decode a storage word from hex with the leading zeros trimmed.
+fn word(hex: &str) -> anyhow::Result<Vec<u8>> {
+    let hex = hex.trim_start_matches("0x");
+    let bytes = if hex.len() % 2 == 1 {
+        hex::decode(format!("0{hex}"))?
+    } else {
+        hex::decode(hex)?
+    };
+
+    let start = bytes.iter().position(|b| *b != 0).unwrap_or(bytes.len());
+    if bytes.len() - start > 32 {
+        anyhow::bail!("storage word 0x{hex} exceeds 32 bytes");
+    }
+
+    Ok(bytes[start..].to_vec())
+}
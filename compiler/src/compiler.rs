@@ -1,10 +1,18 @@
 //! Zink compiler
 
-use crate::{parser::Parser, Artifact, Config, Error, Result};
-use zabi::Abi;
+use crate::{
+    artifact::{CompiledFunc, EliminatedFunc, StorageItem},
+    parser::Parser,
+    srcmap::{self, Lines},
+    Artifact, Config, Error, InitStorage, Result,
+};
+use std::collections::{BTreeMap, BTreeSet};
+use zabi::{Abi, Type};
 use zingen::{
-    wasm::{self, Env},
-    Buffer, Dispatcher, Function, JumpTable, BUFFER_LIMIT,
+    ir::{self, Body},
+    wasm::{self, Env, Functions},
+    Buffer, Constructor, Dispatcher, Function, JumpTable, MacroAssembler, Preamble, Routine,
+    BUFFER_LIMIT,
 };
 
 /// Zink Compiler
@@ -18,6 +26,20 @@ pub struct Compiler {
     pub config: Config,
     /// Global jump table.
     table: JumpTable,
+    /// Bytes saved by the peephole optimizer.
+    saved_bytes: usize,
+    /// Functions eliminated from the runtime bytecode.
+    eliminated: Vec<EliminatedFunc>,
+    /// Functions compiled into the runtime bytecode.
+    funcs: Vec<CompiledFunc>,
+    /// Init code compiled from the constructor function.
+    init_code: Vec<u8>,
+    /// Initial storage preset in the creation bytecode.
+    storage: InitStorage,
+    /// Rust source lines of the WASM operators.
+    lines: Lines,
+    /// Storage items declared in the contract.
+    storage_layout: Vec<StorageItem>,
 }
 
 impl Compiler {
@@ -29,20 +51,67 @@ impl Compiler {
         }
     }
 
+    /// With the initial storage preset in the creation bytecode.
+    pub fn storage(mut self, storage: InitStorage) -> Self {
+        self.storage = storage;
+        self
+    }
+
     /// Compile wasm module to evm bytecode.
     ///
     /// Returns runtime bytecode.
     pub fn compile(mut self, wasm: &[u8]) -> Result<Artifact> {
         let mut parser = Parser::try_from(wasm)?;
+        if !self.config.soft_float && !parser.floats.is_empty() {
+            parser.unsupported.append(&mut parser.floats);
+            parser.unsupported.sort_by_key(|op| op.offset);
+        }
+
+        if !parser.unsupported.is_empty() {
+            return Err(Error::UnsupportedOperator(parser.unsupported));
+        }
+
+        parser.env.max_pages = parser.env.max_pages.min(self.config.max_pages);
+        self.lines = std::mem::take(&mut parser.lines);
+        self.storage_layout = std::mem::take(&mut parser.storage);
         let env = parser.env.clone();
 
+        self.compile_preamble(&env)?;
         self.compile_dispatcher(&mut parser)?;
-        for func in parser.funcs.into_funcs() {
-            self.compile_func(env.with_index(func.index()), func)?;
-        }
 
+        let entries = self.entries(&env)?;
+        let stripped = self.compile_reachable(&env, parser.funcs, entries)?;
+        let init = self.init_funcs(&env, &stripped)?;
+        self.eliminated = stripped
+            .into_keys()
+            .filter(|index| !init.contains(index))
+            .map(|index| EliminatedFunc {
+                index,
+                name: env.exports.get(&index).cloned(),
+            })
+            .collect();
+
+        self.compile_routines(&env)?;
+        self.optimize()?;
         self.table.code_offset(self.buffer.len() as u16);
         self.table.relocate(&mut self.buffer)?;
+        self.funcs = self
+            .table
+            .funcs()
+            .iter()
+            .map(|(index, pc)| CompiledFunc {
+                index: *index,
+                name: env.exports.get(index).cloned().or_else(|| {
+                    Routine::try_from(*index)
+                        .ok()
+                        .map(|routine| format!("{routine:?}"))
+                }),
+                pc: *pc,
+            })
+            .collect();
+        self.funcs.sort_by_key(|func| func.pc);
+
+        self.init_code = self.compile_constructor(wasm)?;
         self.artifact()
     }
 
@@ -54,17 +123,85 @@ impl Compiler {
             abi,
             buffer,
             config,
+            saved_bytes,
+            eliminated,
+            funcs,
+            init_code,
+            storage,
+            table,
+            lines,
+            storage_layout,
             ..
         } = self;
 
+        let mut constructor = Constructor::default();
+        constructor.init_code(&init_code);
+        constructor.storage(storage)?;
+        let creation_bytecode = constructor.finish(buffer.clone())?;
+
+        let (source_map, srcmap) = srcmap::resolve(&buffer, table.srcmap(), &lines);
+        let code = table.code();
+        let code_section = code
+            .funcs()
+            .iter()
+            .filter_map(|func| Some((code.offset_of(func)?, func.bytecode.len())))
+            .collect();
+
         tracing::debug!("code length: {}", buffer.len());
         Ok(Artifact {
             abi,
             config,
             runtime_bytecode: buffer.to_vec(),
+            saved_bytes,
+            eliminated,
+            init_code,
+            creation_bytecode: creation_bytecode.to_vec(),
+            source_map,
+            srcmap,
+            funcs,
+            code_section,
+            storage_layout,
         })
     }
 
+    /// Run the peephole optimizer over the buffer.
+    ///
+    /// The bytes saved are measured against the relocated bytecode
+    /// without the optimization, which includes the pushes of the
+    /// removed jumps.
+    fn optimize(&mut self) -> Result<()> {
+        if !self.config.opt_level.peephole() {
+            return Ok(());
+        }
+
+        let relocated = |mut table: JumpTable, mut buffer: Buffer| -> Result<usize> {
+            table.code_offset(buffer.len() as u16);
+            table.relocate(&mut buffer)?;
+            Ok(buffer.len())
+        };
+
+        let unoptimized = relocated(self.table.clone(), self.buffer.clone())?;
+        let removed = self.table.optimize(&mut self.buffer);
+        tracing::debug!("peephole removed {removed} bytes before relocation");
+
+        let optimized = relocated(self.table.clone(), self.buffer.clone())?;
+        self.saved_bytes = unoptimized.saturating_sub(optimized);
+        Ok(())
+    }
+
+    /// Compile runtime preamble.
+    ///
+    /// Initialize the globals, the page count and the data segments
+    /// of the linear memory before running any function.
+    fn compile_preamble(&mut self, env: &Env) -> Result<()> {
+        let mut preamble = Preamble::default();
+        preamble.globals(env)?;
+        preamble.pages(env)?;
+        preamble.data(env, &mut self.table)?;
+        self.buffer.extend_from_slice(&preamble.finish());
+        Ok(())
+    }
+
     /// Compile EVM dispatcher.
     ///
     /// Drain selectors anyway, compile dispatcher if it is enabled.
@@ -77,8 +214,11 @@ impl Compiler {
             return Ok(());
         }
 
-        let mut dispatcher = Dispatcher::new(env, &parser.funcs)?;
-        let buffer = dispatcher.finish(selectors, &mut self.table)?;
+        let binary_search = self.config.dispatch.binary_search(selectors.len());
+        let mut dispatcher = Dispatcher::new(env, &parser.funcs)?
+            .binary_search(binary_search)
+            .linear_selectors(self.config.opt_level.linear_selectors());
+        let buffer = dispatcher.finish(selectors, &mut self.table, self.buffer.len() as u16)?;
         self.buffer.extend_from_slice(&buffer);
         if self.buffer.len() > BUFFER_LIMIT {
             return Err(Error::BufferOverflow(self.buffer.len()));
@@ -88,25 +228,237 @@ impl Compiler {
         Ok(())
     }
 
-    /// Compile WASM function.
-    fn compile_func(&mut self, env: Env, mut func: wasm::Function<'_>) -> Result<()> {
+    /// Compile the constructor function into the init code.
+    ///
+    /// The constructor function and the functions reachable from it are
+    /// compiled as internal functions with their own jump table, the
+    /// constructor function returns to the end of the init code, which
+    /// is followed by the storage presets and the copying of the runtime
+    /// bytecode in [`zingen::Constructor`].
+    fn compile_constructor(&self, wasm: &[u8]) -> Result<Vec<u8>> {
+        let mut constructors = self
+            .abi
+            .iter()
+            .filter(|abi| matches!(abi.ty, Type::Constructor));
+        let Some(abi) = constructors.next() else {
+            return Ok(Default::default());
+        };
+
+        if constructors.next().is_some() {
+            return Err(zingen::Error::DuplicateEntry(abi.ty.to_string()).into());
+        }
+
+        let mut parser = Parser::try_from(wasm)?;
+        parser.env.max_pages = parser.env.max_pages.min(self.config.max_pages);
+        parser.drain_selectors();
+
+        let env = parser.env.clone();
+        let func = env.query_func(&abi.name)?;
+
+        // None of the functions is the main function in the init code.
+        let mut init = Compiler::new(self.config.clone().dispatcher(true));
+        let mut preamble = Preamble::default();
+        preamble.globals(&env)?;
+        preamble.pages(&env)?;
+        preamble.init_data(
+            &env,
+            &self.table,
+            self.buffer.len(),
+            abi.inputs.len() * 0x20,
+        )?;
+        let ret = preamble.constructor(&env, abi, func, &mut init.table)?;
+        init.buffer.extend_from_slice(&preamble.finish());
+
+        init.compile_reachable(&env, parser.funcs, vec![func])?;
+        init.compile_routines(&env)?;
+
+        init.table.label(ret, init.buffer.len() as u16);
+        let mut masm = MacroAssembler::default();
+        masm._jumpdest()?;
+        init.buffer.extend_from_slice(masm.buffer());
+
+        init.optimize()?;
+        init.table.code_offset(init.buffer.len() as u16);
+        init.table.relocate(&mut init.buffer)?;
+        Ok(init.buffer.to_vec())
+    }
+
+    /// Compile the functions reachable from the entries, returns the
+    /// functions stripped.
+    fn compile_reachable<'w>(
+        &mut self,
+        env: &Env,
+        funcs: Functions<'w>,
+        entries: Vec<u32>,
+    ) -> Result<BTreeMap<u32, wasm::Function<'w>>> {
+        let mut bodies = self.optimize_funcs(&funcs)?;
+        let reachable = ir::reachable(&bodies, entries, env);
+        let mut stripped = BTreeMap::new();
+        for func in funcs.into_funcs() {
+            let index = func.index();
+            if !reachable.contains(&index) {
+                tracing::debug!("strip unreachable function {index}");
+                stripped.insert(index, func);
+                continue;
+            }
+
+            let body = bodies
+                .remove(&index)
+                .filter(|_| self.config.opt_level.fold());
+            self.compile_func(env.with_index(index), func, body)?;
+        }
+
+        // The call graph of the bodies covers the calls emitted by the
+        // code generation, so none of the stripped functions is called.
+        if let Some(index) = self
+            .table
+            .unresolved()
+            .into_iter()
+            .find(|index| stripped.contains_key(index))
+        {
+            return Err(Error::FuncNotReachable(index));
+        }
+
+        Ok(stripped)
+    }
+
+    /// The stripped functions compiled into the init code, which are
+    /// the constructor function and the functions only reachable from it.
+    fn init_funcs(
+        &self,
+        env: &Env,
+        stripped: &BTreeMap<u32, wasm::Function<'_>>,
+    ) -> Result<BTreeSet<u32>> {
+        let Some(abi) = self
+            .abi
+            .iter()
+            .find(|abi| matches!(abi.ty, Type::Constructor))
+        else {
+            return Ok(Default::default());
+        };
+
+        let bodies = stripped
+            .iter()
+            .map(|(index, func)| Ok((*index, Body::new(&func.body)?)))
+            .collect::<Result<BTreeMap<_, _>>>()?;
+        Ok(ir::reachable(&bodies, [env.query_func(&abi.name)?], env))
+    }
+
+    /// Collect the function bodies with the pre-codegen IR, which are
+    /// optimized from `O1`.
+    fn optimize_funcs<'w>(&self, funcs: &Functions<'w>) -> Result<BTreeMap<u32, Body<'w>>> {
+        funcs
+            .values()
+            .map(|func| {
+                let mut body = Body::new(&func.body)?;
+                if self.config.opt_level.fold() {
+                    body.optimize();
+                }
+
+                Ok((func.index(), body))
+            })
+            .collect()
+    }
+
+    /// The entries of the call graph, which are the functions of the
+    /// selectors with the dispatcher, or the main function without it.
+    ///
+    /// The constructor function is compiled into the init code instead.
+    fn entries(&self, env: &Env) -> Result<Vec<u32>> {
+        if !self.config.dispatcher || self.abi.is_empty() {
+            return Ok(vec![env.imports.len() as u32]);
+        }
+
+        self.abi
+            .iter()
+            .filter(|abi| !matches!(abi.ty, Type::Constructor))
+            .map(|abi| env.query_func(&abi.name).map_err(Into::into))
+            .collect()
+    }
+
+    /// Compile WASM function with its optimized body.
+    fn compile_func(
+        &mut self,
+        env: Env,
+        mut func: wasm::Function<'_>,
+        body: Option<Body<'_>>,
+    ) -> Result<()> {
         let func_index = func.index();
         let sig = func.sig()?;
         let abi = self.abi(&env, func_index);
 
         tracing::debug!("compile function {func_index} {:?}, abi: {abi:#?}", sig);
         let is_main = !self.config.dispatcher && env.is_main(func_index);
+
+        // The dispatcher rejects ether for all of the entries if none
+        // of them is payable.
+        let reject_callvalue = self.config.dispatcher
+            && abi
+                .as_ref()
+                .is_some_and(|abi| !abi.state_mutability.is_payable())
+            && self.abi.iter().any(|abi| {
+                !matches!(abi.ty, Type::Constructor) && abi.state_mutability.is_payable()
+            });
+
         let mut codegen = Function::new(env, sig, abi, is_main)?;
+        codegen.shared_reverts = self.config.opt_level.shared_reverts();
+        if reject_callvalue {
+            codegen.reject_callvalue()?;
+        }
+
+        if self.config.dispatcher && !self.config.unchecked_calldata {
+            codegen.validate_calldata()?;
+        }
+
         let mut locals_reader = func.body.get_locals_reader()?;
         let mut ops_reader = func.body.get_operators_reader()?;
 
+        if let Some(body) = body
+            .as_ref()
+            .filter(|_| self.config.opt_level.stack_locals())
+        {
+            codegen.alloc_locals(body.ops());
+        }
+
         codegen.emit_locals(&mut locals_reader, &mut func.validator)?;
-        codegen.emit_operators(&mut ops_reader, &mut func.validator)?;
+        if let Some(body) = body.as_ref() {
+            codegen.emit_body(&mut ops_reader, body, &mut func.validator)?;
+        } else {
+            codegen.emit_operators(&mut ops_reader, &mut func.validator)?;
+        }
 
         self.emit_buffer(func_index, codegen)?;
         Ok(())
     }
 
+    /// Compile the soft-float routines called by the compiled code,
+    /// including the ones called by the routines.
+    fn compile_routines(&mut self, env: &Env) -> Result<()> {
+        loop {
+            let routines = self
+                .table
+                .unresolved()
+                .into_iter()
+                .filter_map(|index| Routine::try_from(index).ok())
+                .collect::<Vec<_>>();
+            if routines.is_empty() {
+                return Ok(());
+            }
+
+            for routine in routines {
+                tracing::debug!("compile soft-float routine {routine:?}");
+                let pc = self.buffer.len() as u16;
+                let buffer = routine.finish(env, &mut self.table, pc)?;
+                self.table.call_offset(routine.index(), pc)?;
+                self.buffer.extend_from_slice(&buffer);
+
+                if self.buffer.len() > BUFFER_LIMIT {
+                    return Err(Error::BufferOverflow(self.buffer.len()));
+                }
+            }
+        }
+    }
+
     /// Emit buffer to the inner buffer.
     fn emit_buffer(&mut self, func_index: u32, codegen: Function) -> Result<()> {
         let buffer = codegen.finish(&mut self.table, self.buffer.len() as u16)?;
@@ -121,9 +473,192 @@ impl Compiler {
         Ok(())
     }
 
-    /// Get abi from env and function index
+    /// Get abi from env and function index, the constructor function
+    /// is called as an internal function.
     fn abi(&self, env: &Env, index: u32) -> Option<Abi> {
         let name = env.exports.get(&index)?;
-        self.abi.iter().find(|a| name == &a.name).cloned()
+        self.abi
+            .iter()
+            .find(|a| name == &a.name && !matches!(a.ty, Type::Constructor))
+            .cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{OptLevel, UnsupportedOperator};
+
+    #[test]
+    fn test_unsupported_operators() -> anyhow::Result<()> {
+        let wasm = wat::parse_str(
+            r#"(module
+                (memory 1)
+                (func (export "main") (param i32) (result i64)
+                  (memory.copy (i32.const 0) (i32.const 1) (i32.const 2))
+                  (i64.trunc_sat_f64_s (f64.const 1))
+                  (call $narrow (local.get 0))
+                  (drop))
+                (func $narrow (param i32) (result i32)
+                  (i32.trunc_sat_f32_u (f32.const 1))))"#,
+        )?;
+
+        let Err(Error::UnsupportedOperator(unsupported)) = Compiler::default().compile(&wasm)
+        else {
+            panic!("unsupported operators should be rejected");
+        };
+
+        let operators = unsupported
+            .iter()
+            .map(|UnsupportedOperator { func, operator, .. }| (*func, *operator))
+            .collect::<Vec<_>>();
+        assert_eq!(operators, [(0, "I64TruncSatF64S"), (1, "I32TruncSatF32U")]);
+        assert!(unsupported[0].offset < unsupported[1].offset);
+        Ok(())
+    }
+
+    #[test]
+    fn test_unsupported_imports() -> anyhow::Result<()> {
+        let wasm = wat::parse_str(
+            r#"(module
+                (import "env" "base" (global i32))
+                (global i32 (i32.const 42))
+                (func (export "main") (result i32)
+                  (global.get 1)))"#,
+        )?;
+
+        let Err(Error::UnsupportedImport(module, name)) = Compiler::default().compile(&wasm) else {
+            panic!("imported globals should be rejected");
+        };
+        assert_eq!((module.as_str(), name.as_str()), ("env", "base"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_storage_item() -> anyhow::Result<()> {
+        let wasm = wat::parse_str(
+            r#"(module
+                (func (export "main"))
+                (@custom "zink.storage" "Counter:0:i32\nBalances:one:U256\n"))"#,
+        )?;
+
+        let Err(Error::InvalidStorageItem(item)) = Compiler::default().compile(&wasm) else {
+            panic!("the slot should be a number");
+        };
+        assert_eq!(item, "Balances:one:U256");
+        Ok(())
+    }
+
+    #[test]
+    fn test_soft_float() -> anyhow::Result<()> {
+        let wasm = wat::parse_str(
+            r#"(module
+                (func (export "main") (param f64) (result f64)
+                  (f64.neg (f64.add (local.get 0) (f64.const 1)))))"#,
+        )?;
+
+        let Err(Error::UnsupportedOperator(unsupported)) = Compiler::default().compile(&wasm)
+        else {
+            panic!("float operators should be rejected without the soft-float runtime");
+        };
+        let operators = unsupported.iter().map(|op| op.operator).collect::<Vec<_>>();
+        assert_eq!(operators, ["F64Add", "F64Neg"]);
+
+        Compiler::new(Config::default().soft_float(true)).compile(&wasm)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_peephole() -> anyhow::Result<()> {
+        let wasm = wat::parse_str(
+            r#"(module
+                (func (export "main") (param i32) (result i32)
+                  (i32.add (local.get 0) (i32.const 0))))"#,
+        )?;
+
+        let unoptimized =
+            Compiler::new(Config::default().opt_level(OptLevel::O0)).compile(&wasm)?;
+        assert_eq!(unoptimized.config.opt_level, OptLevel::O0);
+        assert_eq!(unoptimized.saved_bytes, 0);
+
+        let optimized = Compiler::new(Config::default().opt_level(OptLevel::Os)).compile(&wasm)?;
+        assert!(optimized.saved_bytes > 0);
+        assert_eq!(
+            unoptimized.runtime_bytecode.len() - optimized.runtime_bytecode.len(),
+            optimized.saved_bytes
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_opt_levels() -> anyhow::Result<()> {
+        let wasm = wat::parse_str(
+            r#"(module
+                (func (export "main") (param i32) (result i32) (local i32)
+                  (local.set 1 (i32.add (i32.const 1) (i32.const 2)))
+                  (i32.add (i32.add (local.get 0) (local.get 1)) (i32.const 0))))"#,
+        )?;
+        let compile =
+            |opt_level| Compiler::new(Config::default().opt_level(opt_level)).compile(&wasm);
+        let [o0, o1, o2] = [OptLevel::O0, OptLevel::O1, OptLevel::O2].map(compile);
+        let (o0, o1, o2) = (o0?, o1?, o2?);
+
+        // the library does not optimize by default
+        assert_eq!(Config::default().opt_level, OptLevel::O0);
+
+        // `O1` only folds the constants
+        assert!(o1.runtime_bytecode.len() < o0.runtime_bytecode.len());
+        assert_eq!(o1.saved_bytes, 0);
+
+        // `O2` keeps the local on the stack without `MLOAD`, and runs
+        // the peephole optimizer
+        assert!(o1.runtime_bytecode.contains(&0x51));
+        assert!(!o2.runtime_bytecode.contains(&0x51));
+        assert!(o2.saved_bytes > 0);
+        assert!(o2.runtime_bytecode.len() < o1.runtime_bytecode.len());
+        Ok(())
+    }
+
+    #[test]
+    fn test_strip_unreachable_functions() -> anyhow::Result<()> {
+        let wasm = wat::parse_str(
+            r#"(module
+                (func (export "main") (param i32) (result i32)
+                  (if (i32.eq (i32.const 1) (i32.const 2))
+                    (then (drop (call $called (local.get 0)))))
+                  (i32.add (local.get 0) (i32.add (i32.const 1) (i32.const 2))))
+                (func $called (param i32) (result i32)
+                  (call $unused (local.get 0)))
+                (func $unused (export "unused") (param i32) (result i32)
+                  (local.get 0)))"#,
+        )?;
+        let stripped = wat::parse_str(
+            r#"(module
+                (func (export "main") (param i32) (result i32)
+                  (i32.add (local.get 0) (i32.const 3))))"#,
+        )?;
+
+        let config = Config::default().opt_level(OptLevel::Os);
+        let artifact = Compiler::new(config.clone()).compile(&wasm)?;
+        assert_eq!(
+            artifact.runtime_bytecode,
+            Compiler::new(config).compile(&stripped)?.runtime_bytecode
+        );
+
+        let eliminated = vec![
+            EliminatedFunc {
+                index: 1,
+                name: None,
+            },
+            EliminatedFunc {
+                index: 2,
+                name: Some("unused".into()),
+            },
+        ];
+        assert_eq!(artifact.eliminated, eliminated);
+
+        // the call in the unfolded `if` reaches both of the functions
+        assert!(Compiler::default().compile(&wasm)?.eliminated.is_empty());
+        Ok(())
     }
 }
@@ -2,15 +2,59 @@
 
 #[cfg(feature = "cli")]
 use ccli::clap;
+use zingen::LINEAR_SELECTORS;
+
+/// Default maximum pages of the linear memory, 4MiB.
+pub const MAX_PAGES: u32 = 0x40;
+
+/// Selectors over which the dispatcher binary searches by default.
+pub const BINARY_SEARCH_SELECTORS: usize = 8;
 
 /// Zink compiler configuration.
-#[derive(Debug, Default)]
-#[cfg_attr(feature = "cli", derive(clap::Parser))]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "cli", derive(clap::Args))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct Config {
     /// If enable dispatcher.
-    #[cfg_attr(feature = "cli", clap(long))]
+    #[cfg_attr(feature = "cli", clap(short, long))]
     pub dispatcher: bool,
+    /// Strategy of matching the selectors in the dispatcher.
+    #[cfg_attr(
+        feature = "cli",
+        clap(long, value_enum, default_value_t = Dispatch::default())
+    )]
+    pub dispatch: Dispatch,
+    /// If skip validating the calldata of the external functions,
+    /// which saves gas but lets the short or dirty calldata through.
+    #[cfg_attr(feature = "cli", clap(long))]
+    pub unchecked_calldata: bool,
+    /// Maximum pages of the linear memory, `memory.grow` fails past it.
+    #[cfg_attr(feature = "cli", clap(long, default_value_t = MAX_PAGES))]
+    pub max_pages: u32,
+    /// If enable the soft-float runtime for the float instructions.
+    #[cfg_attr(feature = "cli", clap(long))]
+    pub soft_float: bool,
+    /// Optimization level of wasm-opt and the backend passes, the
+    /// command line defaults to `Os`.
+    #[cfg_attr(
+        feature = "cli",
+        clap(short = 'O', long, value_enum, default_value_t = OptLevel::Os)
+    )]
+    pub opt_level: OptLevel,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            dispatcher: false,
+            dispatch: Dispatch::default(),
+            unchecked_calldata: false,
+            max_pages: MAX_PAGES,
+            soft_float: false,
+            opt_level: OptLevel::default(),
+        }
+    }
 }
 
 impl Config {
@@ -19,4 +63,130 @@ impl Config {
         self.dispatcher = dispatcher;
         self
     }
+
+    /// With the strategy of the dispatcher.
+    pub fn dispatch(mut self, dispatch: Dispatch) -> Self {
+        self.dispatch = dispatch;
+        self
+    }
+
+    /// With the unchecked calldata value.
+    pub fn unchecked_calldata(mut self, unchecked_calldata: bool) -> Self {
+        self.unchecked_calldata = unchecked_calldata;
+        self
+    }
+
+    /// With maximum pages of the linear memory.
+    pub fn max_pages(mut self, max_pages: u32) -> Self {
+        self.max_pages = max_pages;
+        self
+    }
+
+    /// With the soft-float runtime value.
+    pub fn soft_float(mut self, soft_float: bool) -> Self {
+        self.soft_float = soft_float;
+        self
+    }
+
+    /// With optimization level.
+    pub fn opt_level(mut self, opt_level: OptLevel) -> Self {
+        self.opt_level = opt_level;
+        self
+    }
+}
+
+/// Optimization level.
+///
+/// Besides the passes of wasm-opt, the levels enable the backend passes
+/// one over another, `O1` folds the constants and removes the dead code
+/// of the functions, `O2` also keeps the short-lived locals on the stack
+/// and runs the peephole optimizer, and `Os` makes the choices for the
+/// smaller code over gas.
+///
+/// `O0` is the default of the library, which keeps the generated code
+/// close to the WASM, while the command line defaults to `Os`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum OptLevel {
+    /// No optimization, for debugging the generated code.
+    #[default]
+    #[cfg_attr(feature = "cli", value(name = "0"))]
+    O0,
+    /// The basic optimizations of wasm-opt, with the constants folded
+    /// and the dead code removed.
+    #[cfg_attr(feature = "cli", value(name = "1"))]
+    O1,
+    /// The optimizations of wasm-opt without shrinking the code, with
+    /// the locals on the stack and the peephole optimizer, which trades
+    /// size for gas.
+    #[cfg_attr(feature = "cli", value(name = "2"))]
+    O2,
+    /// The aggressive optimizations of wasm-opt for size, with the
+    /// backend choices for the smaller code.
+    #[cfg_attr(feature = "cli", value(name = "s"))]
+    Os,
+}
+
+impl OptLevel {
+    /// If fold the constants and remove the dead code of the functions.
+    pub fn fold(&self) -> bool {
+        *self != Self::O0
+    }
+
+    /// If keep the short-lived locals on the stack.
+    pub fn stack_locals(&self) -> bool {
+        *self >= Self::O2
+    }
+
+    /// If run the peephole optimizer.
+    pub fn peephole(&self) -> bool {
+        *self >= Self::O2
+    }
+
+    /// If share the reverts of the checks of the external functions in
+    /// the code section instead of inlining them.
+    pub fn shared_reverts(&self) -> bool {
+        *self == Self::Os
+    }
+
+    /// Maximum selectors matched one by one in a branch of the binary
+    /// search of the dispatcher, which is doubled with `Os` for the
+    /// fewer comparisons.
+    pub fn linear_selectors(&self) -> usize {
+        if *self == Self::Os {
+            2 * LINEAR_SELECTORS
+        } else {
+            LINEAR_SELECTORS
+        }
+    }
+}
+
+/// Strategy of matching the selectors in the dispatcher.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Dispatch {
+    /// Binary search the selectors if there are more than
+    /// [`BINARY_SEARCH_SELECTORS`] of them, otherwise match them
+    /// one by one.
+    #[default]
+    Auto,
+    /// Match the selectors one by one, the gas of a call grows with
+    /// the position of its selector.
+    Linear,
+    /// Binary search the sorted selectors, the gas of a call grows
+    /// with the logarithm of the selector count.
+    BinarySearch,
+}
+
+impl Dispatch {
+    /// If binary search the selectors.
+    pub fn binary_search(&self, selectors: usize) -> bool {
+        match self {
+            Self::Auto => selectors > BINARY_SEARCH_SELECTORS,
+            Self::Linear => false,
+            Self::BinarySearch => true,
+        }
+    }
 }
@@ -0,0 +1,158 @@
+//! Solidity-compatible JSON artifact.
+#![cfg(feature = "serde")]
+
+use crate::{Artifact, StorageItem};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use zabi::{Abi, Type};
+
+/// JSON artifact in the layout of solc and Foundry, which could be
+/// read by the tooling of the Solidity contracts.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsonArtifact {
+    /// Contract ABIs.
+    pub abi: Vec<Abi>,
+    /// Creation bytecode of the contract.
+    pub bytecode: Bytecode,
+    /// Runtime bytecode of the contract.
+    pub deployed_bytecode: Bytecode,
+    /// Selectors of the external functions by their signatures.
+    pub method_identifiers: BTreeMap<String, String>,
+    /// Storage layout of the contract.
+    pub storage_layout: StorageLayout,
+}
+
+/// Bytecode object.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Bytecode {
+    /// Hex of the bytecode with the `0x` prefix.
+    pub object: String,
+    /// Source map of the bytecode in the `srcmap` format of solc.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub source_map: String,
+}
+
+impl From<&[u8]> for Bytecode {
+    fn from(bytecode: &[u8]) -> Self {
+        Self {
+            object: format!("0x{}", hex::encode(bytecode)),
+            source_map: Default::default(),
+        }
+    }
+}
+
+/// Storage layout.
+///
+/// The storage items of zink contracts take the whole slots, and the
+/// types are identified by their names in Rust, like `t_i32` or
+/// `t_mapping(t_i32,t_Address)`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct StorageLayout {
+    /// Storage slots of the state variables.
+    pub storage: Vec<StorageSlot>,
+    /// Types of the state variables by their identifiers.
+    pub types: BTreeMap<String, StorageType>,
+}
+
+/// Storage slot of a state variable.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct StorageSlot {
+    /// Name of the state variable.
+    pub label: String,
+    /// Storage slot in decimal.
+    pub slot: String,
+    /// Offset in bytes within the slot.
+    pub offset: u32,
+    /// Identifier of the type.
+    #[serde(rename = "type")]
+    pub ty: String,
+}
+
+/// Type of a state variable.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageType {
+    /// Encoding of the type, like `inplace` or `mapping`.
+    pub encoding: String,
+    /// Name of the type.
+    pub label: String,
+    /// Size of the type in bytes in decimal.
+    pub number_of_bytes: String,
+    /// Identifier of the key type of the mapping.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    /// Identifier of the value type of the mapping.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+}
+
+impl From<&[StorageItem]> for StorageLayout {
+    fn from(items: &[StorageItem]) -> Self {
+        let mut layout = Self::default();
+        for item in items {
+            let mut id = format!("t_{}", item.value);
+            let mut label = item.value.clone();
+            layout.types.entry(id.clone()).or_insert(StorageType {
+                encoding: "inplace".into(),
+                label: label.clone(),
+                number_of_bytes: "32".into(),
+                ..Default::default()
+            });
+
+            // the mappings are nested from the last key
+            for key in item.keys.iter().rev() {
+                let key_id = format!("t_{key}");
+                layout.types.entry(key_id.clone()).or_insert(StorageType {
+                    encoding: "inplace".into(),
+                    label: key.clone(),
+                    number_of_bytes: "32".into(),
+                    ..Default::default()
+                });
+
+                let value_id = id;
+                id = format!("t_mapping({key_id},{value_id})");
+                label = format!("mapping({key} => {label})");
+                layout.types.entry(id.clone()).or_insert(StorageType {
+                    encoding: "mapping".into(),
+                    label: label.clone(),
+                    number_of_bytes: "32".into(),
+                    key: Some(key_id),
+                    value: Some(value_id),
+                });
+            }
+
+            layout.storage.push(StorageSlot {
+                label: item.label.clone(),
+                slot: item.slot.to_string(),
+                offset: 0,
+                ty: id,
+            });
+        }
+
+        layout
+    }
+}
+
+impl From<&Artifact> for JsonArtifact {
+    fn from(artifact: &Artifact) -> Self {
+        let method_identifiers = artifact
+            .abi
+            .iter()
+            .filter(|abi| matches!(abi.ty, Type::Function))
+            .map(|abi| (abi.signature(), hex::encode(abi.selector())))
+            .collect();
+
+        Self {
+            abi: artifact.abi.clone(),
+            bytecode: artifact.creation_bytecode.as_slice().into(),
+            deployed_bytecode: Bytecode {
+                source_map: artifact.srcmap.clone(),
+                ..artifact.runtime_bytecode.as_slice().into()
+            },
+            method_identifiers,
+            storage_layout: artifact.storage_layout.as_slice().into(),
+        }
+    }
+}
@@ -2,17 +2,23 @@
 #![deny(missing_docs)]
 
 pub use crate::{
-    artifact::Artifact,
+    artifact::{Artifact, CompiledFunc, EliminatedFunc, Line, SourceLocation, StorageItem},
     compiler::Compiler,
-    config::Config,
-    result::{Error, Result},
+    config::{Config, Dispatch, OptLevel, BINARY_SEARCH_SELECTORS, MAX_PAGES},
+    result::{Error, Result, UnsupportedOperator},
 };
 pub use zingen::{Constructor, InitStorage};
 
+#[cfg(feature = "serde")]
+pub use crate::json::{Bytecode, JsonArtifact, StorageLayout, StorageSlot, StorageType};
+
 mod artifact;
 pub mod cli;
 mod compiler;
 mod config;
+mod json;
+mod listing;
 mod parser;
 mod result;
+mod srcmap;
 pub mod utils;
@@ -0,0 +1,93 @@
+//! Annotated listing of the runtime bytecode.
+
+use crate::Artifact;
+use opcodes::Cancun;
+use std::{collections::BTreeMap, fmt::Write};
+use wasmparser::BinaryReader;
+use zabi::Type;
+
+impl Artifact {
+    /// Disassemble the runtime bytecode into a listing.
+    ///
+    /// The entries of the functions and the code section are labeled,
+    /// the selectors pushed by the dispatcher are annotated with the
+    /// signatures of their functions, and the instructions generated
+    /// from the WASM operators are annotated with the operators, which
+    /// are read from the WASM module compiled into this artifact.
+    pub fn listing(&self, wasm: &[u8]) -> String {
+        let mut labels = BTreeMap::new();
+        for func in self.funcs.iter() {
+            let abi = self
+                .abi
+                .iter()
+                .find(|abi| Some(&abi.name) == func.name.as_ref());
+            let label = match (abi, func.name.as_ref()) {
+                (Some(abi), _) if matches!(abi.ty, Type::Function) => format!(
+                    "function {}: {} (0x{})",
+                    func.index,
+                    abi.signature(),
+                    hex::encode(abi.selector())
+                ),
+                (_, Some(name)) => format!("function {}: {name}", func.index),
+                _ => format!("function {}", func.index),
+            };
+
+            labels.insert(func.pc as usize, label);
+        }
+
+        // the preamble and the dispatcher
+        labels.entry(0).or_insert_with(|| "entry".into());
+        for (pc, len) in self.code_section.iter() {
+            labels.insert(*pc as usize, format!("code section: {len} bytes"));
+        }
+
+        let selectors = self
+            .abi
+            .iter()
+            .filter(|abi| matches!(abi.ty, Type::Function))
+            .map(|abi| (abi.selector().to_vec(), abi.signature()))
+            .collect::<BTreeMap<_, _>>();
+
+        let mut listing = String::new();
+        let mut last = None;
+        for instr in opcodes::disassemble(&self.runtime_bytecode) {
+            if let Some(label) = labels.get(&instr.pc) {
+                let _ = writeln!(listing, "\n; {label}");
+                last = None;
+            }
+
+            let location = self.source(instr.pc as u16);
+            let comment = if instr.opcode == Some(Cancun::PUSH4) {
+                selectors.get(&instr.immediate).cloned()
+            } else {
+                None
+            }
+            .or_else(|| {
+                let location = location?;
+                if last == Some(location.offset) {
+                    return None;
+                }
+
+                let bytes = wasm.get(location.offset..location.offset + location.len)?;
+                let operator = BinaryReader::new_with_offset(bytes, location.offset)
+                    .read_operator()
+                    .ok()?;
+                let mut comment = format!("{operator:?} at 0x{:x}", location.offset);
+                if let Some(line) = location.line.as_ref() {
+                    let _ = write!(comment, " ({}:{})", line.file, line.line);
+                }
+
+                Some(comment)
+            });
+            last = location.map(|location| location.offset);
+
+            let line = format!("{:04x}: {instr}", instr.pc);
+            let _ = match comment {
+                Some(comment) => writeln!(listing, "{line:<32}; {comment}"),
+                None => writeln!(listing, "{line}"),
+            };
+        }
+
+        listing.trim_start().into()
+    }
+}
@@ -1,12 +1,19 @@
 //! Zink parser
 
-use crate::{Error, Result};
-use std::iter::IntoIterator;
+use crate::{srcmap::Lines, Error, Result, StorageItem, UnsupportedOperator};
+use std::{collections::BTreeMap, iter::IntoIterator};
 use wasmparser::{
-    Data, DataKind, Export, ExternalKind, Import, Operator, Payload, SectionLimited, TypeRef,
-    ValidPayload, Validator,
+    Data, DataKind, Element, ElementItems, ElementKind, Export, ExternalKind,
+    FunctionSectionReader, Global, Import, Operator, Payload, SectionLimited, TypeRef,
+    TypeSectionReader, ValidPayload, Validator,
+};
+use zingen::{
+    wasm::{
+        Data as DataSet, Env, Exports, Functions, Globals, HostFunc, Imports, Signatures, Tables,
+        ToLSBytes, Types,
+    },
+    FLOAT_REGISTERS,
 };
-use zingen::wasm::{Data as DataSet, Env, Exports, Functions, HostFunc, Imports};
 
 /// WASM module parser
 #[derive(Default)]
@@ -15,12 +22,22 @@ pub struct Parser<'p> {
     pub env: Env,
     /// All functions
     pub funcs: Functions<'p>,
+    /// Unsupported operators in the function bodies
+    pub unsupported: Vec<UnsupportedOperator>,
+    /// Float operators which require the soft-float runtime
+    pub floats: Vec<UnsupportedOperator>,
+    /// Rust source lines from the DWARF sections
+    pub lines: Lines,
+    /// Storage items from the custom section `zink.storage`
+    pub storage: Vec<StorageItem>,
 }
 
 impl<'p> Parser<'p> {
     /// Parse WASM module.
     pub fn parse(&mut self, wasm: &'p [u8]) -> Result<()> {
         let mut validator = Validator::new();
+        let mut debug = BTreeMap::new();
+        let mut code_offset = 0;
 
         // Compile functions.
         for payload in wasmparser::Parser::new(0).parse_all(wasm) {
@@ -31,6 +48,26 @@ impl<'p> Parser<'p> {
                 Payload::ImportSection(reader) => self.env.imports = Self::imports(reader)?,
                 Payload::DataSection(reader) => self.env.data = Self::data(reader)?,
                 Payload::ExportSection(reader) => self.env.exports = Self::exports(reader)?,
+                Payload::TypeSection(reader) => self.env.types = Self::types(reader)?,
+                Payload::FunctionSection(reader) => {
+                    self.env.signatures = Self::signatures(reader, self.env.imports.len() as u32)?
+                }
+                Payload::ElementSection(reader) => self.env.tables = Self::elements(reader)?,
+                Payload::GlobalSection(reader) => self.env.globals = Self::globals(reader)?,
+                Payload::MemorySection(reader) => {
+                    if let Some(memory) = reader.clone().into_iter().next() {
+                        let memory = memory?;
+                        self.env.initial_pages = memory.initial as u32;
+                        self.env.max_pages = memory.maximum.unwrap_or(u32::MAX as u64) as u32;
+                    }
+                }
+                Payload::CodeSectionStart { range, .. } => code_offset = range.start,
+                Payload::CustomSection(reader) if reader.name().starts_with(".debug_") => {
+                    debug.insert(reader.name(), reader.data());
+                }
+                Payload::CustomSection(reader) if reader.name() == "zink.storage" => {
+                    self.storage = Self::storage(reader.data())?
+                }
                 _ => {}
             }
 
@@ -40,12 +77,18 @@ impl<'p> Parser<'p> {
             }
         }
 
+        self.lines = Lines::new(&debug, code_offset)?;
+
         // compute slots from functions
-        let mut slots = self.env.imports.reserved();
+        let mut slots = self.env.imports.reserved() + self.env.globals.len() as u32;
         for (idx, fun) in self.funcs.iter() {
             let sig = fun.sig()?;
-            let locals = fun.body.get_locals_reader()?.get_count();
-            let params = sig.params().len();
+            let locals = fun
+                .body
+                .get_locals_reader()?
+                .into_iter()
+                .try_fold(0, |acc, local| local.map(|(count, _)| acc + count))?;
+            let params = sig.params().len() as u32;
             tracing::trace!(
                 "computing slots for function {idx}, locals: {locals}, params: {params}, reserved: {slots}, external: {}",
                 self.env.is_external(fun.index())
@@ -54,16 +97,65 @@ impl<'p> Parser<'p> {
             self.env.slots.insert(fun.index(), slots);
             self.env
                 .funcs
-                .insert(fun.index(), (params as u32, sig.results().len() as u32));
+                .insert(fun.index(), (params, sig.results().len() as u32));
+
+            // the local indexes of the function body start after
+            // the params, so reserve slots for both of them.
+            self.env.frames.insert(fun.index(), params + locals);
+            slots += params + locals;
 
-            slots += locals;
+            // record the internal calls and the unsupported operators of this function
+            let mut reader = fun.body.get_operators_reader()?;
+            while !reader.eof() {
+                let (operator, offset) = reader.read_with_offset()?;
+                if let Some(name) = zingen::unsupported_operator(&operator) {
+                    self.unsupported.push(UnsupportedOperator {
+                        func: fun.index(),
+                        offset,
+                        operator: name,
+                    });
+                }
 
-            // process params for internal functions only
-            if !self.env.is_external(fun.index()) && !self.env.is_main(fun.index()) {
-                slots += params as u32;
+                self.env.reads_memory |= zingen::memory_read(&operator);
+                if let Some(name) = zingen::float_operator(&operator) {
+                    self.floats.push(UnsupportedOperator {
+                        func: fun.index(),
+                        offset,
+                        operator: name,
+                    });
+                }
+
+                match operator {
+                    Operator::Call { function_index }
+                        if !self.env.imports.contains_key(&function_index) =>
+                    {
+                        self.env.calls.call(fun.index(), function_index);
+                    }
+                    Operator::CallIndirect {
+                        type_index,
+                        table_index,
+                        ..
+                    } => {
+                        for (_, callee) in self.env.indirect_callees(type_index, table_index) {
+                            self.env.calls.call(fun.index(), callee);
+                        }
+                    }
+                    _ => {}
+                }
             }
         }
 
+        // the registers of the soft-float routines are only reserved for floats
+        let registers = if self.floats.is_empty() {
+            0
+        } else {
+            FLOAT_REGISTERS
+        };
+
+        self.env.frame_stack = slots;
+        self.env.float = slots + 1 + self.env.calls.frame_stack();
+        self.env.pages = self.env.float + registers;
+        self.env.memory = (self.env.pages + 1) * 0x20;
         Ok(())
     }
 
@@ -72,6 +164,38 @@ impl<'p> Parser<'p> {
         self.funcs.drain_selectors(&self.env.exports)
     }
 
+    /// Parse the storage items from the custom section `zink.storage`,
+    /// each item is a line `<label>:<slot>:<type>`, where the type is
+    /// the value type behind the key types of the mappings, separated
+    /// by `=>`.
+    fn storage(data: &[u8]) -> Result<Vec<StorageItem>> {
+        let mut storage = String::from_utf8_lossy(data)
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                let invalid = || Error::InvalidStorageItem(line.into());
+                let mut fields = line.split(':');
+                let (Some(label), Some(slot), Some(ty), None) =
+                    (fields.next(), fields.next(), fields.next(), fields.next())
+                else {
+                    return Err(invalid());
+                };
+
+                let mut keys: Vec<String> = ty.split("=>").map(Into::into).collect();
+                let value = keys.pop().unwrap_or_default();
+                Ok(StorageItem {
+                    label: label.into(),
+                    slot: slot.parse().map_err(|_| invalid())?,
+                    keys,
+                    value,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        storage.sort_by_key(|item| item.slot);
+        Ok(storage)
+    }
+
     /// Parse data section.
     fn data(reader: &SectionLimited<Data>) -> Result<DataSet> {
         let mut dataset = DataSet::default();
@@ -95,6 +219,90 @@ impl<'p> Parser<'p> {
         Ok(dataset)
     }
 
+    /// Parse element section.
+    ///
+    /// Only the active segments are collected since the tables
+    /// could not be modified at runtime.
+    fn elements(reader: &SectionLimited<Element>) -> Result<Tables> {
+        let mut tables = Tables::default();
+        for element in reader.clone().into_iter() {
+            let Element { kind, items, .. } = element?;
+            let ElementKind::Active {
+                table_index,
+                offset_expr,
+            } = kind
+            else {
+                continue;
+            };
+
+            // [i32.const offset]
+            let mut reader = offset_expr.get_binary_reader();
+            let Operator::I32Const { value: offset } = reader.read_operator()? else {
+                return Err(Error::InvalidElementOffset);
+            };
+
+            let table = tables.entry(table_index.unwrap_or(0)).or_default();
+            match items {
+                ElementItems::Functions(funcs) => {
+                    for (index, func) in funcs.into_iter().enumerate() {
+                        table.insert(offset as u32 + index as u32, func?);
+                    }
+                }
+                ElementItems::Expressions(_, exprs) => {
+                    for (index, expr) in exprs.into_iter().enumerate() {
+                        // `ref.null` leaves the element uninitialized
+                        if let Operator::RefFunc { function_index } =
+                            expr?.get_binary_reader().read_operator()?
+                        {
+                            table.insert(offset as u32 + index as u32, function_index);
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(tables)
+    }
+
+    /// Parse global section.
+    fn globals(reader: &SectionLimited<Global>) -> Result<Globals> {
+        let mut globals = Globals::default();
+        for (index, global) in reader.clone().into_iter().enumerate() {
+            let mut reader = global?.init_expr.get_binary_reader();
+            let init = match reader.read_operator()? {
+                Operator::I32Const { value } => value.to_ls_bytes().to_vec(),
+                Operator::I64Const { value } => value.to_ls_bytes().to_vec(),
+                _ => return Err(Error::InvalidGlobalInit),
+            };
+
+            globals.insert(index as u32, init.into());
+        }
+
+        Ok(globals)
+    }
+
+    /// Parse type section.
+    fn types(reader: &TypeSectionReader) -> Result<Types> {
+        let mut types = Types::default();
+        for (index, ty) in reader.clone().into_iter_err_on_gc_types().enumerate() {
+            types.insert(index as u32, ty?);
+        }
+
+        Ok(types)
+    }
+
+    /// Parse function section.
+    ///
+    /// The indexes of the defined functions start after the imported ones.
+    fn signatures(reader: &FunctionSectionReader, imports: u32) -> Result<Signatures> {
+        let mut signatures = Signatures::default();
+        for (index, ty) in reader.clone().into_iter().enumerate() {
+            signatures.insert(imports + index as u32, ty?);
+        }
+
+        Ok(signatures)
+    }
+
     /// Parse export section
     pub fn exports(reader: &SectionLimited<Export>) -> Result<Exports> {
         let mut exports = Exports::default();
@@ -121,22 +329,25 @@ impl<'p> Parser<'p> {
     }
 
     /// Parse import section.
+    ///
+    /// The imported globals are rejected, since they have no initial
+    /// values in the bytecode and would shift the indexes of the
+    /// defined globals.
     pub fn imports(reader: &SectionLimited<Import>) -> Result<Imports> {
-        // TODO: use real index from WASM. (#122)
         let mut index = 0;
-
         let mut imports = Imports::default();
-        let mut iter = reader.clone().into_iter();
-        while let Some(Ok(Import {
-            module,
-            name,
-            ty: TypeRef::Func(_),
-        })) = iter.next()
-        {
-            let func = HostFunc::try_from((module, name))?;
-            tracing::trace!("imported function: {}::{} at {index}", module, name);
-            imports.insert(index, func);
-            index += 1;
+        for import in reader.clone().into_iter() {
+            let Import { module, name, ty } = import?;
+            match ty {
+                TypeRef::Func(_) => {
+                    let func = HostFunc::try_from((module, name))?;
+                    tracing::trace!("imported function: {}::{} at {index}", module, name);
+                    imports.insert(index, func);
+                    index += 1;
+                }
+                TypeRef::Memory(_) => {}
+                _ => return Err(Error::UnsupportedImport(module.into(), name.into())),
+            }
         }
 
         Ok(imports)
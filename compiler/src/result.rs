@@ -1,5 +1,7 @@
 //! Zinkc result
 
+use std::fmt;
+
 /// Zinkc errors
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -15,9 +17,54 @@ pub enum Error {
     /// Failed in code generation.
     #[error(transparent)]
     Codegen(#[from] zingen::Error),
+    /// Failed to read the DWARF sections.
+    #[error(transparent)]
+    Dwarf(#[from] gimli::Error),
+    /// Failed to find the called function in the call graph.
+    #[error("Function {0} is called but not reachable in the call graph")]
+    FuncNotReachable(u32),
     /// Failed to parse WASM data with data reader.
     #[error("Invalid data offset")]
     InvalidDataOffset,
+    /// Failed to parse WASM global with global reader.
+    #[error("Invalid global initializer")]
+    InvalidGlobalInit,
+    /// Failed to parse WASM element with element reader.
+    #[error("Invalid element offset")]
+    InvalidElementOffset,
+    /// Failed to parse the storage item from the custom section.
+    #[error("Invalid storage item {0:?} in the custom section zink.storage")]
+    InvalidStorageItem(String),
+    /// Failed to import the items other than functions and memories.
+    #[error("Unsupported import {0}::{1}, only functions and memories could be imported")]
+    UnsupportedImport(String, String),
+    /// Failed to compile the unsupported operators.
+    #[error(
+        "Unsupported operators:{}",
+        .0.iter().map(|op| format!("\n  {op}")).collect::<String>()
+    )]
+    UnsupportedOperator(Vec<UnsupportedOperator>),
+}
+
+/// Unsupported operator in a function body.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsupportedOperator {
+    /// Index of the function.
+    pub func: u32,
+    /// Byte offset of the operator in the WASM module.
+    pub offset: usize,
+    /// Name of the operator.
+    pub operator: &'static str,
+}
+
+impl fmt::Display for UnsupportedOperator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} in function {} at offset 0x{:x}",
+            self.operator, self.func, self.offset
+        )
+    }
 }
 
 /// Zinkc result
@@ -0,0 +1,143 @@
+//! Source maps of the runtime bytecode.
+
+use crate::{Line, Result, SourceLocation};
+use gimli::{Dwarf, EndianSlice, LittleEndian};
+use std::{collections::BTreeMap, path::PathBuf};
+use zingen::SourceMap;
+
+/// Rust source lines of the WASM operators, which are read from the
+/// line programs of the DWARF sections.
+#[derive(Debug, Default)]
+pub struct Lines {
+    /// Offset of the code section, the addresses of DWARF in WASM are
+    /// relative to it.
+    code_offset: usize,
+    /// Source lines by the addresses where they start, the ends of
+    /// the sequences have no lines.
+    rows: BTreeMap<u64, Option<Line>>,
+}
+
+impl Lines {
+    /// Read the line programs from the DWARF sections of the module,
+    /// the lines are empty if the module carries no DWARF.
+    pub fn new(sections: &BTreeMap<&str, &[u8]>, code_offset: usize) -> Result<Self> {
+        let mut lines = Self {
+            code_offset,
+            rows: Default::default(),
+        };
+
+        if !sections.contains_key(".debug_line") {
+            return Ok(lines);
+        }
+
+        let dwarf = Dwarf::load(|id| {
+            let section = sections.get(id.name()).copied().unwrap_or_default();
+            Ok::<_, gimli::Error>(EndianSlice::new(section, LittleEndian))
+        })?;
+
+        let mut units = dwarf.units();
+        while let Some(header) = units.next()? {
+            let unit = dwarf.unit(header)?;
+            let Some(program) = unit.line_program.clone() else {
+                continue;
+            };
+
+            let mut rows = program.rows();
+            while let Some((header, row)) = rows.next_row()? {
+                if row.end_sequence() {
+                    lines.rows.insert(row.address(), None);
+                    continue;
+                }
+
+                let Some(file) = row.file(header) else {
+                    continue;
+                };
+
+                let mut path = PathBuf::new();
+                if let Some(dir) = file.directory(header) {
+                    path.push(&*dwarf.attr_string(&unit, dir)?.to_string_lossy());
+                }
+                path.push(
+                    &*dwarf
+                        .attr_string(&unit, file.path_name())?
+                        .to_string_lossy(),
+                );
+
+                lines.rows.insert(
+                    row.address(),
+                    Some(Line {
+                        file: path.to_string_lossy().into(),
+                        line: row.line().map(|line| line.get()).unwrap_or_default(),
+                    }),
+                );
+            }
+        }
+
+        Ok(lines)
+    }
+
+    /// Get the source line of the operator at the offset of the module.
+    pub fn get(&self, offset: usize) -> Option<Line> {
+        let address = offset.checked_sub(self.code_offset)? as u64;
+        self.rows
+            .range(..=address)
+            .next_back()
+            .and_then(|(_, line)| line.clone())
+    }
+}
+
+/// Resolve the source locations of the instructions of the bytecode,
+/// and encode them in the `srcmap` format of solc.
+///
+/// The WASM module is the source `0` of the `srcmap`, where the
+/// instructions point at the byte ranges of their operators, and the
+/// code generated without operators points at the source `-1`.
+pub fn resolve(
+    bytecode: &[u8],
+    srcmap: &SourceMap,
+    lines: &Lines,
+) -> (Vec<SourceLocation>, String) {
+    let mut locations = Vec::new();
+    let mut entries = Vec::new();
+    let mut last: Option<[String; 4]> = None;
+    for (pc, source) in srcmap.sources(bytecode) {
+        let fields: [String; 4] = if let Some(source) = source {
+            locations.push(SourceLocation {
+                pc,
+                func: source.func,
+                offset: source.offset,
+                len: source.len,
+                line: lines.get(source.offset),
+            });
+
+            [
+                source.offset.to_string(),
+                source.len.to_string(),
+                "0".into(),
+                "-".into(),
+            ]
+        } else {
+            ["-1", "-1", "-1", "-"].map(Into::into)
+        };
+
+        // the fields same with the last entry are omitted
+        let mut entry = fields
+            .iter()
+            .enumerate()
+            .map(|(index, field)| {
+                if last.as_ref().is_some_and(|last| last[index] == *field) {
+                    ""
+                } else {
+                    field.as_str()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(":");
+        entry.truncate(entry.trim_end_matches(':').len());
+
+        entries.push(entry);
+        last = Some(fields);
+    }
+
+    (locations, entries.join(";"))
+}
@@ -1,12 +1,35 @@
 //! Zink compiler utils
 #![cfg(feature = "utils")]
 
-use std::path::Path;
+use crate::OptLevel;
+use std::{fs, path::Path};
 
-/// Run wasm-opt on the given WASM file.
-pub fn wasm_opt(input: impl AsRef<Path>, output: impl AsRef<Path>) -> anyhow::Result<()> {
-    ::wasm_opt::OptimizationOptions::new_opt_level_4()
-        .shrink_level(::wasm_opt::ShrinkLevel::Level2)
+/// Run wasm-opt on the given WASM file with the optimization level.
+///
+/// The WASM file is copied to the output without optimizations at `O0`.
+pub fn wasm_opt(
+    input: impl AsRef<Path>,
+    output: impl AsRef<Path>,
+    level: OptLevel,
+) -> anyhow::Result<()> {
+    let mut options = match level {
+        OptLevel::O0 => {
+            if input.as_ref() != output.as_ref() {
+                fs::copy(&input, &output)?;
+            }
+
+            return Ok(());
+        }
+        OptLevel::O1 => ::wasm_opt::OptimizationOptions::new_opt_level_1(),
+        OptLevel::O2 => ::wasm_opt::OptimizationOptions::new_opt_level_2(),
+        OptLevel::Os => {
+            let mut options = ::wasm_opt::OptimizationOptions::new_opt_level_4();
+            options.shrink_level(::wasm_opt::ShrinkLevel::Level2);
+            options
+        }
+    };
+
+    options
         .debug_info(false)
         .mvp_features_only()
         .set_converge()
@@ -22,6 +22,9 @@ pub struct Abi {
     pub inputs: Vec<Arg>,
     /// An array of arguments, similar to inputs.
     pub outputs: Vec<Arg>,
+    /// State mutability of the function.
+    #[cfg_attr(feature = "serde", serde(rename = "stateMutability"))]
+    pub state_mutability: StateMutability,
 }
 
 #[cfg(feature = "syn")]
@@ -58,6 +61,7 @@ impl From<&syn::Signature> for Abi {
             name,
             inputs,
             outputs,
+            state_mutability: StateMutability::default(),
         }
     }
 }
@@ -72,12 +76,18 @@ pub enum Type {
     /// Function ABI.
     #[default]
     Function,
+    /// Fallback function ABI, called if no selector matches.
+    Fallback,
+    /// Receive function ABI, called with empty calldata.
+    Receive,
 }
 
 impl From<&str> for Type {
     fn from(s: &str) -> Self {
         match s {
             "constructor" => Type::Constructor,
+            "fallback" => Type::Fallback,
+            "receive" => Type::Receive,
             _ => Type::Function,
         }
     }
@@ -96,6 +106,8 @@ impl AsRef<str> for Type {
         match self {
             Type::Constructor => "constructor",
             Type::Function => "function",
+            Type::Fallback => "fallback",
+            Type::Receive => "receive",
         }
     }
 }
@@ -106,3 +118,58 @@ impl fmt::Display for Type {
         write!(f, "{ty}")
     }
 }
+
+/// State mutability of the function.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum StateMutability {
+    /// Does not read the state.
+    Pure,
+    /// Does not modify the state.
+    View,
+    /// Rejects ether.
+    #[default]
+    NonPayable,
+    /// Accepts ether.
+    Payable,
+}
+
+impl StateMutability {
+    /// If the function accepts ether.
+    pub fn is_payable(&self) -> bool {
+        *self == Self::Payable
+    }
+}
+
+impl FromStr for StateMutability {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "pure" => Self::Pure,
+            "view" => Self::View,
+            "nonpayable" => Self::NonPayable,
+            "payable" => Self::Payable,
+            _ => return Err(s.to_string()),
+        })
+    }
+}
+
+impl AsRef<str> for StateMutability {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Pure => "pure",
+            Self::View => "view",
+            Self::NonPayable => "nonpayable",
+            Self::Payable => "payable",
+        }
+    }
+}
+
+impl fmt::Display for StateMutability {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mutability: &str = self.as_ref();
+        write!(f, "{mutability}")
+    }
+}
@@ -18,6 +18,6 @@ pub(crate) mod std {
 }
 
 pub use self::{
-    abi::Abi,
+    abi::{Abi, StateMutability, Type},
     arg::{Arg, Param},
 };
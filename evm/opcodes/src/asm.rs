@@ -0,0 +1,285 @@
+//! Textual assembler.
+//!
+//! The source is a sequence of instructions separated by whitespace,
+//! and comments run from `;` to the end of the line:
+//!
+//! ```text
+//! PUSH1 0x2a      ; the immediate of `PUSHn` follows the mnemonic
+//! PUSH0
+//! CALLDATALOAD
+//! JUMPI @skip     ; push the address of `skip` and jump to it
+//! POP
+//! PUSH1 7
+//! skip:           ; the label is placed as a `JUMPDEST`
+//! PUSH0
+//! SSTORE
+//! ```
+//!
+//! The reference `@label` pushes the address of the label with
+//! `PUSH2`, and `JUMP @label` or `JUMPI @label` is the shorthand
+//! of the reference followed by the jump.
+
+use crate::{Cancun, Group, OpCode};
+use core::{fmt, str::FromStr};
+use std::collections::BTreeMap;
+
+/// Item of the assembly.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Item {
+    /// Instruction with the immediate of the `PUSHn` instructions.
+    Op {
+        /// The opcode.
+        opcode: Cancun,
+        /// Immediate padded to the size of `PUSHn`.
+        immediate: Vec<u8>,
+    },
+    /// Label placed as a `JUMPDEST`.
+    Label(String),
+    /// Push of the address of the label.
+    Ref(String),
+}
+
+/// Error of the assembler.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AsmError {
+    /// Line of the source, starts from 1, or 0 if the error is not
+    /// of a single line.
+    pub line: usize,
+    /// Kind of the error.
+    pub kind: AsmErrorKind,
+}
+
+/// Kind of the errors of the assembler.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AsmErrorKind {
+    /// The mnemonic is not a defined opcode.
+    UnknownOpcode(String),
+    /// The `PUSHn` instruction has no immediate.
+    MissingImmediate(Cancun),
+    /// The immediate is not a number.
+    InvalidImmediate(String),
+    /// The immediate doesn't fit in the `PUSHn` instruction.
+    ImmediateOverflow(Cancun, String),
+    /// The label has no name or is defined twice.
+    InvalidLabel(String),
+    /// The referenced label is not defined.
+    UndefinedLabel(String),
+    /// The address of the label doesn't fit in `PUSH2`.
+    AddressOverflow(String),
+}
+
+impl fmt::Display for AsmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+        match &self.kind {
+            AsmErrorKind::UnknownOpcode(mnemonic) => write!(f, "unknown opcode {mnemonic}"),
+            AsmErrorKind::MissingImmediate(opcode) => {
+                write!(f, "missing immediate of {opcode:?}")
+            }
+            AsmErrorKind::InvalidImmediate(immediate) => {
+                write!(f, "invalid immediate {immediate}")
+            }
+            AsmErrorKind::ImmediateOverflow(opcode, immediate) => {
+                write!(f, "immediate {immediate} overflows {opcode:?}")
+            }
+            AsmErrorKind::InvalidLabel(label) => write!(f, "invalid label {label:?}"),
+            AsmErrorKind::UndefinedLabel(label) => write!(f, "undefined label {label}"),
+            AsmErrorKind::AddressOverflow(label) => {
+                write!(f, "address of label {label} overflows PUSH2")
+            }
+        }
+    }
+}
+
+impl std::error::Error for AsmError {}
+
+/// Parse the source into items, the references are checked against
+/// the labels.
+pub fn parse(source: &str) -> Result<Vec<Item>, AsmError> {
+    let mut items = Vec::new();
+    let mut labels = BTreeMap::new();
+    let mut refs = Vec::new();
+    for (index, text) in source.lines().enumerate() {
+        let line = index + 1;
+        let error = |kind| AsmError { line, kind };
+        let text = text.split(';').next().unwrap_or_default();
+
+        let mut tokens = text.split_whitespace().peekable();
+        while let Some(token) = tokens.next() {
+            if let Some(label) = token.strip_suffix(':') {
+                if !is_label(label) || labels.insert(label.to_string(), line).is_some() {
+                    return Err(error(AsmErrorKind::InvalidLabel(label.into())));
+                }
+
+                items.push(Item::Label(label.into()));
+                continue;
+            }
+
+            if let Some(label) = token.strip_prefix('@') {
+                refs.push((line, label.to_string()));
+                items.push(Item::Ref(label.into()));
+                continue;
+            }
+
+            let opcode = Cancun::from_str(&token.to_lowercase())
+                .map_err(|_| error(AsmErrorKind::UnknownOpcode(token.into())))?;
+
+            let mut immediate = Vec::new();
+            if opcode.group() == Group::Push && opcode != Cancun::PUSH0 {
+                let size = (u8::from(opcode) - u8::from(Cancun::PUSH0)) as usize;
+                let value = tokens
+                    .next()
+                    .ok_or_else(|| error(AsmErrorKind::MissingImmediate(opcode)))?;
+                let bytes = parse_immediate(value)
+                    .ok_or_else(|| error(AsmErrorKind::InvalidImmediate(value.into())))?;
+                if bytes.len() > size {
+                    return Err(error(AsmErrorKind::ImmediateOverflow(opcode, value.into())));
+                }
+
+                immediate.resize(size - bytes.len(), 0);
+                immediate.extend_from_slice(&bytes);
+            } else if matches!(opcode, Cancun::JUMP | Cancun::JUMPI) {
+                if let Some(label) = tokens.next_if(|token| token.starts_with('@')) {
+                    refs.push((line, label[1..].to_string()));
+                    items.push(Item::Ref(label[1..].into()));
+                }
+            }
+
+            items.push(Item::Op { opcode, immediate });
+        }
+    }
+
+    for (line, label) in refs {
+        if !labels.contains_key(&label) {
+            return Err(AsmError {
+                line,
+                kind: AsmErrorKind::UndefinedLabel(label),
+            });
+        }
+    }
+
+    Ok(items)
+}
+
+/// Assemble the source into bytecode.
+pub fn assemble(source: &str) -> Result<Vec<u8>, AsmError> {
+    let items = parse(source)?;
+
+    let mut labels = BTreeMap::new();
+    let mut pc = 0;
+    for item in items.iter() {
+        match item {
+            Item::Op { immediate, .. } => pc += 1 + immediate.len(),
+            Item::Label(label) => {
+                labels.insert(label.as_str(), pc);
+                pc += 1;
+            }
+            Item::Ref(_) => pc += 3,
+        }
+    }
+
+    let mut bytecode = Vec::with_capacity(pc);
+    for item in items.iter() {
+        match item {
+            Item::Op { opcode, immediate } => {
+                bytecode.push((*opcode).into());
+                bytecode.extend_from_slice(immediate);
+            }
+            Item::Label(_) => bytecode.push(Cancun::JUMPDEST.into()),
+            Item::Ref(label) => {
+                let address = u16::try_from(labels[label.as_str()]).map_err(|_| AsmError {
+                    line: 0,
+                    kind: AsmErrorKind::AddressOverflow(label.clone()),
+                })?;
+
+                bytecode.push(Cancun::PUSH2.into());
+                bytecode.extend_from_slice(&address.to_be_bytes());
+            }
+        }
+    }
+
+    Ok(bytecode)
+}
+
+/// If the name of the label is an identifier.
+fn is_label(label: &str) -> bool {
+    label.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Parse the hexadecimal or decimal immediate into the big-endian
+/// bytes without leading zeros.
+fn parse_immediate(value: &str) -> Option<Vec<u8>> {
+    let mut bytes = if let Some(hex) = value.strip_prefix("0x") {
+        if hex.is_empty() || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+
+        let hex = if hex.len() % 2 == 1 {
+            format!("0{hex}")
+        } else {
+            hex.into()
+        };
+
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+            .collect::<Option<Vec<_>>>()?
+    } else {
+        value.parse::<u128>().ok()?.to_be_bytes().to_vec()
+    };
+
+    let zeros = bytes.iter().take_while(|byte| **byte == 0).count();
+    bytes.drain(..zeros);
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_assemble() {
+        let bytecode = assemble(
+            "
+            PUSH1 0x2a      ; value
+            PUSH0 CALLDATALOAD
+            JUMPI @skip
+            POP
+            PUSH2 7
+            skip:
+            PUSH0
+            SSTORE
+            ",
+        )
+        .unwrap();
+
+        assert_eq!(
+            bytecode,
+            [
+                0x60, 0x2a, 0x5f, 0x35, 0x61, 0x00, 0x0c, 0x57, 0x50, 0x61, 0x00, 0x07, 0x5b, 0x5f,
+                0x55
+            ]
+        );
+    }
+
+    #[test]
+    fn test_assemble_errors() {
+        let kind = |source| assemble(source).unwrap_err().kind;
+
+        assert_eq!(kind("PUSH3"), AsmErrorKind::MissingImmediate(Cancun::PUSH3));
+        assert_eq!(kind("FOO"), AsmErrorKind::UnknownOpcode("FOO".into()));
+        assert_eq!(
+            kind("PUSH1 0x0100"),
+            AsmErrorKind::ImmediateOverflow(Cancun::PUSH1, "0x0100".into())
+        );
+        assert_eq!(kind("a: a:"), AsmErrorKind::InvalidLabel("a".into()));
+        assert_eq!(
+            assemble("STOP\nJUMP @end"),
+            Err(AsmError {
+                line: 2,
+                kind: AsmErrorKind::UndefinedLabel("end".into())
+            })
+        );
+    }
+}
@@ -0,0 +1,94 @@
+//! Bytecode disassembler.
+
+use crate::{Cancun, Group, OpCode};
+use core::fmt;
+
+/// Instruction of the bytecode.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Instruction {
+    /// Program counter of the instruction.
+    pub pc: usize,
+    /// Byte of the opcode.
+    pub byte: u8,
+    /// The opcode, `None` if the byte is not a defined opcode.
+    pub opcode: Option<Cancun>,
+    /// Immediate of the `PUSHn` instructions, which is shorter than
+    /// `n` if the bytecode ends inside of it.
+    pub immediate: Vec<u8>,
+}
+
+impl Instruction {
+    /// Program counter of the next instruction.
+    pub fn next_pc(&self) -> usize {
+        self.pc + 1 + self.immediate.len()
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some(opcode) = self.opcode else {
+            return write!(f, "INVALID(0x{:02x})", self.byte);
+        };
+
+        write!(f, "{opcode:?}")?;
+        if !self.immediate.is_empty() {
+            write!(f, " 0x")?;
+            for byte in self.immediate.iter() {
+                write!(f, "{byte:02x}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Disassemble the bytecode into instructions.
+pub fn disassemble(bytecode: &[u8]) -> Vec<Instruction> {
+    let mut instrs = Vec::new();
+    let mut pc = 0;
+    while pc < bytecode.len() {
+        let byte = bytecode[pc];
+        let opcode = Cancun::from_byte(byte);
+        let len = match opcode {
+            Some(opcode) if opcode.group() == Group::Push => {
+                (byte - u8::from(Cancun::PUSH0)) as usize
+            }
+            _ => 0,
+        };
+
+        let end = (pc + 1 + len).min(bytecode.len());
+        instrs.push(Instruction {
+            pc,
+            byte,
+            opcode,
+            immediate: bytecode[pc + 1..end].to_vec(),
+        });
+        pc += 1 + len;
+    }
+
+    instrs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disassemble() {
+        // PUSH1 0x2a PUSH0 MSTORE 0x0c PUSH2 0x01
+        let instrs = disassemble(&[0x60, 0x2a, 0x5f, 0x52, 0x0c, 0x61, 0x01]);
+        assert_eq!(
+            instrs
+                .iter()
+                .map(|instr| (instr.pc, instr.to_string()))
+                .collect::<Vec<_>>(),
+            [
+                (0, "PUSH1 0x2a".into()),
+                (2, "PUSH0".into()),
+                (3, "MSTORE".into()),
+                (4, "INVALID(0x0c)".into()),
+                (5, "PUSH2 0x01".into()),
+            ]
+        );
+    }
+}
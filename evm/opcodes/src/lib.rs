@@ -1,10 +1,13 @@
 //! Ethereum virtual machine opcode
 #![deny(missing_docs)]
 
+pub mod asm;
 mod cancun;
+mod disasm;
 mod shanghai;
 
 pub use cancun::Cancun;
+pub use disasm::{disassemble, Instruction};
 pub use shanghai::ShangHai;
 
 /// Ethereum virtual machine opcode generator.
@@ -39,17 +42,25 @@ macro_rules! opcodes {
             )*
         }
 
-        impl From<u8> for $version {
-            fn from(value: u8) -> Self {
+        impl $version {
+            /// Get the opcode of the byte, returns `None` if the byte
+            /// is not a defined opcode.
+            pub fn from_byte(value: u8) -> Option<Self> {
                 match value {
                     $(
-                        $opcode => Self::$name,
+                        $opcode => Some(Self::$name),
                     )*
-                    _ => unreachable!("Invalid opcode."),
+                    _ => None,
                 }
             }
         }
 
+        impl From<u8> for $version {
+            fn from(value: u8) -> Self {
+                Self::from_byte(value).unwrap_or_else(|| unreachable!("Invalid opcode."))
+            }
+        }
+
         impl From<$version> for u8 {
             fn from(version: $version) -> Self {
                 match version {
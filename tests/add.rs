@@ -34,7 +34,23 @@ fn tee(module: &str) -> Result<()> {
     Ok(())
 }
 
+fn overflow(module: &str) -> Result<()> {
+    let mut contract = Contract::from(Test::load(module, "overflow")?.wasm)
+        .pure()
+        .compile()?;
+    let max = if module.starts_with("i32") {
+        u32::MAX as u64
+    } else {
+        u64::MAX
+    };
+
+    // max + 1 wraps to 0
+    let info = contract.execute([max])?;
+    assert_eq!(info.ret, 0.to_bytes32());
+    Ok(())
+}
+
 impl_tests! {
-    tests: [params, locals, tee],
+    tests: [params, locals, tee, overflow],
     modules: ["i32add", "i64add"]
 }
@@ -0,0 +1,194 @@
+//! artifact tests
+#![cfg(test)]
+
+use anyhow::Result;
+use filetests::Test;
+use std::collections::BTreeSet;
+use zinkc::{Compiler, Config, InitStorage, JsonArtifact, OptLevel};
+use zint::{keccak256, Bytes32, Contract, FoundryOutput, EVM};
+
+#[test]
+fn json() -> Result<()> {
+    let contract = Contract::from(Test::CONSTRUCTOR_ARGS).compile()?;
+    let json = contract.json_artifact()?;
+
+    // read back as the outputs of foundry
+    let output: FoundryOutput = serde_json::from_str(&json)?;
+    assert_eq!(
+        hex::decode(output.bytecode().trim_start_matches("0x"))?,
+        contract.bytecode()?
+    );
+
+    let artifact: JsonArtifact = serde_json::from_str(&json)?;
+    assert_eq!(
+        artifact.deployed_bytecode.object,
+        format!("0x{}", hex::encode(&contract.artifact.runtime_bytecode))
+    );
+    assert_eq!(
+        artifact.method_identifiers.into_iter().collect::<Vec<_>>(),
+        [(
+            "get(uint32)".to_string(),
+            hex::encode(&keccak256(b"get(uint32)")[..4])
+        )]
+    );
+    assert_eq!(artifact.abi.len(), 2);
+    assert!(artifact.storage_layout.storage.is_empty());
+    assert_eq!(
+        artifact.deployed_bytecode.source_map,
+        contract.artifact.srcmap
+    );
+    Ok(())
+}
+
+#[test]
+fn storage_layout() -> Result<()> {
+    let contract = Contract::from(Test::STORAGE_LAYOUT).pure().compile()?;
+    let layout = serde_json::from_str::<JsonArtifact>(&contract.json_artifact()?)?.storage_layout;
+
+    // the items are sorted by their slots
+    assert_eq!(
+        layout
+            .storage
+            .iter()
+            .map(|slot| (slot.label.as_str(), slot.slot.as_str(), slot.ty.as_str()))
+            .collect::<Vec<_>>(),
+        [
+            ("Counter", "0", "t_i32"),
+            ("Balances", "1", "t_mapping(t_Address,t_U256)")
+        ]
+    );
+
+    let mapping = &layout.types["t_mapping(t_Address,t_U256)"];
+    assert_eq!(mapping.encoding, "mapping");
+    assert_eq!(mapping.label, "mapping(Address => U256)");
+    assert_eq!(mapping.key.as_deref(), Some("t_Address"));
+    assert_eq!(mapping.value.as_deref(), Some("t_U256"));
+    assert_eq!(layout.types["t_i32"].encoding, "inplace");
+    Ok(())
+}
+
+#[test]
+fn creation() -> Result<()> {
+    let mut contract = Contract::from(Test::CONSTRUCTOR_ARGS).compile()?;
+    let runtime_bytecode = contract.artifact.runtime_bytecode.clone();
+
+    // preset the storage in the creation bytecode
+    let storage: InitStorage = [(vec![2], vec![9]), (vec![3], vec![0]), (vec![4], vec![1])]
+        .into_iter()
+        .map(|(key, value)| (key.into(), value.into()))
+        .collect();
+    let artifact = Compiler::new(Config::default().dispatcher(true))
+        .storage(storage.clone())
+        .compile(&contract.wasm)?;
+    assert_eq!(artifact.runtime_bytecode, runtime_bytecode);
+
+    // the constructor and its callee are compiled into the init code
+    assert!(artifact.eliminated.is_empty());
+
+    // the presets are emitted in the order of the keys
+    contract.construct(storage)?;
+    assert_eq!(contract.bytecode()?, artifact.creation_bytecode);
+
+    let mut bytecode = artifact.creation_bytecode;
+    bytecode.extend_from_slice(&[42.to_bytes32(), 7.to_bytes32()].concat());
+
+    let mut evm = EVM::default();
+    let address = evm.deploy(&bytecode)?.address;
+    for (key, value) in [(0, 42), (1, 7), (2, 9)] {
+        let info = evm
+            .calldata(&contract.encode([b"get(uint32)".to_vec(), vec![key]])?)
+            .call(address)?;
+        assert_eq!(info.ret, value.to_bytes32());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn srcmap() -> Result<()> {
+    let contract = Contract::from(Test::SRCMAP_DWARF).pure().compile()?;
+    let artifact = &contract.artifact;
+
+    // the operators are located with the lines of DWARF
+    let lines = artifact
+        .source_map
+        .iter()
+        .map(|location| {
+            assert_eq!(location.func, 0);
+            assert_eq!(artifact.source(location.pc), Some(location));
+            location
+                .line
+                .as_ref()
+                .map(|line| (line.file.as_str(), line.line))
+        })
+        .collect::<BTreeSet<_>>();
+    assert_eq!(
+        lines,
+        [Some(("src/lib.rs", 2)), Some(("src/lib.rs", 3))].into()
+    );
+
+    // the srcmap has an entry for each instruction
+    assert_eq!(
+        artifact.srcmap.split(';').count(),
+        opcodes::disassemble(&artifact.runtime_bytecode).len()
+    );
+
+    // the lines are missing without DWARF
+    let contract = Contract::from(Test::CONSTRUCTOR_ARGS).compile()?;
+    assert!(!contract.artifact.source_map.is_empty());
+    assert!(contract
+        .artifact
+        .source_map
+        .iter()
+        .all(|location| location.line.is_none()));
+    Ok(())
+}
+
+#[test]
+fn revert_source() -> Result<()> {
+    let mut contract = Contract::from(Test::SRCMAP_REVERT)
+        .pure()
+        .opt_level(OptLevel::Os)
+        .compile()?;
+    let info = contract.execute([0])?;
+    assert_eq!(info.revert, None);
+    assert_eq!(info.revert_source, None);
+
+    // the revert is traced back to the call of `revert1`
+    let info = contract.execute([1])?;
+    assert_eq!(info.revert.as_deref(), Some("fail"));
+
+    let pc = info.revert_pc.expect("revert is traced");
+    assert_eq!(contract.artifact.runtime_bytecode[pc], 0xfd);
+
+    let source = info.revert_source.expect("revert has the source");
+    assert_eq!(contract.wasm[source.offset], 0x10);
+
+    // the operators are recorded the same without the backend passes
+    let artifact =
+        Compiler::new(Config::default().opt_level(OptLevel::O0)).compile(&Test::SRCMAP_REVERT)?;
+    let info = EVM::interp(&artifact.runtime_bytecode, &1.to_bytes32())?;
+    let source = artifact
+        .source(info.revert_pc.expect("revert is traced") as u16)
+        .expect("revert has the source");
+    assert_eq!(contract.wasm[source.offset], 0x10);
+    Ok(())
+}
+
+#[test]
+fn listing() -> Result<()> {
+    let contract = Contract::from(Test::CONSTRUCTOR_ARGS).compile()?;
+    let listing = contract.artifact.listing(&contract.wasm);
+
+    let selector = hex::encode(&keccak256(b"get(uint32)")[..4]);
+    assert!(listing.starts_with("; entry\n0000: CALLVALUE\n"));
+    assert!(listing.contains(&format!("PUSH4 0x{selector}          ; get(uint32)\n")));
+    assert!(listing.contains(&format!("\n; function 5: get(uint32) (0x{selector})\n")));
+    assert!(listing.contains("; LocalGet { local_index: 0 } at 0x"));
+
+    // the lines of DWARF are annotated as well
+    let contract = Contract::from(Test::SRCMAP_DWARF).pure().compile()?;
+    let listing = contract.artifact.listing(&contract.wasm);
+    assert!(listing.contains("; I32Add at 0x1e (src/lib.rs:3)\n"));
+    Ok(())
+}
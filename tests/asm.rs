@@ -0,0 +1,17 @@
+//! Tests for inline assembly.
+
+use anyhow::Result;
+use filetests::Test;
+use zint::{Bytes32, Contract};
+
+#[test]
+fn store() -> Result<()> {
+    let mut contract = Contract::from(Test::ASM_STORE).pure().compile()?;
+
+    let info = contract.execute([1])?;
+    assert_eq!(info.ret, 42.to_bytes32());
+
+    let info = contract.execute([0])?;
+    assert_eq!(info.ret, 7.to_bytes32());
+    Ok(())
+}
@@ -7,11 +7,11 @@ use zint::{Contract, HaltReason};
 fn as_block_last() -> Result<()> {
     let mut contract = Contract::from(Test::BR_IF_AS_BLOCK_LAST).pure().compile()?;
 
-    let info = contract.execute(&[0])?;
+    let info = contract.execute([0])?;
     assert!(info.halt.is_none());
     assert!(info.ret.is_empty());
 
-    let info = contract.execute(&[42])?;
+    let info = contract.execute([42])?;
     assert!(matches!(info.halt, Some(HaltReason::OutOfGas(_))));
     assert!(info.ret.is_empty());
 
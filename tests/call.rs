@@ -3,7 +3,7 @@
 
 use anyhow::Result;
 use filetests::Test;
-use zint::{Bytes32, Contract};
+use zint::{Bytes32, Contract, HaltReason};
 
 #[test]
 fn dummy() -> Result<()> {
@@ -40,3 +40,22 @@ fn as_if() -> Result<()> {
     assert_eq!(info.ret, 42.to_bytes32());
     Ok(())
 }
+
+#[test]
+fn indirect() -> Result<()> {
+    let mut contract = Contract::from(Test::CALL_INDIRECT).pure().compile()?;
+
+    let info = contract.execute([0, 7, 3])?;
+    assert_eq!(info.ret, 10.to_bytes32());
+
+    let info = contract.execute([1, 7, 3])?;
+    assert_eq!(info.ret, 4.to_bytes32());
+
+    // signature mismatch, uninitialized element and out of bounds.
+    for elem in [2, 3, 4, 0x100] {
+        let info = contract.execute([elem, 7, 3])?;
+        assert_eq!(info.halt, Some(HaltReason::InvalidFEOpcode));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,54 @@
+//! constructor tests
+#![cfg(test)]
+
+use anyhow::Result;
+use filetests::Test;
+use zint::{Bytes32, Contract, EVM, U256};
+
+#[test]
+fn args() -> Result<()> {
+    let mut contract = Contract::from(Test::CONSTRUCTOR_ARGS).compile()?;
+    let mut evm = contract.deploy_with([42, 7])?;
+
+    for (key, value) in [(0, 42), (1, 7)] {
+        let info = evm
+            .calldata(&contract.encode([b"get(uint32)".to_vec(), vec![key]])?)
+            .call(contract.address)?;
+        assert_eq!(info.ret, value.to_bytes32());
+    }
+
+    // the constructor is not dispatched in the runtime bytecode
+    let info = evm
+        .calldata(&contract.encode([b"constructor(uint256,uint256)".to_vec()])?)
+        .call(contract.address)?;
+    assert!(info.revert.is_some());
+    assert_eq!(contract.artifact.abi[0].ty.as_ref(), "constructor");
+    Ok(())
+}
+
+#[test]
+fn data() -> Result<()> {
+    let mut contract = Contract::from(Test::CONSTRUCTOR_DATA).compile()?;
+
+    for (index, value) in [(0, 2), (3, 7)] {
+        let mut evm = contract.deploy_with([index, 1])?;
+        let info = evm
+            .calldata(&contract.encode([b"get(uint32)".to_vec(), vec![1]])?)
+            .call(contract.address)?;
+        assert_eq!(info.ret, value.to_bytes32());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn non_payable() -> Result<()> {
+    let contract = Contract::from(Test::CONSTRUCTOR_ARGS).compile()?;
+    let mut bytecode = contract.bytecode()?;
+    bytecode.extend_from_slice(&[0; 64]);
+
+    let mut evm = EVM::default();
+    evm.value(U256::from(1));
+    assert!(evm.deploy(&bytecode)?.revert.is_some());
+    Ok(())
+}
@@ -0,0 +1,211 @@
+//! dispatcher tests for the zink compiler.
+use anyhow::Result;
+use filetests::Test;
+use zinkc::{Compiler, Config, OptLevel};
+use zint::{Bytes32, Contract, CONTRACT, EVM, U256};
+
+#[test]
+fn binary_search() -> Result<()> {
+    let mut contract = Contract::from(Test::DISPATCHER_BINARY_SEARCH).compile()?;
+
+    // the selectors are not sorted by the function indexes
+    for index in 0..9 {
+        let info = contract.execute([format!("f{index}()").into_bytes()])?;
+        assert_eq!(info.halt, None);
+        assert_eq!(info.ret, index.to_bytes32());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn fallback() -> Result<()> {
+    let mut contract = Contract::from(Test::DISPATCHER_FALLBACK).compile()?;
+
+    let info = contract.execute([b"f0()".to_vec()])?;
+    assert_eq!(info.ret, 7.to_bytes32());
+    assert!(info.storage.is_empty());
+
+    // no selector matches
+    let info = contract.execute([b"f2()".to_vec()])?;
+    assert_eq!(info.revert, None);
+    assert_eq!(info.storage.get(&U256::ZERO), Some(&U256::from(1)));
+
+    // empty calldata
+    let info = EVM::interp(&contract.artifact.runtime_bytecode, &[])?;
+    assert_eq!(info.revert, None);
+    assert_eq!(info.storage.get(&U256::ZERO), Some(&U256::from(2)));
+
+    let abi = &contract.artifact.abi;
+    assert_eq!(abi[2].ty.as_ref(), "fallback");
+    assert_eq!(abi[3].ty.as_ref(), "receive");
+    Ok(())
+}
+
+#[test]
+fn payable() -> Result<()> {
+    let contract = Contract::from(Test::DISPATCHER_FALLBACK).compile()?;
+    let call = |calldata: &[u8]| {
+        EVM::default()
+            .contract(&contract.artifact.runtime_bytecode)
+            .calldata(calldata)
+            .value(U256::from(1))
+            .call(CONTRACT)
+    };
+
+    // `f0` and `fallback` are non-payable
+    assert!(call(&contract.encode([b"f0()".to_vec()])?)?
+        .revert
+        .is_some());
+    assert!(call(&contract.encode([b"f2()".to_vec()])?)?
+        .revert
+        .is_some());
+
+    // `f1` and `receive` are payable
+    assert_eq!(
+        call(&contract.encode([b"f1()".to_vec()])?)?.ret,
+        8.to_bytes32()
+    );
+    let info = call(&[])?;
+    assert_eq!(info.revert, None);
+    assert_eq!(info.storage.get(&U256::ZERO), Some(&U256::from(2)));
+
+    let abi = &contract.artifact.abi;
+    assert_eq!(abi[0].state_mutability.as_ref(), "nonpayable");
+    assert_eq!(abi[1].state_mutability.as_ref(), "payable");
+    Ok(())
+}
+
+#[test]
+fn no_fallback() -> Result<()> {
+    let mut contract = Contract::from(Test::DISPATCHER_NO_FALLBACK).compile()?;
+
+    let info = contract.execute([b"f0()".to_vec()])?;
+    assert_eq!(info.ret, 7.to_bytes32());
+
+    let info = contract.execute([b"f1()".to_vec()])?;
+    assert!(info.revert.is_some());
+
+    let info = EVM::interp(&contract.artifact.runtime_bytecode, &[])?;
+    assert!(info.revert.is_some());
+
+    // none of the entries is payable
+    let info = EVM::default()
+        .contract(&contract.artifact.runtime_bytecode)
+        .calldata(&contract.encode([b"f0()".to_vec()])?)
+        .value(U256::from(1))
+        .call(CONTRACT)?;
+    assert!(info.revert.is_some());
+    Ok(())
+}
+
+#[test]
+fn calldata() -> Result<()> {
+    let mut contract = Contract::from(Test::DISPATCHER_CALLDATA).compile()?;
+    let mut call = |inputs: &[&[u8]]| {
+        contract.execute(
+            inputs
+                .iter()
+                .map(|input| input.to_vec())
+                .collect::<Vec<_>>(),
+        )
+    };
+
+    assert_eq!(call(&[b"u8(uint8)", &[0xff]])?.ret, 0xff.to_bytes32());
+    assert_eq!(call(&[b"i8(int8)", &[0xff; 32]])?.ret, [0xff; 32]);
+    assert_eq!(
+        call(&[b"addr(address)", &[0xff; 20]])?.ret,
+        [0xff; 20].to_bytes32()
+    );
+    assert_eq!(
+        call(&[b"pair(uint8,uint8)", &[1], &[2]])?.ret,
+        3.to_bytes32()
+    );
+    assert_eq!(call(&[b"u256(uint256)", &[0xff; 32]])?.ret, [0xff; 32]);
+
+    // the negative integers are sign-extended in both ways
+    let (a, b) = ((-7i32).to_bytes32(), 2i32.to_bytes32());
+    assert_eq!(call(&[b"i32(int32)", &a])?.ret, a);
+    assert_eq!(
+        call(&[b"div(int32,int32)", &a, &b])?.ret,
+        (-3i32).to_bytes32()
+    );
+    assert_eq!(call(&[b"div(int32,int32)", &b, &a])?.ret, 0.to_bytes32());
+    assert_eq!(call(&[b"lt(int32,int32)", &a, &b])?.ret, true.to_bytes32());
+    assert_eq!(call(&[b"lt(int32,int32)", &b, &a])?.ret, false.to_bytes32());
+
+    // the calldata is shorter than the parameters
+    assert!(call(&[b"u8(uint8)"])?.revert.is_some());
+    assert!(call(&[b"pair(uint8,uint8)", &[1]])?.revert.is_some());
+
+    // the high bits are dirty
+    assert!(call(&[b"u8(uint8)", &[1, 0]])?.revert.is_some());
+    assert!(call(&[b"i8(int8)", &[0xff]])?.revert.is_some());
+    assert!(call(&[b"addr(address)", &[0xff; 21]])?.revert.is_some());
+    assert!(call(&[b"pair(uint8,uint8)", &[1], &[1, 0]])?
+        .revert
+        .is_some());
+    Ok(())
+}
+
+#[test]
+fn unchecked_calldata() -> Result<()> {
+    let mut contract = Contract::from(Test::DISPATCHER_CALLDATA)
+        .unchecked_calldata()
+        .compile()?;
+
+    let info = contract.execute([b"u8(uint8)".to_vec()])?;
+    assert_eq!(info.revert, None);
+
+    // the dirty bits are masked out
+    let info = contract.execute([b"u8(uint8)".to_vec(), vec![1, 0]])?;
+    assert_eq!(info.ret, 0.to_bytes32());
+
+    let mut call = |inputs: &[&[u8]]| {
+        contract.execute(
+            inputs
+                .iter()
+                .map(|input| input.to_vec())
+                .collect::<Vec<_>>(),
+        )
+    };
+    assert_eq!(call(&[b"i8(int8)", &[0xff]])?.ret, [0xff; 32]);
+    assert_eq!(
+        call(&[b"addr(address)", &[0xff; 21]])?.ret,
+        [0xff; 20].to_bytes32()
+    );
+    assert_eq!(
+        call(&[b"pair(uint8,uint8)", &[1], &[1, 2]])?.ret,
+        3.to_bytes32()
+    );
+
+    let (a, b) = ([1, 0xff, 0xff, 0xff, 0xf9], [0xff, 0, 0, 0, 2]);
+    assert_eq!(call(&[b"i32(int32)", &a])?.ret, (-7i32).to_bytes32());
+    assert_eq!(
+        call(&[b"div(int32,int32)", &a, &b])?.ret,
+        (-3i32).to_bytes32()
+    );
+    assert_eq!(call(&[b"lt(int32,int32)", &a, &b])?.ret, true.to_bytes32());
+    Ok(())
+}
+
+#[test]
+fn size_level() -> Result<()> {
+    let compile = |wasm: &[u8], opt_level| {
+        Compiler::new(Config::default().dispatcher(true).opt_level(opt_level)).compile(wasm)
+    };
+
+    // `Os` jumps to the revert shared in the code section for the
+    // checks of the calldata instead of inlining them
+    let o2 = compile(&Test::DISPATCHER_CALLDATA, OptLevel::O2)?;
+    let os = compile(&Test::DISPATCHER_CALLDATA, OptLevel::Os)?;
+    assert!(o2.code_section.is_empty());
+    assert_eq!(os.code_section.len(), 1);
+    assert!(os.runtime_bytecode.len() < o2.runtime_bytecode.len());
+
+    // and matches more selectors one by one in the binary search
+    let o2 = compile(&Test::DISPATCHER_BINARY_SEARCH, OptLevel::O2)?;
+    let os = compile(&Test::DISPATCHER_BINARY_SEARCH, OptLevel::Os)?;
+    assert!(os.runtime_bytecode.len() < o2.runtime_bytecode.len());
+    Ok(())
+}
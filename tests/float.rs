@@ -0,0 +1,334 @@
+//! Soft-float tests for the zink compiler.
+use anyhow::Result;
+use filetests::Test;
+use zint::{Bytes32, Contract, HaltReason, U256};
+
+/// Special and random 64-bit floats.
+fn f64s() -> Vec<f64> {
+    let mut values = vec![
+        0.0,
+        -0.0,
+        1.0,
+        -1.0,
+        0.1,
+        0.5,
+        1.5,
+        2.5,
+        -2.5,
+        3.5,
+        1.0 / 3.0,
+        123456.789,
+        -9007199254740993.0,
+        1e308,
+        -1e-308,
+        f64::MAX,
+        f64::MIN_POSITIVE,
+        5e-324,
+        -2.5e-320,
+        f64::INFINITY,
+        f64::NEG_INFINITY,
+        f64::NAN,
+    ];
+
+    // xorshift
+    let mut state = 0x2545_f491_4f6c_dd1du64;
+    for _ in 0..24 {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        values.push(f64::from_bits(state));
+        values.push(f64::from_bits(state >> 12 | 0x3ff0_0000_0000_0000) * 10.0);
+    }
+
+    values
+}
+
+/// Special and random 32-bit floats.
+fn f32s() -> Vec<f32> {
+    let mut values = f64s().into_iter().map(|v| v as f32).collect::<Vec<_>>();
+    values.extend([f32::MAX, f32::MIN_POSITIVE, 1e-45, -3e-40, 16777217.0]);
+    values
+}
+
+/// Check the result with the expected float, the payloads of NaN
+/// are not compared.
+fn check(ret: &[u8], expected: f64, f32: bool, inputs: &str) {
+    let bits = U256::from_be_slice(ret).to::<u64>();
+    if expected.is_nan() {
+        let nan = if f32 {
+            f32::from_bits(bits as u32).is_nan() && bits >> 32 == 0
+        } else {
+            f64::from_bits(bits).is_nan()
+        };
+        assert!(nan, "{inputs}: expected NaN, got {bits:x}");
+    } else if f32 {
+        assert_eq!(ret, (expected as f32).to_bits().to_bytes32(), "{inputs}");
+    } else {
+        assert_eq!(ret, expected.to_bits().to_bytes32(), "{inputs}");
+    }
+}
+
+fn f64_binary(test: &[u8], op: impl Fn(f64, f64) -> f64) -> Result<()> {
+    let mut contract = Contract::from(test).pure().soft_float().compile()?;
+    let values = f64s();
+    for a in values.iter() {
+        for b in values.iter() {
+            let info = contract.execute([a.to_bits(), b.to_bits()])?;
+            let expected = op(*a, *b);
+            check(&info.ret, expected, false, &format!("{a:e}, {b:e}"));
+        }
+    }
+
+    Ok(())
+}
+
+fn f32_binary(test: &[u8], op: impl Fn(f32, f32) -> f32) -> Result<()> {
+    let mut contract = Contract::from(test).pure().soft_float().compile()?;
+    let values = f32s();
+    for a in values.iter() {
+        for b in values.iter() {
+            let info = contract.execute([a.to_bits(), b.to_bits()])?;
+            let expected = op(*a, *b);
+            check(&info.ret, expected as f64, true, &format!("{a:e}, {b:e}"));
+        }
+    }
+
+    Ok(())
+}
+
+fn f64_unary(test: &[u8], op: impl Fn(f64) -> f64) -> Result<()> {
+    let mut contract = Contract::from(test).pure().soft_float().compile()?;
+    for a in f64s() {
+        let info = contract.execute([a.to_bits()])?;
+        let expected = op(a);
+        check(&info.ret, expected, false, &format!("{a:e}"));
+    }
+
+    Ok(())
+}
+
+fn f32_unary(test: &[u8], op: impl Fn(f32) -> f32) -> Result<()> {
+    let mut contract = Contract::from(test).pure().soft_float().compile()?;
+    for a in f32s() {
+        let info = contract.execute([a.to_bits()])?;
+        let expected = op(a);
+        check(&info.ret, expected as f64, true, &format!("{a:e}"));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn arithmetic() -> Result<()> {
+    f64_binary(&Test::FLOAT_F64_ADD, |a, b| a + b)?;
+    f64_binary(&Test::FLOAT_F64_SUB, |a, b| a - b)?;
+    f64_binary(&Test::FLOAT_F64_MUL, |a, b| a * b)?;
+    f64_binary(&Test::FLOAT_F64_DIV, |a, b| a / b)?;
+    f32_binary(&Test::FLOAT_F32_ADD, |a, b| a + b)?;
+    f32_binary(&Test::FLOAT_F32_SUB, |a, b| a - b)?;
+    f32_binary(&Test::FLOAT_F32_MUL, |a, b| a * b)?;
+    f32_binary(&Test::FLOAT_F32_DIV, |a, b| a / b)
+}
+
+#[test]
+fn min_max() -> Result<()> {
+    // WASM returns NaN if any operand is NaN, and `-0 < +0`.
+    let min = |a: f64, b: f64| {
+        if a.is_nan() || b.is_nan() {
+            f64::NAN
+        } else if a == b {
+            f64::from_bits(a.to_bits() | b.to_bits())
+        } else {
+            a.min(b)
+        }
+    };
+    let max = |a: f64, b: f64| {
+        if a.is_nan() || b.is_nan() {
+            f64::NAN
+        } else if a == b {
+            f64::from_bits(a.to_bits() & b.to_bits())
+        } else {
+            a.max(b)
+        }
+    };
+
+    f64_binary(&Test::FLOAT_F64_MIN, min)?;
+    f64_binary(&Test::FLOAT_F64_MAX, max)?;
+    f64_binary(&Test::FLOAT_F64_COPYSIGN, f64::copysign)
+}
+
+#[test]
+fn rounding() -> Result<()> {
+    f64_unary(&Test::FLOAT_F64_SQRT, f64::sqrt)?;
+    f64_unary(&Test::FLOAT_F64_CEIL, f64::ceil)?;
+    f64_unary(&Test::FLOAT_F64_FLOOR, f64::floor)?;
+    f64_unary(&Test::FLOAT_F64_TRUNC, f64::trunc)?;
+    f64_unary(&Test::FLOAT_F64_NEAREST, f64::round_ties_even)?;
+    f32_unary(&Test::FLOAT_F32_SQRT, f32::sqrt)?;
+    f32_unary(&Test::FLOAT_F32_CEIL, f32::ceil)?;
+    f32_unary(&Test::FLOAT_F32_FLOOR, f32::floor)?;
+    f32_unary(&Test::FLOAT_F32_TRUNC, f32::trunc)?;
+    f32_unary(&Test::FLOAT_F32_NEAREST, f32::round_ties_even)?;
+    f64_unary(&Test::FLOAT_ABS_NEG, |a| -a.abs())
+}
+
+#[test]
+fn compare() -> Result<()> {
+    for (test, f32) in [
+        (&Test::FLOAT_F64_CMP[..], false),
+        (&Test::FLOAT_F32_CMP[..], true),
+    ] {
+        let mut contract = Contract::from(test).pure().soft_float().compile()?;
+        let values = f64s();
+        for a in values.iter() {
+            for b in values.iter() {
+                let (a, b) = if f32 {
+                    ((*a as f32) as f64, (*b as f32) as f64)
+                } else {
+                    (*a, *b)
+                };
+                let bits = |a: f64| {
+                    if f32 {
+                        (a as f32).to_bits() as u64
+                    } else {
+                        a.to_bits()
+                    }
+                };
+
+                let info = contract.execute([bits(a), bits(b)])?;
+                let expected = [a == b, a != b, a < b, a <= b, a > b, a >= b]
+                    .iter()
+                    .fold(0, |acc, bit| acc << 1 | *bit as u64);
+                assert_eq!(info.ret, expected.to_bytes32(), "{a:e}, {b:e}");
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn convert() -> Result<()> {
+    let mut contract = Contract::from(Test::FLOAT_F64_CONVERT_I64_S)
+        .pure()
+        .soft_float()
+        .compile()?;
+    for value in [0, 1, -1, i64::MIN, i64::MAX, 9007199254740993, -123456789] {
+        let info = contract.execute([value])?;
+        assert_eq!(info.ret, (value as f64).to_bits().to_bytes32(), "{value}");
+    }
+
+    let mut contract = Contract::from(Test::FLOAT_F64_CONVERT_I32_U)
+        .pure()
+        .soft_float()
+        .compile()?;
+    for value in [0, 1, u32::MAX, 0x8000_0000] {
+        let info = contract.execute([value])?;
+        assert_eq!(info.ret, (value as f64).to_bits().to_bytes32(), "{value}");
+    }
+
+    let mut contract = Contract::from(Test::FLOAT_F32_CONVERT_I32_S)
+        .pure()
+        .soft_float()
+        .compile()?;
+    for value in [0, -1, i32::MIN, i32::MAX, 16777217, -16777219] {
+        let info = contract.execute([value])?;
+        assert_eq!(info.ret, (value as f32).to_bits().to_bytes32(), "{value}");
+    }
+
+    let mut contract = Contract::from(Test::FLOAT_F32_CONVERT_I64_U)
+        .pure()
+        .soft_float()
+        .compile()?;
+    for value in [0, u64::MAX, 1 << 63, 0x0123_4567_89ab_cdef] {
+        let info = contract.execute([value])?;
+        assert_eq!(info.ret, (value as f32).to_bits().to_bytes32(), "{value}");
+    }
+
+    let mut contract = Contract::from(Test::FLOAT_F32_DEMOTE_F64)
+        .pure()
+        .soft_float()
+        .compile()?;
+    for a in f64s() {
+        let info = contract.execute([a.to_bits()])?;
+        check(&info.ret, a as f32 as f64, true, &format!("{a:e}"));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn trunc() -> Result<()> {
+    let mut contract = Contract::from(Test::FLOAT_I32_TRUNC_F64_S)
+        .pure()
+        .soft_float()
+        .compile()?;
+    for value in [0.0, -0.9, 1.9, -2147483648.9, 2147483647.9, -123.5] {
+        let info = contract.execute([f64::to_bits(value)])?;
+        assert_eq!(info.ret, (value as i32 as u32).to_bytes32(), "{value}");
+    }
+
+    for value in [2147483648.0, -2147483649.0, f64::NAN, f64::INFINITY] {
+        let info = contract.execute([f64::to_bits(value)])?;
+        assert_eq!(info.halt, Some(HaltReason::InvalidFEOpcode), "{value}");
+    }
+
+    let mut contract = Contract::from(Test::FLOAT_I64_TRUNC_F64_U)
+        .pure()
+        .soft_float()
+        .compile()?;
+    for value in [-0.9, 18446744073709549568.0, 4503599627370497.5] {
+        let info = contract.execute([f64::to_bits(value)])?;
+        assert_eq!(info.ret, (value as u64).to_bytes32(), "{value}");
+    }
+
+    for value in [-1.0, 18446744073709551616.0, 1e300] {
+        let info = contract.execute([f64::to_bits(value)])?;
+        assert_eq!(info.halt, Some(HaltReason::InvalidFEOpcode), "{value}");
+    }
+
+    let mut contract = Contract::from(Test::FLOAT_I32_TRUNC_F32_U)
+        .pure()
+        .soft_float()
+        .compile()?;
+    for value in [0.5f32, 4294967040.0, 65535.9] {
+        let info = contract.execute([value.to_bits()])?;
+        assert_eq!(info.ret, (value as u32).to_bytes32(), "{value}");
+    }
+
+    let mut contract = Contract::from(Test::FLOAT_I64_TRUNC_F32_S)
+        .pure()
+        .soft_float()
+        .compile()?;
+    for value in [-9223372036854775808.0f32, -1.5, 1e18] {
+        let info = contract.execute([value.to_bits()])?;
+        assert_eq!(info.ret, (value as i64 as u64).to_bytes32(), "{value}");
+    }
+
+    let info = contract.execute([9223372036854775808.0f32.to_bits()])?;
+    assert_eq!(info.halt, Some(HaltReason::InvalidFEOpcode));
+    Ok(())
+}
+
+#[test]
+fn promote() -> Result<()> {
+    let mut contract = Contract::from(Test::FLOAT_F64_PROMOTE_F32)
+        .pure()
+        .soft_float()
+        .compile()?;
+    for a in f32s() {
+        let info = contract.execute([a.to_bits()])?;
+        let expected = a as f64;
+        check(&info.ret, expected, false, &format!("{a:e}"));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn soft_float_disabled() {
+    assert!(Contract::from(Test::FLOAT_F64_ADD)
+        .pure()
+        .compile()
+        .is_err());
+}
@@ -0,0 +1,30 @@
+//! global tests for the zink compiler.
+use anyhow::Result;
+use filetests::Test;
+use zint::{Bytes32, Contract};
+
+#[test]
+fn counter() -> Result<()> {
+    let mut contract = Contract::from(Test::GLOBAL_COUNTER).pure().compile()?;
+
+    let info = contract.execute([0])?;
+    assert_eq!(info.ret, 42.to_bytes32());
+
+    let info = contract.execute([1])?;
+    assert_eq!(info.ret, 43.to_bytes32());
+
+    Ok(())
+}
+
+#[test]
+fn stack_pointer() -> Result<()> {
+    let mut contract = Contract::from(Test::GLOBAL_STACK_POINTER)
+        .pure()
+        .compile()?;
+
+    let info = contract.execute::<()>([])?;
+    assert_eq!(info.halt, None);
+    assert_eq!(info.ret, 16.to_bytes32());
+
+    Ok(())
+}
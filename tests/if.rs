@@ -9,12 +9,12 @@ fn if_then() -> Result<()> {
 
     // Skip the condition.
     let input = [0; 32];
-    let info = contract.execute(&[input])?;
+    let info = contract.execute([input])?;
     assert_eq!(info.ret, input);
 
     // Enter the if branch.
     let input = 1.to_bytes32();
-    let info = contract.execute(&[input])?;
+    let info = contract.execute([input])?;
     assert_eq!(info.ret, input);
 
     Ok(())
@@ -27,11 +27,11 @@ fn singular() -> Result<()> {
     // test if
     //
     // Enter if block if 1
-    let info = contract.execute(&[1])?;
+    let info = contract.execute([1])?;
     assert_eq!(info.ret, 7.to_bytes32());
 
     // test else
-    let info = contract.execute(&[0])?;
+    let info = contract.execute([0])?;
     assert_eq!(info.ret, 8.to_bytes32());
 
     Ok(())
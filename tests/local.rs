@@ -0,0 +1,38 @@
+//! Tests for the locals kept on the stack.
+use anyhow::Result;
+use filetests::Test;
+use zint::{Bytes32, Contract};
+
+#[test]
+fn stack() -> Result<()> {
+    let mut contract = Contract::from(Test::LOCAL_STACK).pure().compile()?;
+
+    // a = 3, b = 9, a = 6
+    let info = contract.execute([2])?;
+    assert_eq!(info.ret, 15.to_bytes32());
+
+    let info = contract.execute([0])?;
+    assert_eq!(info.ret, 1.to_bytes32());
+    Ok(())
+}
+
+#[test]
+fn across_regions() -> Result<()> {
+    let mut contract = Contract::from(Test::LOCAL_SQUARES).pure().compile()?;
+
+    let info = contract.execute([3])?;
+    assert_eq!(info.ret, 14.to_bytes32());
+
+    let info = contract.execute([0])?;
+    assert_eq!(info.ret, 0.to_bytes32());
+    Ok(())
+}
+
+#[test]
+fn internal_call() -> Result<()> {
+    let mut contract = Contract::from(Test::LOCAL_CALL).pure().compile()?;
+
+    let info = contract.execute([3])?;
+    assert_eq!(info.ret, 15.to_bytes32());
+    Ok(())
+}
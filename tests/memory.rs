@@ -0,0 +1,136 @@
+//! linear memory tests for the zink compiler.
+use anyhow::Result;
+use filetests::Test;
+use zint::{Bytes32, Contract};
+
+#[test]
+fn load() -> Result<()> {
+    let mut contract = Contract::from(Test::MEMORY_LOAD).pure().compile()?;
+
+    for (address, value) in [(0, 0x11223344), (1, 0x112233), (4, 0)] {
+        let info = contract.execute([address])?;
+        assert_eq!(info.ret, value.to_bytes32());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn load16() -> Result<()> {
+    let mut contract = Contract::from(Test::MEMORY_LOAD16).pure().compile()?;
+
+    for (address, value) in [(0, 0xddee), (1, 0xccdd), (6, 0x88), (7, 0)] {
+        let info = contract.execute([address])?;
+        assert_eq!(info.ret, value.to_bytes32());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn load8_s() -> Result<()> {
+    let mut contract = Contract::from(Test::MEMORY_LOAD8_S).pure().compile()?;
+
+    for (address, value) in [(0, 0x7f_u64), (1, 0xffffffff), (2, 0)] {
+        let info = contract.execute([address])?;
+        assert_eq!(info.ret, value.to_bytes32());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn sign_extend() -> Result<()> {
+    let mut contract = Contract::from(Test::MEMORY_SIGN_EXTEND).pure().compile()?;
+
+    for (address, value) in [
+        (0, 0xffffffffffffff7f_u64),
+        (1, 0x01ff),
+        (2, 0xffffffffffff8001),
+        (3, 0x80),
+    ] {
+        let info = contract.execute([address])?;
+        assert_eq!(info.ret, value.to_bytes32());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn store_narrow() -> Result<()> {
+    let mut contract = Contract::from(Test::MEMORY_STORE_NARROW).pure().compile()?;
+
+    let info = contract.execute::<()>([])?;
+    assert_eq!(info.ret, 0x1122cc44aabb7788_u64.to_bytes32());
+    Ok(())
+}
+
+#[test]
+fn grow() -> Result<()> {
+    let mut contract = Contract::from(Test::MEMORY_GROW).pure().compile()?;
+
+    for (delta, pages) in [(0, 1_u64), (2, 1), (3, 0xffffffff)] {
+        let info = contract.execute([delta])?;
+        assert_eq!(info.ret, pages.to_bytes32());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn size() -> Result<()> {
+    let mut contract = Contract::from(Test::MEMORY_SIZE).pure().compile()?;
+
+    for (delta, pages) in [(0, 2), (2, 4), (3, 2)] {
+        let info = contract.execute([delta])?;
+        assert_eq!(info.ret, pages.to_bytes32());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn copy() -> Result<()> {
+    let mut contract = Contract::from(Test::MEMORY_COPY).pure().compile()?;
+
+    for (size, value) in [
+        (0, 0x0807060504030201_u64),
+        (3, 0x0807060302010201),
+        (8, 0x0605040302010201),
+    ] {
+        let info = contract.execute([size])?;
+        assert_eq!(info.ret, value.to_bytes32());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn fill() -> Result<()> {
+    let mut contract = Contract::from(Test::MEMORY_FILL).pure().compile()?;
+
+    for (size, value) in [
+        (0, 0_u64),
+        (1, 0xab00),
+        (3, 0xababab00),
+        (6, 0xabababababab00),
+        (7, 0xababababababab00),
+    ] {
+        let info = contract.execute([size])?;
+        assert_eq!(info.ret, value.to_bytes32());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn data() -> Result<()> {
+    let mut contract = Contract::from(Test::MEMORY_DATA).pure().compile()?;
+
+    for (index, value) in [2_u64, 3, 5, 7, 11, 13, 0].into_iter().enumerate() {
+        let info = contract.execute([index])?;
+        assert_eq!(info.ret, value.to_bytes32());
+    }
+
+    Ok(())
+}
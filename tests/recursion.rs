@@ -2,7 +2,6 @@ use anyhow::Result;
 use filetests::Test;
 use zint::{Bytes32, Contract};
 
-#[ignore]
 #[test]
 fn fibonacci() -> Result<()> {
     let mut contract = Contract::from(Test::RECURSION_FIBONACCI).pure().compile()?;
@@ -34,3 +33,16 @@ fn fibonacci() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn mutual() -> Result<()> {
+    let mut contract = Contract::from(Test::RECURSION_MUTUAL).pure().compile()?;
+
+    for (x, even) in [(0, 1), (1, 0), (2, 1), (3, 0), (6, 1), (7, 0)] {
+        let info = contract.execute([x])?;
+        assert_eq!(info.halt, None);
+        assert_eq!(even.to_bytes32().to_vec(), info.ret);
+    }
+
+    Ok(())
+}
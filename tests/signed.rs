@@ -0,0 +1,82 @@
+//! Signed integer tests for the zink compiler.
+//!
+//! The inputs are sign-extended to 256 bits as what they are in the
+//! ABI encoding, while the outputs are zero-extended without the ABI.
+use anyhow::Result;
+use filetests::Test;
+use zint::{Bytes32, Contract};
+
+#[test]
+fn shr_s() -> Result<()> {
+    let mut contract = Contract::from(Test::SIGNED_SHR_S).pure().compile()?;
+
+    for (value, count, shifted) in [(i32::MIN, 4, -0x0800_0000), (0x40, 4, 4), (-1, 33, -1)] {
+        let info = contract.execute([value, count])?;
+        assert_eq!(info.ret, (shifted as u32).to_bytes32());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn div_s() -> Result<()> {
+    let mut contract = Contract::from(Test::SIGNED_DIV_S).pure().compile()?;
+
+    for (a, b, quotient) in [(-7, 2, -3), (7, -2, -3), (-8, -2, 4), (7, 2, 3)] {
+        let info = contract.execute([a, b])?;
+        assert_eq!(info.ret, (quotient as u32).to_bytes32());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn rem_s() -> Result<()> {
+    let mut contract = Contract::from(Test::SIGNED_REM_S).pure().compile()?;
+
+    for (a, b, remainder) in [(-7, 2, -1), (7, -2, 1), (-8, 3, -2), (7, 3, 1)] {
+        let info = contract.execute([a, b])?;
+        assert_eq!(info.ret, (remainder as u32).to_bytes32());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn cmp() -> Result<()> {
+    let mut contract = Contract::from(Test::SIGNED_CMP).pure().compile()?;
+
+    // bits of [lt_s, le_s, ge_s, gt_s]
+    for (a, b, bits) in [(-1, 0, 0b0011), (0, -1, 0b1100), (-2, -2, 0b0110)] {
+        let info = contract.execute([a, b])?;
+        assert_eq!(info.ret, bits.to_bytes32());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn extend8_s() -> Result<()> {
+    let mut contract = Contract::from(Test::SIGNED_EXTEND8_S).pure().compile()?;
+
+    for (value, extended) in [(0x80, -0x80), (0x7f, 0x7f), (0x1ff, -1)] {
+        let info = contract.execute([value])?;
+        assert_eq!(info.ret, (extended as u32).to_bytes32());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn i64_extend_i32_s() -> Result<()> {
+    let mut contract = Contract::from(Test::SIGNED_I64_EXTEND_I32_S)
+        .pure()
+        .compile()?;
+
+    for (value, extended) in [(-1, u64::MAX), (0x7fff_ffff, 0x7fff_ffff)] {
+        let info = contract.execute([value])?;
+        assert_eq!(info.ret, extended.to_bytes32());
+    }
+
+    Ok(())
+}
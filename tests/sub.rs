@@ -25,7 +25,23 @@ fn locals(module: &str) -> Result<()> {
     Ok(())
 }
 
+fn overflow(module: &str) -> Result<()> {
+    let mut contract = Contract::from(Test::load(module, "overflow")?.wasm)
+        .pure()
+        .compile()?;
+    let max = if module.starts_with("i32") {
+        u32::MAX as u64
+    } else {
+        u64::MAX
+    };
+
+    // 0 - 1 wraps to max
+    let info = contract.execute([0])?;
+    assert_eq!(info.ret, max.to_bytes32());
+    Ok(())
+}
+
 impl_tests! {
-    tests: [params, locals],
+    tests: [params, locals, overflow],
     modules: ["i32sub", "i64sub"]
 }
@@ -0,0 +1,50 @@
+//! Integer wrapping tests for the zink compiler.
+use anyhow::Result;
+use filetests::Test;
+use zint::{Bytes32, Contract};
+
+#[test]
+fn mul() -> Result<()> {
+    let mut contract = Contract::from(Test::WRAP_MUL).pure().compile()?;
+
+    // 0x10000 * 0x10001 = 0x1_0001_0000
+    let info = contract.execute([0x10000, 0x10001])?;
+    assert_eq!(info.ret, 0x10000.to_bytes32());
+    Ok(())
+}
+
+#[test]
+fn shl() -> Result<()> {
+    let mut contract = Contract::from(Test::WRAP_SHL).pure().compile()?;
+
+    for (value, count, shifted) in [
+        (0x8000_0000_0000_0001_u64, 1, 2_u64),
+        (1, 63, 0x8000_0000_0000_0000),
+        // the shift count is taken modulo 64
+        (3, 65, 6),
+    ] {
+        let info = contract.execute([value, count])?;
+        assert_eq!(info.ret, shifted.to_bytes32());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn i32_wrap_i64() -> Result<()> {
+    let mut contract = Contract::from(Test::WRAP_I32_WRAP_I64).pure().compile()?;
+
+    let info = contract.execute([0x1_2345_6789_u64])?;
+    assert_eq!(info.ret, 0x2345_6789.to_bytes32());
+    Ok(())
+}
+
+#[test]
+fn narrow() -> Result<()> {
+    let mut contract = Contract::from(Test::WRAP_NARROW).pure().compile()?;
+
+    // (0x44 + 0x2233) << 2
+    let info = contract.execute([0x1122_3344])?;
+    assert_eq!(info.ret, 0x89dc.to_bytes32());
+    Ok(())
+}
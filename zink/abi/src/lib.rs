@@ -11,6 +11,7 @@ pub mod selector;
 pub use encoding::{decode, encode, is_dynamic_type, AbiDecode, AbiEncode, DecodeError};
 
 pub use abi::Abi;
+pub use sol_abi::{Param, StateMutability, Type};
 
 #[cfg(feature = "selector")]
 pub use selector::keccak256;
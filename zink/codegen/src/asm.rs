@@ -0,0 +1,17 @@
+//! Inline assembly macro
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::LitStr;
+
+/// Check the assembly and splice it into the code
+pub fn parse(input: LitStr) -> TokenStream {
+    if let Err(e) = opcodes::asm::parse(&input.value()) {
+        return syn::Error::new(input.span(), e).to_compile_error().into();
+    }
+
+    quote! {
+        unsafe { zink::asm::asm(#input) }
+    }
+    .into()
+}
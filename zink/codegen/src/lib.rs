@@ -8,6 +8,7 @@ use proc_macro2::Span;
 use quote::ToTokens;
 use syn::{parse_macro_input, Attribute, DeriveInput, Expr, ItemFn, ItemStruct, LitStr};
 
+mod asm;
 mod contract;
 mod event;
 mod revert;
@@ -24,6 +25,21 @@ pub fn revert(input: TokenStream) -> TokenStream {
     revert::parse(input)
 }
 
+/// Splice the hand-written EVM assembly into the contract
+///
+/// ```ignore
+/// zink::asm!("PUSH1 0x2a PUSH0 SSTORE");
+/// ```
+///
+/// The assembly is checked while expanding the macro, see the `asm`
+/// module of `evm-opcodes` for the syntax, and it should leave the
+/// stack as it was.
+#[proc_macro]
+pub fn asm(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as LitStr);
+    asm::parse(input)
+}
+
 /// Check and expression and revert with the input message
 ///
 /// This is similar with the builtin `assert!` in rust, but the revert
@@ -107,8 +123,46 @@ pub fn transient_storage(attr: TokenStream, input: TokenStream) -> TokenStream {
 }
 
 /// Mark the function as an external entry point.
+///
+/// The function rejects ether by default, which could be accepted with
+/// `#[zink::external(payable)]`, `view` and `pure` are recorded in the
+/// ABI as well.
+#[proc_macro_attribute]
+pub fn external(args: TokenStream, input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as ItemFn);
+    selector::external(args, input)
+}
+
+/// Mark the function as the fallback entry point, which is called
+/// if no selector matches the calldata.
+///
+/// The contract reverts if no selector matches without it, and it
+/// accepts ether with `#[zink::fallback(payable)]`.
+#[proc_macro_attribute]
+pub fn fallback(args: TokenStream, input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as ItemFn);
+    selector::fallback(args, input)
+}
+
+/// Mark the function as the constructor, which is executed once on
+/// deployment with the ABI-encoded arguments appended to the creation
+/// bytecode.
+///
+/// The constructor rejects ether by default, which could be accepted
+/// with `#[zink::constructor(payable)]`.
+#[proc_macro_attribute]
+pub fn constructor(args: TokenStream, input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as ItemFn);
+    selector::constructor(args, input)
+}
+
+/// Mark the function as the receive entry point, which is called
+/// with empty calldata, like plain ether transfers, it always accepts
+/// ether.
+///
+/// The fallback entry point is called instead without it.
 #[proc_macro_attribute]
-pub fn external(_args: TokenStream, input: TokenStream) -> TokenStream {
+pub fn receive(_args: TokenStream, input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as ItemFn);
-    selector::external(input)
+    selector::receive(input)
 }
@@ -4,10 +4,89 @@ use proc_macro::TokenStream;
 use proc_macro2::{Ident, Span};
 use quote::quote;
 use syn::{parse_quote, ItemFn};
-use zabi::Abi;
+use zabi::{Abi, StateMutability, Type};
 
 /// Mark the function as external.
-pub fn external(mut item: ItemFn) -> TokenStream {
+pub fn external(args: TokenStream, item: ItemFn) -> TokenStream {
+    match mutability(args) {
+        Ok(mutability) => entry(item, Type::Function, mutability),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// Mark the function as the fallback entry, which is called if no
+/// selector matches.
+pub fn fallback(args: TokenStream, item: ItemFn) -> TokenStream {
+    payable_entry(args, item, Type::Fallback)
+}
+
+/// Mark the function as the constructor, which is compiled into the
+/// init code and called with the arguments appended to the creation
+/// bytecode.
+pub fn constructor(args: TokenStream, item: ItemFn) -> TokenStream {
+    payable_entry(args, item, Type::Constructor)
+}
+
+/// Mark the function as the receive entry, which is called with
+/// empty calldata, it is always payable.
+pub fn receive(item: ItemFn) -> TokenStream {
+    entry(item, Type::Receive, StateMutability::Payable)
+}
+
+/// Mark the function as an entry which could only be `nonpayable`
+/// or `payable`.
+fn payable_entry(args: TokenStream, item: ItemFn, ty: Type) -> TokenStream {
+    match mutability(args) {
+        Ok(mutability @ (StateMutability::NonPayable | StateMutability::Payable)) => {
+            entry(item, ty, mutability)
+        }
+        Ok(mutability) => {
+            syn::Error::new_spanned(&item.sig, format!("{ty} function can not be {mutability}"))
+                .to_compile_error()
+                .into()
+        }
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// Parse the state mutability from the arguments of the attribute,
+/// which is `nonpayable` without arguments.
+fn mutability(args: TokenStream) -> syn::Result<StateMutability> {
+    if args.is_empty() {
+        return Ok(StateMutability::default());
+    }
+
+    let ident: Ident = syn::parse(args)?;
+    ident.to_string().parse().map_err(|_| {
+        syn::Error::new_spanned(
+            &ident,
+            "expected one of `payable`, `nonpayable`, `view` or `pure`",
+        )
+    })
+}
+
+/// Mark the function as an entry of the dispatcher with the ABI type.
+fn entry(mut item: ItemFn, ty: Type, mutability: StateMutability) -> TokenStream {
+    let mut abi = Abi::from(&item.sig);
+    abi.state_mutability = mutability;
+    match ty {
+        Type::Function => {}
+        Type::Constructor if !abi.outputs.is_empty() => {
+            return syn::Error::new_spanned(&item.sig, "constructor function can not have outputs")
+                .to_compile_error()
+                .into();
+        }
+        Type::Fallback | Type::Receive if !abi.inputs.is_empty() || !abi.outputs.is_empty() => {
+            return syn::Error::new_spanned(
+                &item.sig,
+                format!("{ty} function can not have inputs or outputs"),
+            )
+            .to_compile_error()
+            .into();
+        }
+        _ => abi.ty = ty,
+    }
+
     item.sig.abi = Some(parse_quote! { extern "C" });
     item.attrs.push(parse_quote! { #[no_mangle] });
     item.attrs
@@ -16,7 +95,7 @@ pub fn external(mut item: ItemFn) -> TokenStream {
     let selector: ItemFn = {
         let func = item.sig.ident.clone().to_string();
         let ident = Ident::new(&(func.clone() + "_selector"), Span::call_site());
-        let abi = Abi::from(&item.sig).to_hex().expect("ABI is not supported");
+        let abi = abi.to_hex().expect("ABI is not supported");
         let abi_len = abi.len() as u32;
         let doc = " EVM selector for the function `".to_string() + &func + "`";
 
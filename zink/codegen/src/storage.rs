@@ -1,5 +1,5 @@
 use crate::utils::Bytes32;
-use heck::AsSnakeCase;
+use heck::{AsShoutySnakeCase, AsSnakeCase};
 use proc_macro::TokenStream;
 use proc_macro2::{Literal, Span, TokenTree};
 use quote::quote;
@@ -95,6 +95,7 @@ impl Storage {
         let key = slot.to_bytes32();
 
         let keyl = Literal::byte_string(&key);
+        let layout = self.layout(slot);
         let trait_path = match self.kind {
             StorageKind::Persistent => quote!(zink::storage::Storage),
             StorageKind::Transient => quote!(zink::storage::TransientStorage),
@@ -110,6 +111,8 @@ impl Storage {
 
                 type Value = #value;
             }
+
+            #layout
         };
 
         if let Some(getter) = self.getter() {
@@ -130,6 +133,7 @@ impl Storage {
         let is = &self.target;
         let name = self.target.ident.clone();
         let slot = self.get_storage_slot(name.to_string());
+        let layout = self.layout(slot);
 
         let trait_path = match self.kind {
             StorageKind::Persistent => quote!(zink::storage::Mapping),
@@ -155,6 +159,8 @@ impl Storage {
                     zink::keccak256(&seed)
                 }
             }
+
+            #layout
         };
 
         if let Some(getter) = self.getter() {
@@ -175,6 +181,7 @@ impl Storage {
         let is = &self.target;
         let name = self.target.ident.clone();
         let slot = self.get_storage_slot(name.to_string());
+        let layout = self.layout(slot);
 
         let trait_path = match self.kind {
             StorageKind::Persistent => quote!(zink::storage::DoubleKeyMapping),
@@ -204,6 +211,8 @@ impl Storage {
                     zink::keccak256(&seed)
                 }
             }
+
+            #layout
         };
 
         if let Some(getter) = self.getter() {
@@ -239,6 +248,41 @@ impl Storage {
         }
     }
 
+    /// Record the slot and the types of the persistent storage in the
+    /// custom section `zink.storage`, which is read back by the compiler
+    /// for the storage layout.
+    fn layout(&self, slot: i32) -> proc_macro2::TokenStream {
+        let types = match &self.ty {
+            _ if self.kind == StorageKind::Transient => return Default::default(),
+            StorageType::Value(value) => vec![value],
+            StorageType::Mapping { key, value } => vec![key, value],
+            StorageType::DoubleKeyMapping { key1, key2, value } => vec![key1, key2, value],
+            StorageType::Invalid => panic!("Invalid storage type"),
+        };
+
+        let name = &self.target.ident;
+        let types = types
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("=>");
+        let item = format!("{name}:{slot}:{types}\n");
+        let bytes = Literal::byte_string(item.as_bytes());
+        let len = item.len();
+        let ident = Ident::new(
+            &format!("__ZINK_STORAGE_{}", AsShoutySnakeCase(name.to_string())),
+            Span::call_site(),
+        );
+
+        quote! {
+            #[cfg(target_arch = "wasm32")]
+            #[doc(hidden)]
+            #[used]
+            #[link_section = "zink.storage"]
+            static #ident: [u8; #len] = *#bytes;
+        }
+    }
+
     /// Get the getter of this storage
     fn getter(&mut self) -> Option<Ident> {
         let mut getter = if matches!(self.target.vis, Visibility::Public(_)) {
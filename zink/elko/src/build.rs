@@ -4,7 +4,7 @@ use anyhow::{anyhow, Result};
 use ccli::clap::{self, Parser};
 use etc::{Etc, FileSystem};
 use std::{env, fs, path::PathBuf};
-use zinkc::{Compiler, Config};
+use zinkc::{cli::load_storage, Compiler, Config};
 
 /// Build zink project to EVM bytecode.
 #[derive(Debug, Parser)]
@@ -18,6 +18,12 @@ pub struct Build {
     /// Write output to compiler-chosen filename in <dir>
     #[clap(long, value_name = "dir")]
     pub out_dir: Option<PathBuf>,
+    /// Write the creation bytecode to disk.
+    #[clap(long)]
+    pub creation: bool,
+    /// Preset the storage in the creation bytecode from a TOML or JSON file.
+    #[clap(long, value_name = "FILE")]
+    pub storage: Option<PathBuf>,
     /// Compiler configuration
     #[clap(flatten)]
     pub config: Config,
@@ -55,6 +61,7 @@ impl Build {
                 builder.with_output(output);
             }
 
+            builder.with_opt_level(self.config.opt_level);
             builder.build()?;
         }
 
@@ -71,11 +78,20 @@ impl Build {
 
         // Compile the wasm to evm bytecode.
         let wasm = fs::read(&wasm_path)?;
-        let config = Config::default().dispatcher(self.config.dispatcher);
-        let artifact = Compiler::new(config).compile(&wasm)?;
-        let dst = wasm_path.with_extension("bin");
+        let mut compiler = Compiler::new(self.config.clone());
+        if let Some(storage) = self.storage.as_ref() {
+            compiler = compiler.storage(load_storage(storage)?);
+        }
+
+        let artifact = compiler.compile(&wasm)?;
+        if self.creation {
+            fs::write(
+                wasm_path.with_extension("creation.bin"),
+                &artifact.creation_bytecode,
+            )?;
+        }
 
-        fs::write(dst, artifact.runtime_bytecode)?;
+        fs::write(wasm_path.with_extension("bin"), artifact.runtime_bytecode)?;
         Ok(())
     }
 }
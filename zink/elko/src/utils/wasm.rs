@@ -5,6 +5,7 @@ use anyhow::anyhow;
 use cargo_metadata::{Metadata, MetadataCommand, Package};
 use etc::{Etc, FileSystem};
 use std::{fs, path::PathBuf, process::Command};
+use zinkc::OptLevel;
 
 /// WASM Builder
 pub struct WasmBuilder {
@@ -12,6 +13,7 @@ pub struct WasmBuilder {
     package: Package,
     output: Option<PathBuf>,
     out_dir: Option<PathBuf>,
+    opt_level: OptLevel,
 }
 
 impl WasmBuilder {
@@ -42,6 +44,7 @@ impl WasmBuilder {
             package,
             output: None,
             out_dir: None,
+            opt_level: OptLevel::default(),
         })
     }
 
@@ -86,6 +89,12 @@ impl WasmBuilder {
         self
     }
 
+    /// Set the optimization level of wasm-opt.
+    pub fn with_opt_level(&mut self, opt_level: OptLevel) -> &mut Self {
+        self.opt_level = opt_level;
+        self
+    }
+
     /// Run the WASM Builder.
     pub fn build(&self) -> Result<()> {
         self.compile()?;
@@ -119,7 +128,7 @@ impl WasmBuilder {
             .with_extension("wasm");
 
         // run the wasm optimizer
-        zinkc::utils::wasm_opt(src, self.output()?)?;
+        zinkc::utils::wasm_opt(src, self.output()?, self.opt_level)?;
 
         Ok(())
     }
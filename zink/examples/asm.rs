@@ -0,0 +1,41 @@
+//! Inline assembly example.
+#![cfg_attr(target_arch = "wasm32", no_std)]
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+extern crate zink;
+
+/// Store 42 at the slot 0 if the flag is set, otherwise 7.
+#[zink::external]
+pub fn set(flag: i32) {
+    if flag != 0 {
+        zink::asm!("PUSH1 0x2a PUSH0 SSTORE");
+    } else {
+        zink::asm!(
+            "
+            PUSH1 0x07  ; value
+            PUSH0       ; key
+            JUMP @store
+            INVALID
+            store:
+            SSTORE
+            "
+        );
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() {}
+
+#[test]
+fn test_asm() -> anyhow::Result<()> {
+    use zint::{Bytes32, Contract, U256};
+
+    let mut contract = Contract::search("asm")?.compile()?;
+    for (flag, value) in [(1, 42), (0, 7)] {
+        let info = contract.execute(&[b"set(int32)".to_vec(), flag.to_bytes32().to_vec()])?;
+        assert!(info.ret.is_empty());
+        assert_eq!(info.storage.get(&U256::from(0)), Some(&U256::from(value)));
+    }
+
+    Ok(())
+}
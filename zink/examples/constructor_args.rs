@@ -0,0 +1,36 @@
+//! Constructor with arguments example.
+#![cfg_attr(target_arch = "wasm32", no_std)]
+#![cfg_attr(target_arch = "wasm32", no_main)]
+
+extern crate zink;
+
+use zink::Storage;
+
+/// Counter with value type `i32`
+#[zink::storage(i32)]
+pub struct Counter;
+
+/// Set the initial value of the counter on deployment.
+#[zink::constructor]
+pub fn constructor(value: i32) {
+    Counter::set(value);
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() {}
+
+#[test]
+fn init_counter() -> anyhow::Result<()> {
+    use zint::{Bytes32, Contract};
+
+    let mut contract = Contract::search("constructor_args")?.compile()?;
+    let value = 42;
+
+    let mut evm = contract.deploy_with([value])?;
+    let info = evm
+        .calldata(&contract.encode(&["counter()"])?)
+        .call(contract.address)?;
+
+    assert_eq!(info.ret, value.to_bytes32());
+    Ok(())
+}
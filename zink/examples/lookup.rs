@@ -0,0 +1,29 @@
+//! Constant array example.
+#![cfg_attr(target_arch = "wasm32", no_std)]
+#![cfg_attr(target_arch = "wasm32", no_main)]
+extern crate zink;
+
+/// The first primes, placed in the data section.
+static PRIMES: [u64; 8] = [2, 3, 5, 7, 11, 13, 17, 19];
+
+/// Returns the prime at the index, wrapped to the count of the primes.
+#[zink::external]
+pub fn prime(index: u64) -> u64 {
+    PRIMES[(index % 8) as usize]
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() {}
+
+#[test]
+fn test() -> anyhow::Result<()> {
+    use zint::{Bytes32, Contract};
+    let mut contract = Contract::search("lookup")?.compile()?;
+
+    for (index, prime) in [2u64, 3, 5, 7, 11, 13, 17, 19].into_iter().enumerate() {
+        let info = contract.execute(["prime(uint64)".as_bytes(), &(index as u64).to_bytes32()])?;
+        assert_eq!(info.ret, prime.to_bytes32());
+    }
+
+    Ok(())
+}
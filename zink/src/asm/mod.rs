@@ -10,6 +10,9 @@ extern "C" {
     /// Emit ABI to host state.
     pub fn emit_abi(ptr: u32, len: u32);
 
+    /// Splice the EVM assembly into the code.
+    pub fn asm(source: &'static str);
+
     /// Set up a label for reserving 32 bytes in memory
     pub fn label_reserve_mem_32();
 
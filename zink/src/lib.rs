@@ -9,7 +9,10 @@ pub mod primitives;
 pub mod storage;
 pub use self::{event::Event, num::Numeric, storage::Value};
 pub use storage::{DoubleKeyMapping, Mapping, Storage, TransientStorage};
-pub use zink_codegen::{assert, external, revert, storage, transient_storage, Event, Storage};
+pub use zink_codegen::{
+    asm, assert, constructor, external, fallback, receive, revert, storage, transient_storage,
+    Event, Storage,
+};
 
 #[cfg(feature = "abi-import")]
 pub use zabi_codegen::import;
@@ -11,25 +11,20 @@ pub trait Bytes32: Sized {
     }
 }
 
-/// Implement Bytes32 for types.
+/// Implement Bytes32 for integers, the signed ones are sign-extended
+/// as what they are in the ABI encoding.
 macro_rules! impl_bytes32 {
     ($($ty:ident),+) => {
         $(
             impl Bytes32 for $ty {
                 fn to_bytes32(&self) -> [u8; 32] {
-                    let mut bytes = [0u8; 32];
-                    let ls_bytes = {
-                        self.to_le_bytes()
-                            .into_iter()
-                            .rev()
-                            .skip_while(|b| *b == 0)
-                            .collect::<Vec<_>>()
-                            .into_iter()
-                            .rev()
-                            .collect::<Vec<_>>()
+                    let mut bytes = if $ty::MIN != 0 && self.leading_ones() > 0 {
+                        [0xff; 32]
+                    } else {
+                        [0; 32]
                     };
-
-                    bytes[(32 - ls_bytes.len())..].copy_from_slice(&ls_bytes);
+                    let be_bytes = self.to_be_bytes();
+                    bytes[(32 - be_bytes.len())..].copy_from_slice(&be_bytes);
                     bytes
                 }
 
@@ -4,7 +4,7 @@ use anyhow::{anyhow, Context, Result};
 use serde::Deserialize;
 use std::fs;
 use std::path::PathBuf;
-use zinkc::{Artifact, Compiler, Config, Constructor, InitStorage};
+use zinkc::{Artifact, Compiler, Config, InitStorage, JsonArtifact, OptLevel};
 
 /// Represents the bytecode object in Foundry output
 #[derive(Deserialize)]
@@ -30,12 +30,18 @@ impl FoundryOutput {
 pub struct Contract {
     /// If enable dispatcher.
     pub dispatcher: bool,
+    /// If skip validating the calldata.
+    pub unchecked_calldata: bool,
+    /// If enable the soft-float runtime.
+    pub soft_float: bool,
+    /// Optimization level of the compiler.
+    pub opt_level: OptLevel,
     /// The artifact of the contract.
     pub artifact: Artifact,
     /// The source WASM of the contract.
     pub wasm: Vec<u8>,
-    /// Bytecode constructor
-    pub constructor: Constructor,
+    /// Initial storage preset in the creation bytecode.
+    pub storage: InitStorage,
     /// Address in evm
     pub address: [u8; 20],
 }
@@ -117,27 +123,33 @@ impl Contract {
 
     /// Get the bytecode of the contract.
     pub fn bytecode(&self) -> Result<Vec<u8>> {
-        let bytecode = self
-            .constructor
-            .finish(self.artifact.runtime_bytecode.clone().into())
-            .map(|v| v.to_vec())?;
+        let bytecode = self.artifact.creation_bytecode.clone();
 
-        tracing::debug!("runtime bytecode: {}", hex::encode(&bytecode));
+        tracing::debug!("creation bytecode: {}", hex::encode(&bytecode));
         Ok(bytecode)
     }
 
     /// Preset the storage of the contract, similar with the concept `constructor`
     /// in solidity, but just in time.
     pub fn construct(&mut self, storage: InitStorage) -> Result<&mut Self> {
-        self.constructor.storage(storage)?;
+        self.storage.extend(storage);
+        self.artifact = self.compiler().compile(&self.wasm)?;
         Ok(self)
     }
 
+    /// The compiler of the contract.
+    fn compiler(&self) -> Compiler {
+        let config = Config::default()
+            .dispatcher(self.dispatcher)
+            .unchecked_calldata(self.unchecked_calldata)
+            .soft_float(self.soft_float)
+            .opt_level(self.opt_level);
+        Compiler::new(config).storage(self.storage.clone())
+    }
+
     /// Compile WASM to EVM bytecode.
     pub fn compile(mut self) -> Result<Self> {
-        let config = Config::default().dispatcher(self.dispatcher);
-        let compiler = Compiler::new(config);
-        self.artifact = compiler.compile(&self.wasm)?;
+        self.artifact = self.compiler().compile(&self.wasm)?;
 
         // tracing::debug!("abi: {:#}", self.json_abi()?);
         tracing::debug!("bytecode: {}", hex::encode(&self.artifact.runtime_bytecode));
@@ -146,8 +158,22 @@ impl Contract {
 
     /// Deploy self to evm
     pub fn deploy<'e>(&mut self) -> Result<EVM<'e>> {
+        self.deploy_with::<()>([])
+    }
+
+    /// Deploy self to evm with the arguments of the constructor, which
+    /// are ABI-encoded and appended to the creation bytecode.
+    pub fn deploy_with<'e, Param>(&mut self, args: impl AsRef<[Param]>) -> Result<EVM<'e>>
+    where
+        Param: Bytes32,
+    {
+        let mut bytecode = self.bytecode()?;
+        for arg in args.as_ref() {
+            bytecode.extend_from_slice(&arg.to_bytes32());
+        }
+
         let mut evm = EVM::default();
-        let info = evm.deploy(&self.bytecode()?)?;
+        let info = evm.deploy(&bytecode)?;
 
         self.address.copy_from_slice(&info.address);
         Ok(evm)
@@ -186,12 +212,19 @@ impl Contract {
         Ok(calldata)
     }
 
-    /// Execute the contract.
+    /// Execute the contract, the `REVERT` is traced back to its source
+    /// location if the transaction is reverted.
     pub fn execute<Param>(&mut self, inputs: impl AsRef<[Param]>) -> Result<Info>
     where
         Param: Bytes32,
     {
-        EVM::interp(&self.artifact.runtime_bytecode, &self.encode(inputs)?)
+        let mut info = EVM::interp(&self.artifact.runtime_bytecode, &self.encode(inputs)?)?;
+        if let Some(pc) = info.revert_pc {
+            info.revert_source = self.artifact.source(pc as u16).cloned();
+            tracing::debug!("reverted at 0x{pc:x}: {:?}", info.revert_source);
+        }
+
+        Ok(info)
     }
 
     /// Get the JSON ABI of the contract.
@@ -199,19 +232,46 @@ impl Contract {
         serde_json::to_string_pretty(&self.artifact.abi).map_err(Into::into)
     }
 
+    /// Get the Solidity-compatible JSON artifact of the contract,
+    /// the creation bytecode includes the storage preset by
+    /// [`Contract::construct`].
+    pub fn json_artifact(&self) -> Result<String> {
+        let mut json = JsonArtifact::from(&self.artifact);
+        json.bytecode = self.bytecode()?.as_slice().into();
+        serde_json::to_string_pretty(&json).map_err(Into::into)
+    }
+
     /// Disable dispatcher.
     pub fn pure(mut self) -> Self {
         self.dispatcher = false;
         self
     }
 
+    /// Skip validating the calldata.
+    pub fn unchecked_calldata(mut self) -> Self {
+        self.unchecked_calldata = true;
+        self
+    }
+
+    /// Enable the soft-float runtime.
+    pub fn soft_float(mut self) -> Self {
+        self.soft_float = true;
+        self
+    }
+
+    /// With the optimization level of the compiler.
+    pub fn opt_level(mut self, opt_level: OptLevel) -> Self {
+        self.opt_level = opt_level;
+        self
+    }
+
     /// Search for zink contract in the target directory.
     pub fn search(name: &str) -> Result<Self> {
         // TODO(g4tianx): `Contract::search` to fail properly
         // when the contract file isn’t found
         crate::setup_logger();
         let wasm = lookup::wasm(name)?;
-        zinkc::utils::wasm_opt(&wasm, &wasm)?;
+        zinkc::utils::wasm_opt(&wasm, &wasm, Default::default())?;
 
         tracing::debug!("loading contract from {}", wasm.display());
         Ok(Self::from(fs::read(wasm)?))
@@ -3,13 +3,16 @@
 use anyhow::{anyhow, Result};
 use revm::{
     db::EmptyDB,
+    inspector_handle_register,
+    interpreter::{opcode, Interpreter},
     primitives::{
         AccountInfo, Bytecode, Bytes, ExecutionResult, HaltReason, Log, Output, ResultAndState,
         SuccessReason, TransactTo, TxKind, U256,
     },
-    Database, Evm as Revm, InMemoryDB,
+    Database, Evm as Revm, EvmContext, InMemoryDB, Inspector,
 };
 use std::collections::HashMap;
+use zinkc::SourceLocation;
 
 /// Transaction gas limit.
 const GAS_LIMIT: u64 = 1_000_000_000;
@@ -22,7 +25,7 @@ pub const CONTRACT: [u8; 20] = [1; 20];
 
 /// Wrapper of full REVM
 pub struct EVM<'e> {
-    inner: Revm<'e, (), InMemoryDB>,
+    inner: Revm<'e, Tracer, InMemoryDB>,
     /// Caller for the execution
     pub caller: [u8; 20],
     /// If commit changes
@@ -34,7 +37,11 @@ impl<'e> Default for EVM<'e> {
         let mut db = InMemoryDB::default();
         db.insert_account_info(ALICE.into(), AccountInfo::from_balance(U256::MAX));
 
-        let evm = Revm::<'e, (), EmptyDB>::builder().with_db(db).build();
+        let evm = Revm::<'e, (), EmptyDB>::builder()
+            .with_db(db)
+            .with_external_context(Tracer::default())
+            .append_handler_register(inspector_handle_register)
+            .build();
         Self {
             inner: evm,
             caller: [0; 20],
@@ -79,19 +86,34 @@ impl EVM<'_> {
         self.inner.tx_mut().transact_to = to;
         self.inner.tx_mut().caller = self.caller.into();
 
-        if self.commit {
+        let info = if self.commit {
             self.inner.transact_commit()?.try_into()
         } else {
             let result = self.inner.transact().map_err(|e| anyhow!(e))?;
             (result, to).try_into()
-        }
+        };
+
+        self.traced(info)
     }
 
     /// Interpret runtime bytecode with provided arguments
     pub fn deploy(&mut self, bytecode: &[u8]) -> Result<Info> {
         self.calldata(bytecode);
         self.inner.tx_mut().transact_to = TxKind::Create;
-        self.inner.transact_commit()?.try_into()
+        let info = self.inner.transact_commit()?.try_into();
+        self.traced(info)
+    }
+
+    /// Fill the program counter of the `REVERT` traced in the
+    /// present transaction.
+    fn traced(&mut self, info: Result<Info>) -> Result<Info> {
+        let revert_pc = self.inner.context.external.revert_pc.take();
+        let mut info = info?;
+        if info.revert.is_some() {
+            info.revert_pc = revert_pc;
+        }
+
+        Ok(info)
     }
 
     /// Fill the calldata of the present transaction.
@@ -100,6 +122,12 @@ impl EVM<'_> {
         self
     }
 
+    /// Set the value in wei transferred with the present transaction.
+    pub fn value(&mut self, value: U256) -> &mut Self {
+        self.inner.tx_mut().value = value;
+        self
+    }
+
     /// Override the present contract
     pub fn contract(mut self, runtime_bytecode: &[u8]) -> Self {
         self.db().insert_account_info(
@@ -137,6 +165,25 @@ pub struct Info {
     pub halt: Option<HaltReason>,
     /// The revert message.
     pub revert: Option<String>,
+    /// Program counter of the `REVERT` if the transaction is reverted.
+    pub revert_pc: Option<usize>,
+    /// Source location of the `REVERT` in the contract, which is
+    /// resolved by [`crate::Contract::execute`].
+    pub revert_source: Option<SourceLocation>,
+}
+
+/// Inspector tracing the program counter of the last executed `REVERT`.
+#[derive(Debug, Default)]
+struct Tracer {
+    revert_pc: Option<usize>,
+}
+
+impl<DB: Database> Inspector<DB> for Tracer {
+    fn step(&mut self, interp: &mut Interpreter, _context: &mut EvmContext<DB>) {
+        if interp.current_opcode() == opcode::REVERT {
+            self.revert_pc = Some(interp.program_counter());
+        }
+    }
 }
 
 impl TryFrom<ExecutionResult> for Info {
@@ -11,8 +11,8 @@ pub mod utils;
 
 pub use self::{
     bytes::Bytes32,
-    contract::Contract,
-    evm::{Info, EVM},
+    contract::{Contract, FoundryOutput},
+    evm::{Info, CONTRACT, EVM},
 };
 
 pub use hex;